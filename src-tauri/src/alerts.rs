@@ -0,0 +1,243 @@
+use std::collections::VecDeque;
+use std::net::Ipv4Addr;
+use serde::{Serialize, Deserialize};
+use crate::dhcp_capture::{DHCPMessageType, DHCPPacket, MacAddress};
+use crate::DHCPLog;
+
+pub const ALERT_EVENT: &str = "dhcp://alert";
+// Un serveur pirate répond à chaque Discover: borner l'historique conservé
+const MAX_ALERTS: usize = 5000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    RogueServer,
+    Starvation,
+    AddressConflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub id: u64,
+    pub kind: AlertKind,
+    pub timestamp: String,
+    pub message: String,
+    pub packet: DHCPLog,
+}
+
+// Anomalie détectée sur un paquet, avant mise en forme pour le frontend
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub kind: AlertKind,
+    pub message: String,
+}
+
+#[derive(Default)]
+pub struct AlertStore {
+    alerts: VecDeque<Alert>,
+    next_id: u64,
+}
+
+impl AlertStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, finding: Finding, packet: DHCPLog) -> Alert {
+        self.next_id += 1;
+        let alert = Alert {
+            id: self.next_id,
+            kind: finding.kind,
+            timestamp: packet.timestamp.clone(),
+            message: finding.message,
+            packet,
+        };
+        if self.alerts.len() >= MAX_ALERTS {
+            self.alerts.pop_front();
+        }
+        self.alerts.push_back(alert.clone());
+        alert
+    }
+
+    pub fn clear(&mut self) {
+        self.alerts.clear();
+    }
+
+    // Recharge les alertes d'une session enregistrée; les suivantes reprennent après le dernier id
+    pub fn restore(&mut self, alerts: Vec<Alert>) {
+        self.next_id = self.next_id.max(alerts.iter().map(|alert| alert.id).max().unwrap_or_default());
+        let excess = alerts.len().saturating_sub(MAX_ALERTS);
+        self.alerts = alerts.into_iter().skip(excess).collect();
+    }
+
+    pub fn snapshot(&self) -> Vec<Alert> {
+        self.alerts.iter().cloned().collect()
+    }
+}
+
+// Serveurs DHCP autorisés; une liste vide désactive la vérification correspondante
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerAllowlist {
+    #[serde(default)]
+    pub ips: Vec<Ipv4Addr>,
+    #[serde(default)]
+    pub macs: Vec<MacAddress>,
+}
+
+impl ServerAllowlist {
+    pub fn is_empty(&self) -> bool {
+        self.ips.is_empty() && self.macs.is_empty()
+    }
+
+    pub fn check(&self, packet: &DHCPPacket) -> Option<Finding> {
+        if !matches!(packet.message_type, DHCPMessageType::Offer | DHCPMessageType::Ack) {
+            return None;
+        }
+
+        let server = packet.server_address();
+        let mut reasons = Vec::new();
+        if !self.ips.is_empty() && !self.ips.contains(&server) {
+            reasons.push(format!("serveur {} non autorisé", server));
+        }
+
+        // Une réponse relayée porte la MAC du relais, pas celle du serveur
+        let relayed = packet.bootp.giaddr != Ipv4Addr::UNSPECIFIED;
+        if !self.macs.is_empty() && !relayed {
+            if let Some(mac) = packet.source_mac.filter(|mac| !self.macs.contains(mac)) {
+                reasons.push(format!("MAC source {} non autorisée", mac));
+            }
+        }
+
+        if reasons.is_empty() {
+            return None;
+        }
+
+        Some(Finding {
+            kind: AlertKind::RogueServer,
+            message: format!(
+                "{:?} suspect pour le client {}: {}",
+                packet.message_type,
+                packet.bootp.chaddr,
+                reasons.join(", ")
+            ),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dhcp_capture::test_support::{mac, packet, SERVER_IP, SERVER_MAC};
+    use crate::dhcp_options::DhcpOption;
+
+    const ROGUE_IP: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 66);
+    const ROGUE_MAC: MacAddress = MacAddress([0x00, 0xde, 0xad, 0xbe, 0xef, 0x01]);
+
+    fn allowlist() -> ServerAllowlist {
+        ServerAllowlist { ips: vec![SERVER_IP], macs: vec![SERVER_MAC] }
+    }
+
+    // Réponse d'un autre serveur, identifiée par l'option 54 et la MAC source
+    fn rogue(message_type: DHCPMessageType) -> DHCPPacket {
+        let mut packet = packet(message_type, mac(1), 1, 0);
+        packet.source_mac = Some(ROGUE_MAC);
+        packet.source_ip = ROGUE_IP;
+        packet.options.retain(|option| !matches!(option, DhcpOption::ServerIdentifier(_)));
+        packet.options.push(DhcpOption::ServerIdentifier(ROGUE_IP));
+        packet
+    }
+
+    fn finding() -> Finding {
+        Finding { kind: AlertKind::RogueServer, message: "test".to_string() }
+    }
+
+    fn log(id: u64) -> DHCPLog {
+        DHCPLog { id, timestamp: format!("t{}", id), ..Default::default() }
+    }
+
+    #[test]
+    fn authorized_server_raises_nothing() {
+        let allowlist = allowlist();
+        assert_eq!(allowlist.check(&packet(DHCPMessageType::Offer, mac(1), 1, 0)), None);
+        assert_eq!(allowlist.check(&packet(DHCPMessageType::Ack, mac(1), 1, 0)), None);
+    }
+
+    #[test]
+    fn foreign_offer_and_ack_are_flagged() {
+        let allowlist = allowlist();
+        for message_type in [DHCPMessageType::Offer, DHCPMessageType::Ack] {
+            let finding = allowlist.check(&rogue(message_type)).unwrap();
+            assert_eq!(finding.kind, AlertKind::RogueServer);
+            assert!(finding.message.contains("serveur 192.168.1.66 non autorisé"), "{}", finding.message);
+            assert!(finding.message.contains(&format!("MAC source {} non autorisée", ROGUE_MAC)), "{}", finding.message);
+            assert!(finding.message.contains(&mac(1).to_string()));
+        }
+
+        // Seuls les Offer et Ack sont vérifiés
+        assert_eq!(allowlist.check(&rogue(DHCPMessageType::Nack)), None);
+        assert_eq!(allowlist.check(&packet(DHCPMessageType::Discover, mac(1), 1, 0)), None);
+    }
+
+    #[test]
+    fn empty_lists_disable_their_check() {
+        let ips_only = ServerAllowlist { ips: vec![SERVER_IP], macs: Vec::new() };
+        let mut packet = packet(DHCPMessageType::Offer, mac(1), 1, 0);
+        packet.source_mac = Some(ROGUE_MAC);
+        assert_eq!(ips_only.check(&packet), None);
+
+        let macs_only = ServerAllowlist { ips: Vec::new(), macs: vec![SERVER_MAC] };
+        let finding = macs_only.check(&rogue(DHCPMessageType::Offer)).unwrap();
+        assert!(!finding.message.contains("serveur"), "{}", finding.message);
+
+        assert!(ServerAllowlist::default().is_empty());
+        assert_eq!(ServerAllowlist::default().check(&rogue(DHCPMessageType::Ack)), None);
+    }
+
+    #[test]
+    fn relayed_replies_skip_the_mac_check() {
+        let mut packet = packet(DHCPMessageType::Offer, mac(1), 1, 0);
+        packet.bootp.giaddr = Ipv4Addr::new(10, 0, 0, 1);
+        packet.source_mac = Some(ROGUE_MAC);
+        assert_eq!(allowlist().check(&packet), None);
+
+        // Le serveur, lui, reste vérifié via l'option 54
+        let mut packet = rogue(DHCPMessageType::Offer);
+        packet.bootp.giaddr = Ipv4Addr::new(10, 0, 0, 1);
+        let finding = allowlist().check(&packet).unwrap();
+        assert!(!finding.message.contains("MAC source"), "{}", finding.message);
+    }
+
+    #[test]
+    fn store_keeps_the_most_recent_alerts() {
+        let mut store = AlertStore::new();
+        for id in 1..=(MAX_ALERTS as u64 + 2) {
+            let alert = store.push(finding(), log(id));
+            assert_eq!(alert.id, id);
+            assert_eq!(alert.timestamp, format!("t{}", id));
+        }
+
+        let alerts = store.snapshot();
+        assert_eq!(alerts.len(), MAX_ALERTS);
+        assert_eq!(alerts.first().unwrap().id, 3);
+        assert_eq!(alerts.last().unwrap().id, MAX_ALERTS as u64 + 2);
+    }
+
+    #[test]
+    fn restore_resumes_numbering_after_the_last_id() {
+        let mut store = AlertStore::new();
+        store.push(finding(), log(1));
+
+        let mut saved = AlertStore::new();
+        for id in 1..=3 {
+            saved.push(finding(), log(id));
+        }
+        store.restore(saved.snapshot());
+        assert_eq!(store.snapshot().len(), 3);
+        assert_eq!(store.push(finding(), log(4)).id, 4);
+
+        store.clear();
+        assert!(store.snapshot().is_empty());
+        // Les ids ne repartent pas de zéro après un effacement
+        assert_eq!(store.push(finding(), log(5)).id, 5);
+    }
+}
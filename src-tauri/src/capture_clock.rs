@@ -0,0 +1,70 @@
+use chrono::{DateTime, Utc};
+
+// Heure de référence des délais (attente, expiration): l'horloge murale pendant une capture
+// en direct, l'horodatage du dernier paquet lu pour un fichier, où l'heure actuelle n'a pas de sens
+#[derive(Debug, Default)]
+pub struct CaptureClock {
+    live: bool,
+    latest: Option<DateTime<Utc>>,
+}
+
+impl CaptureClock {
+    pub fn set_live(&mut self, live: bool) {
+        self.live = live;
+    }
+
+    pub fn observe(&mut self, timestamp: DateTime<Utc>) {
+        if !self.latest.is_some_and(|latest| timestamp <= latest) {
+            self.latest = Some(timestamp);
+        }
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        match self.latest {
+            Some(latest) if !self.live => latest,
+            _ => Utc::now(),
+        }
+    }
+
+    // Oublie le dernier paquet; le mode direct appartient à start_capture/stop_capture
+    pub fn reset(&mut self) {
+        self.latest = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn offline_clock_follows_the_newest_packet() {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut clock = CaptureClock::default();
+        clock.observe(start + Duration::seconds(10));
+        // Paquet plus ancien reçu en retard
+        clock.observe(start);
+        assert_eq!(clock.now(), start + Duration::seconds(10));
+
+        clock.set_live(true);
+        assert!(clock.now() > start + Duration::days(365));
+    }
+
+    #[test]
+    fn reset_keeps_the_live_mode() {
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut clock = CaptureClock::default();
+        clock.set_live(true);
+        clock.reset();
+
+        // Effacement des logs en pleine capture: l'horloge murale continue de faire foi
+        clock.observe(start);
+        let before = Utc::now();
+        assert!(clock.now() >= before);
+
+        clock.set_live(false);
+        assert_eq!(clock.now(), start);
+        clock.reset();
+        assert!(clock.now() >= before);
+    }
+}
@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use crate::alerts::{AlertKind, Finding};
+use crate::dhcp_capture::{ArpPacket, DHCPMessageType, DHCPPacket, MacAddress};
+
+// Durée pendant laquelle une annonce ARP reste un indice d'occupation de l'adresse
+const ARP_RETENTION_SECS: i64 = 600;
+const MAX_ARP_CLAIMS: usize = 50_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Squatter {
+    pub mac: String,
+    pub last_seen: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddressConflict {
+    pub ip: String,
+    pub decline_count: u32,
+    pub first_declined: String,
+    pub last_declined: String,
+    pub declining_clients: Vec<String>,
+    pub acked_by: Option<String>,
+    pub ack_to_decline_ms: Option<f64>,
+    pub squatters: Vec<Squatter>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct AckRecord {
+    chaddr: MacAddress,
+    server: Ipv4Addr,
+    timestamp: DateTime<Utc>,
+}
+
+struct ConflictEntry {
+    decline_count: u32,
+    first_declined: DateTime<Utc>,
+    last_declined: DateTime<Utc>,
+    declining_clients: Vec<MacAddress>,
+    preceding_ack: Option<AckRecord>,
+    ack_to_decline: Option<Duration>,
+    squatters: HashMap<MacAddress, DateTime<Utc>>,
+}
+
+// Corrèle les DHCPDECLINE avec l'Ack précédent et les annonces ARP pour trouver l'occupant réel
+#[derive(Default)]
+pub struct ConflictAnalyzer {
+    last_acks: HashMap<Ipv4Addr, AckRecord>,
+    arp_claims: HashMap<Ipv4Addr, HashMap<MacAddress, DateTime<Utc>>>,
+    conflicts: HashMap<Ipv4Addr, ConflictEntry>,
+}
+
+impl ConflictAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, packet: &DHCPPacket) -> Option<Finding> {
+        match packet.message_type {
+            DHCPMessageType::Ack if packet.bootp.yiaddr != Ipv4Addr::UNSPECIFIED => {
+                let ip = packet.bootp.yiaddr;
+                self.last_acks.insert(ip, AckRecord {
+                    chaddr: packet.bootp.chaddr,
+                    server: packet.server_address(),
+                    timestamp: packet.timestamp,
+                });
+                // Les annonces du nouveau titulaire du bail s'expliquent par celui-ci
+                if let Some(claims) = self.arp_claims.get_mut(&ip) {
+                    claims.remove(&packet.bootp.chaddr);
+                }
+                if let Some(entry) = self.conflicts.get_mut(&ip) {
+                    entry.squatters.remove(&packet.bootp.chaddr);
+                }
+                None
+            }
+            DHCPMessageType::Decline => self.record_decline(packet),
+            _ => None,
+        }
+    }
+
+    fn record_decline(&mut self, packet: &DHCPPacket) -> Option<Finding> {
+        // L'adresse refusée est portée par l'option 50 (RFC 2131 §4.4.1)
+        let ip = packet.option_50?;
+        let client = packet.bootp.chaddr;
+        let timestamp = packet.timestamp;
+
+        let preceding_ack = self.last_acks
+            .get(&ip)
+            .copied()
+            .filter(|ack| ack.chaddr == client && ack.timestamp <= timestamp);
+
+        let entry = self.conflicts.entry(ip).or_insert_with(|| ConflictEntry {
+            decline_count: 0,
+            first_declined: timestamp,
+            last_declined: timestamp,
+            declining_clients: Vec::new(),
+            preceding_ack: None,
+            ack_to_decline: None,
+            squatters: HashMap::new(),
+        });
+        entry.decline_count += 1;
+        entry.last_declined = entry.last_declined.max(timestamp);
+        if !entry.declining_clients.contains(&client) {
+            entry.declining_clients.push(client);
+        }
+        if let Some(ack) = preceding_ack {
+            entry.preceding_ack = Some(ack);
+            entry.ack_to_decline = Some(timestamp - ack.timestamp);
+        }
+
+        // Annonces ARP récentes d'une autre machine pour cette adresse
+        let cutoff = timestamp - Duration::seconds(ARP_RETENTION_SECS);
+        let holder = self.last_acks.get(&ip).filter(|ack| ack.chaddr != client).map(|ack| ack.chaddr);
+        if let Some(claims) = self.arp_claims.get(&ip) {
+            for (mac, seen) in claims {
+                if *seen >= cutoff && !entry.declining_clients.contains(mac) && Some(*mac) != holder {
+                    entry.squatters.insert(*mac, *seen);
+                }
+            }
+        }
+
+        let mut message = format!("Conflit d'adresse {}: DECLINE de {}", ip, client);
+        if entry.decline_count > 1 {
+            message.push_str(&format!(" ({} refus au total)", entry.decline_count));
+        }
+        if let Some(ack) = preceding_ack {
+            message.push_str(&format!(", attribuée par {}", ack.server));
+        }
+        if !entry.squatters.is_empty() {
+            let mut squatters: Vec<String> = entry.squatters.keys().map(|mac| mac.to_string()).collect();
+            squatters.sort();
+            message.push_str(&format!(", occupée par {}", squatters.join(", ")));
+        }
+
+        Some(Finding {
+            kind: AlertKind::AddressConflict,
+            message,
+        })
+    }
+
+    pub fn record_arp(&mut self, arp: &ArpPacket) {
+        // Les sondes ARP (RFC 5227) ont une adresse émettrice nulle
+        if arp.sender_ip == Ipv4Addr::UNSPECIFIED {
+            return;
+        }
+
+        if self.arp_claims.len() >= MAX_ARP_CLAIMS && !self.arp_claims.contains_key(&arp.sender_ip) {
+            let cutoff = arp.timestamp - Duration::seconds(ARP_RETENTION_SECS);
+            self.arp_claims.retain(|_, claims| {
+                claims.retain(|_, seen| *seen >= cutoff);
+                !claims.is_empty()
+            });
+        }
+        self.arp_claims
+            .entry(arp.sender_ip)
+            .or_default()
+            .insert(arp.sender_mac, arp.timestamp);
+
+        // Une réponse ARP peu après le DECLINE désigne aussi l'occupant, sauf si elle vient du
+        // client à qui l'adresse a été attribuée depuis
+        let holder = self.last_acks.get(&arp.sender_ip).map(|ack| ack.chaddr);
+        if let Some(entry) = self.conflicts.get_mut(&arp.sender_ip) {
+            let recent = arp.timestamp - entry.last_declined <= Duration::seconds(ARP_RETENTION_SECS);
+            let squatting = !entry.declining_clients.contains(&arp.sender_mac) && holder != Some(arp.sender_mac);
+            if recent && squatting {
+                entry.squatters.insert(arp.sender_mac, arp.timestamp);
+                println!("🔎 {} occupe {} (adresse refusée par DECLINE)", arp.sender_mac, arp.sender_ip);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.last_acks.clear();
+        self.arp_claims.clear();
+        self.conflicts.clear();
+    }
+
+    pub fn snapshot(&self) -> Vec<AddressConflict> {
+        let mut entries: Vec<(&Ipv4Addr, &ConflictEntry)> = self.conflicts.iter().collect();
+        entries.sort_by(|(_, a), (_, b)| {
+            b.decline_count.cmp(&a.decline_count).then_with(|| b.last_declined.cmp(&a.last_declined))
+        });
+
+        entries
+            .into_iter()
+            .map(|(ip, entry)| {
+                let mut squatters: Vec<Squatter> = entry.squatters
+                    .iter()
+                    .map(|(mac, seen)| Squatter {
+                        mac: mac.to_string(),
+                        last_seen: seen.to_rfc3339(),
+                    })
+                    .collect();
+                squatters.sort_by(|a, b| a.mac.cmp(&b.mac));
+
+                AddressConflict {
+                    ip: ip.to_string(),
+                    decline_count: entry.decline_count,
+                    first_declined: entry.first_declined.to_rfc3339(),
+                    last_declined: entry.last_declined.to_rfc3339(),
+                    declining_clients: entry.declining_clients.iter().map(|mac| mac.to_string()).collect(),
+                    acked_by: entry.preceding_ack.map(|ack| ack.server.to_string()),
+                    ack_to_decline_ms: entry.ack_to_decline
+                        .and_then(|delay| delay.num_microseconds())
+                        .map(|us| us as f64 / 1000.0),
+                    squatters,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dhcp_capture::test_support::{at, mac, packet, SERVER_IP};
+
+    const IP: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 50);
+
+    fn ack(chaddr: MacAddress, offset_ms: i64) -> DHCPPacket {
+        let mut ack = packet(DHCPMessageType::Ack, chaddr, 1, offset_ms);
+        ack.bootp.yiaddr = IP;
+        ack
+    }
+
+    fn decline(chaddr: MacAddress, offset_ms: i64) -> DHCPPacket {
+        let mut decline = packet(DHCPMessageType::Decline, chaddr, 2, offset_ms);
+        decline.option_50 = Some(IP);
+        decline
+    }
+
+    fn arp(sender_mac: MacAddress, sender_ip: Ipv4Addr, offset_ms: i64) -> ArpPacket {
+        ArpPacket {
+            timestamp: at(offset_ms),
+            vlan_ids: Vec::new(),
+            operation: 2,
+            sender_mac,
+            sender_ip,
+            target_mac: MacAddress::default(),
+            target_ip: IP,
+        }
+    }
+
+    fn squatters(analyzer: &ConflictAnalyzer) -> Vec<String> {
+        analyzer.snapshot()[0].squatters.iter().map(|squatter| squatter.mac.clone()).collect()
+    }
+
+    #[test]
+    fn correlates_decline_with_the_preceding_ack_and_arp() {
+        let mut analyzer = ConflictAnalyzer::new();
+        analyzer.record(&ack(mac(1), 0));
+        analyzer.record_arp(&arp(mac(9), IP, 500));
+        let finding = analyzer.record(&decline(mac(1), 1_250)).unwrap();
+
+        assert_eq!(finding.kind, AlertKind::AddressConflict);
+        assert!(finding.message.contains(&format!("attribuée par {}", SERVER_IP)));
+        assert!(finding.message.contains(&format!("occupée par {}", mac(9))));
+
+        let conflict = &analyzer.snapshot()[0];
+        assert_eq!(conflict.ip, IP.to_string());
+        assert_eq!(conflict.acked_by, Some(SERVER_IP.to_string()));
+        assert_eq!(conflict.ack_to_decline_ms, Some(1250.0));
+        assert_eq!(conflict.declining_clients, vec![mac(1).to_string()]);
+        assert_eq!(squatters(&analyzer), vec![mac(9).to_string()]);
+    }
+
+    #[test]
+    fn ignores_ack_to_another_client() {
+        let mut analyzer = ConflictAnalyzer::new();
+        analyzer.record(&ack(mac(2), 0));
+        let finding = analyzer.record(&decline(mac(1), 100)).unwrap();
+        assert!(!finding.message.contains("attribuée par"));
+        assert_eq!(analyzer.snapshot()[0].acked_by, None);
+
+        assert!(analyzer.record(&packet(DHCPMessageType::Decline, mac(1), 3, 200)).is_none());
+    }
+
+    #[test]
+    fn arp_after_the_decline_reveals_the_squatter() {
+        let mut analyzer = ConflictAnalyzer::new();
+        analyzer.record(&decline(mac(1), 0));
+        // Le client qui a refusé, une sonde RFC 5227 et une autre adresse ne comptent pas
+        analyzer.record_arp(&arp(mac(1), IP, 100));
+        analyzer.record_arp(&arp(mac(8), Ipv4Addr::UNSPECIFIED, 150));
+        analyzer.record_arp(&arp(mac(8), Ipv4Addr::new(192, 168, 1, 51), 175));
+        assert!(squatters(&analyzer).is_empty());
+
+        analyzer.record_arp(&arp(mac(9), IP, 200));
+        assert_eq!(squatters(&analyzer), vec![mac(9).to_string()]);
+    }
+
+    #[test]
+    fn ages_out_arp_claims() {
+        let retention_ms = ARP_RETENTION_SECS * 1000;
+        let mut analyzer = ConflictAnalyzer::new();
+        analyzer.record_arp(&arp(mac(9), IP, 0));
+        analyzer.record(&decline(mac(1), retention_ms + 1));
+        assert!(squatters(&analyzer).is_empty());
+
+        analyzer.record_arp(&arp(mac(7), IP, 2 * retention_ms + 2));
+        assert!(squatters(&analyzer).is_empty());
+    }
+
+    #[test]
+    fn new_lease_holder_is_not_a_squatter() {
+        let mut analyzer = ConflictAnalyzer::new();
+        analyzer.record(&decline(mac(1), 0));
+        analyzer.record_arp(&arp(mac(9), IP, 100));
+        assert_eq!(squatters(&analyzer), vec![mac(9).to_string()]);
+
+        // Le serveur attribue ensuite l'adresse à l'ancien occupant, puis à un autre client
+        analyzer.record(&ack(mac(9), 5_000));
+        assert!(squatters(&analyzer).is_empty());
+        analyzer.record(&ack(mac(2), 10_000));
+        analyzer.record_arp(&arp(mac(2), IP, 10_100));
+        assert!(squatters(&analyzer).is_empty());
+
+        // Un nouveau refus ne reprend pas le titulaire actuel comme occupant
+        analyzer.record(&decline(mac(3), 11_000));
+        assert!(squatters(&analyzer).is_empty());
+        assert_eq!(analyzer.snapshot()[0].decline_count, 2);
+    }
+}
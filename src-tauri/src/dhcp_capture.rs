@@ -1,37 +1,287 @@
 use std::net::Ipv4Addr;
 use chrono::{DateTime, Utc};
 use thiserror::Error;
-use pcap::{Device, Capture};
+use pcap::{Device, Capture, Linktype, PacketHeader, Precision};
 use std::thread;
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread::JoinHandle;
+use crate::dhcp_options::{assemble_options, format_identifier, DhcpOption, RelaySubOption, OVERLOAD_FILE, OVERLOAD_SNAME};
+use crate::fingerprint::ClientFingerprint;
+use crate::packet_decoder::{decode_arp, decode_frame, DecodeError, ETHERTYPE_ARP};
+use crate::pcapng_recorder::{CaptureRecorder, RecordingOptions};
 
 #[derive(Error, Debug)]
 pub enum DHCPError {
-    // CaptureError variant removed as it's not used
+    #[error("Impossible d'ouvrir le fichier de capture {path}: {source}")]
+    OpenFile { path: String, source: pcap::Error },
+    #[error("Erreur de lecture du fichier de capture: {0}")]
+    ReadFile(pcap::Error),
+    #[error("Filtre BPF invalide: {0}")]
+    Filter(pcap::Error),
 }
 
 #[derive(Debug, Clone)]
 pub struct DHCPPacket {
     pub timestamp: DateTime<Utc>,
     pub message_type: DHCPMessageType,
+    pub source_mac: Option<MacAddress>,
+    pub destination_mac: Option<MacAddress>,
+    pub vlan_ids: Vec<u16>,
     pub source_ip: Ipv4Addr,
     pub destination_ip: Ipv4Addr,
+    pub source_port: u16,
+    pub destination_port: u16,
+    pub bootp: BootpHeader,
+    pub options: Vec<DhcpOption>,
+    pub option_overload: bool,
+    pub options_concatenated: bool,
     pub option_50: Option<Ipv4Addr>,
     pub raw_data: Vec<u8>,
 }
 
+impl DHCPPacket {
+    pub fn server_identifier(&self) -> Option<Ipv4Addr> {
+        self.options.iter().find_map(|option| match option {
+            DhcpOption::ServerIdentifier(ip) => Some(*ip),
+            _ => None,
+        })
+    }
+
+    // Serveur à l'origine d'une réponse: option 54, sinon l'adresse IP source
+    pub fn server_address(&self) -> Ipv4Addr {
+        self.server_identifier().unwrap_or(self.source_ip)
+    }
+
+    pub fn lease_time(&self) -> Option<u32> {
+        self.options.iter().find_map(|option| match option {
+            DhcpOption::LeaseTime(seconds) => Some(*seconds),
+            _ => None,
+        })
+    }
+
+    pub fn renewal_time(&self) -> Option<u32> {
+        self.options.iter().find_map(|option| match option {
+            DhcpOption::RenewalTime(seconds) => Some(*seconds),
+            _ => None,
+        })
+    }
+
+    pub fn rebinding_time(&self) -> Option<u32> {
+        self.options.iter().find_map(|option| match option {
+            DhcpOption::RebindingTime(seconds) => Some(*seconds),
+            _ => None,
+        })
+    }
+
+    pub fn host_name(&self) -> Option<&str> {
+        self.options.iter().find_map(|option| match option {
+            DhcpOption::HostName(name) => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn client_identifier(&self) -> Option<&[u8]> {
+        self.options.iter().find_map(|option| match option {
+            DhcpOption::ClientIdentifier(data) => Some(data.as_slice()),
+            _ => None,
+        })
+    }
+
+    // Empreinte client (options 55, 60, 57), uniquement sur les trames émises par un client
+    pub fn fingerprint(&self) -> Option<ClientFingerprint> {
+        if self.bootp.op != 1 {
+            return None;
+        }
+
+        let mut parameter_request_list = None;
+        let mut vendor_class = None;
+        let mut max_message_size = None;
+        for option in &self.options {
+            match option {
+                DhcpOption::ParameterRequestList(codes) => parameter_request_list = Some(codes.clone()),
+                DhcpOption::VendorClassIdentifier(class) => vendor_class = Some(class.clone()),
+                DhcpOption::MaxMessageSize(size) => max_message_size = Some(*size),
+                _ => {}
+            }
+        }
+
+        Some(ClientFingerprint {
+            parameter_request_list: parameter_request_list?,
+            vendor_class,
+            max_message_size,
+        })
+    }
+
+    pub fn relay_agent_information(&self) -> Option<&[RelaySubOption]> {
+        self.options.iter().find_map(|option| match option {
+            DhcpOption::RelayAgentInformation(sub_options) => Some(sub_options.as_slice()),
+            _ => None,
+        })
+    }
+
+    pub fn circuit_id(&self) -> Option<String> {
+        self.relay_agent_information()?.iter().find_map(|sub_option| match sub_option {
+            RelaySubOption::CircuitId(data) => Some(format_identifier(data)),
+            _ => None,
+        })
+    }
+
+    pub fn subnet_mask(&self) -> Option<Ipv4Addr> {
+        self.options.iter().find_map(|option| match option {
+            DhcpOption::SubnetMask(mask) => Some(*mask),
+            _ => None,
+        })
+    }
+
+    // Option 56: texte explicatif joint par le serveur (typiquement dans un NAK)
+    pub fn server_message(&self) -> Option<&str> {
+        self.options.iter().find_map(|option| match option {
+            DhcpOption::Message(text) => Some(text.as_str()),
+            _ => None,
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
+pub struct ArpPacket {
+    pub timestamp: DateTime<Utc>,
+    pub vlan_ids: Vec<u16>,
+    pub operation: u16,
+    pub sender_mac: MacAddress,
+    pub sender_ip: Ipv4Addr,
+    pub target_mac: MacAddress,
+    pub target_ip: Ipv4Addr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct MacAddress(pub [u8; 6]);
+
+impl std::fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let b = self.0;
+        write!(f, "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}", b[0], b[1], b[2], b[3], b[4], b[5])
+    }
+}
+
+// Accepte les séparateurs ':' et '-' (format Windows)
+impl std::str::FromStr for MacAddress {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().split([':', '-']).collect();
+        if parts.len() != 6 {
+            return Err(format!("Adresse MAC invalide: {}", s));
+        }
+
+        let mut bytes = [0u8; 6];
+        for (byte, part) in bytes.iter_mut().zip(&parts) {
+            *byte = u8::from_str_radix(part, 16).map_err(|_| format!("Adresse MAC invalide: {}", s))?;
+        }
+        Ok(MacAddress(bytes))
+    }
+}
+
+impl serde::Serialize for MacAddress {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MacAddress {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+// En-tête BOOTP fixe (RFC 951 / RFC 2131), 236 octets avant le magic cookie
+pub const BOOTP_HEADER_LEN: usize = 236;
+pub const BOOTP_SNAME_RANGE: std::ops::Range<usize> = 44..108;
+pub const BOOTP_FILE_RANGE: std::ops::Range<usize> = 108..236;
+const BOOTP_FLAG_BROADCAST: u16 = 0x8000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BootpHeader {
+    pub op: u8,
+    pub htype: u8,
+    pub hlen: u8,
+    pub hops: u8,
+    pub xid: u32,
+    pub secs: u16,
+    pub flags: u16,
+    pub ciaddr: Ipv4Addr,
+    pub yiaddr: Ipv4Addr,
+    pub siaddr: Ipv4Addr,
+    pub giaddr: Ipv4Addr,
+    pub chaddr: MacAddress,
+    pub sname: String,
+    pub file: String,
+}
+
+impl BootpHeader {
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < BOOTP_HEADER_LEN {
+            return None;
+        }
+
+        let ip_at = |offset: usize| {
+            Ipv4Addr::new(data[offset], data[offset + 1], data[offset + 2], data[offset + 3])
+        };
+
+        // chaddr fait 16 octets, seuls les 6 premiers forment l'adresse MAC Ethernet
+        let mut chaddr = [0u8; 6];
+        chaddr.copy_from_slice(&data[28..34]);
+
+        Some(Self {
+            op: data[0],
+            htype: data[1],
+            hlen: data[2],
+            hops: data[3],
+            xid: u32::from_be_bytes([data[4], data[5], data[6], data[7]]),
+            secs: u16::from_be_bytes([data[8], data[9]]),
+            flags: u16::from_be_bytes([data[10], data[11]]),
+            ciaddr: ip_at(12),
+            yiaddr: ip_at(16),
+            siaddr: ip_at(20),
+            giaddr: ip_at(24),
+            chaddr: MacAddress(chaddr),
+            sname: Self::null_terminated(&data[BOOTP_SNAME_RANGE]),
+            file: Self::null_terminated(&data[BOOTP_FILE_RANGE]),
+        })
+    }
+
+    pub fn is_broadcast(&self) -> bool {
+        self.flags & BOOTP_FLAG_BROADCAST != 0
+    }
+
+    fn null_terminated(field: &[u8]) -> String {
+        let end = field.iter().position(|&b| b == 0).unwrap_or(field.len());
+        String::from_utf8_lossy(&field[..end]).into_owned()
+    }
+}
+
+// Registre IANA des types de message DHCP (option 53)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum DHCPMessageType {
     Discover,
     Offer,
     Request,
+    Decline,
     Ack,
     Nack,
-    Decline,
     Release,
     Inform,
-    Unknown,
+    ForceRenew,
+    LeaseQuery,
+    LeaseUnassigned,
+    LeaseUnknown,
+    LeaseActive,
+    BulkLeaseQuery,
+    LeaseQueryDone,
+    ActiveLeaseQuery,
+    LeaseQueryStatus,
+    Tls,
+    Unknown(u8),
 }
 
 impl From<u8> for DHCPMessageType {
@@ -40,20 +290,69 @@ impl From<u8> for DHCPMessageType {
             1 => DHCPMessageType::Discover,
             2 => DHCPMessageType::Offer,
             3 => DHCPMessageType::Request,
+            4 => DHCPMessageType::Decline,
             5 => DHCPMessageType::Ack,
             6 => DHCPMessageType::Nack,
-            7 => DHCPMessageType::Decline,
-            8 => DHCPMessageType::Release,
-            9 => DHCPMessageType::Inform,
-            _ => DHCPMessageType::Unknown,
+            7 => DHCPMessageType::Release,
+            8 => DHCPMessageType::Inform,
+            9 => DHCPMessageType::ForceRenew,
+            10 => DHCPMessageType::LeaseQuery,
+            11 => DHCPMessageType::LeaseUnassigned,
+            12 => DHCPMessageType::LeaseUnknown,
+            13 => DHCPMessageType::LeaseActive,
+            14 => DHCPMessageType::BulkLeaseQuery,
+            15 => DHCPMessageType::LeaseQueryDone,
+            16 => DHCPMessageType::ActiveLeaseQuery,
+            17 => DHCPMessageType::LeaseQueryStatus,
+            18 => DHCPMessageType::Tls,
+            other => DHCPMessageType::Unknown(other),
         }
     }
 }
 
+impl From<DHCPMessageType> for u8 {
+    fn from(value: DHCPMessageType) -> Self {
+        match value {
+            DHCPMessageType::Discover => 1,
+            DHCPMessageType::Offer => 2,
+            DHCPMessageType::Request => 3,
+            DHCPMessageType::Decline => 4,
+            DHCPMessageType::Ack => 5,
+            DHCPMessageType::Nack => 6,
+            DHCPMessageType::Release => 7,
+            DHCPMessageType::Inform => 8,
+            DHCPMessageType::ForceRenew => 9,
+            DHCPMessageType::LeaseQuery => 10,
+            DHCPMessageType::LeaseUnassigned => 11,
+            DHCPMessageType::LeaseUnknown => 12,
+            DHCPMessageType::LeaseActive => 13,
+            DHCPMessageType::BulkLeaseQuery => 14,
+            DHCPMessageType::LeaseQueryDone => 15,
+            DHCPMessageType::ActiveLeaseQuery => 16,
+            DHCPMessageType::LeaseQueryStatus => 17,
+            DHCPMessageType::Tls => 18,
+            DHCPMessageType::Unknown(value) => value,
+        }
+    }
+}
+
+type ArpCallback = Box<dyn FnMut(ArpPacket) + Send>;
+
+fn combine_filters(base: &str, user_filter: &str) -> String {
+    format!("({}) and ({})", base, user_filter)
+}
+
+const DHCP_FILTER: &str = "udp and (port 67 or port 68)";
+const DHCP_AND_ARP_FILTER: &str = "(udp and (port 67 or port 68)) or arp";
+
 pub struct DHCPCapture {
     interface_name: String,
     is_capturing: Arc<Mutex<bool>>,
     capture_thread: Option<JoinHandle<()>>,
+    recording: Option<RecordingOptions>,
+    arp_callback: Option<ArpCallback>,
+    // Filtre BPF de l'utilisateur, combiné au filtre DHCP
+    user_filter: Option<String>,
 }
 
 impl DHCPCapture {
@@ -62,9 +361,28 @@ impl DHCPCapture {
             interface_name: interface_name.to_string(),
             is_capturing: Arc::new(Mutex::new(false)),
             capture_thread: None,
+            recording: None,
+            arp_callback: None,
+            user_filter: None,
         })
     }
 
+    pub fn set_recording(&mut self, recording: Option<RecordingOptions>) {
+        self.recording = recording;
+    }
+
+    // Capture aussi l'ARP (filtre BPF élargi) pour l'analyse des conflits d'adresses
+    pub fn set_arp_callback<G>(&mut self, callback: G)
+    where
+        G: FnMut(ArpPacket) + Send + 'static,
+    {
+        self.arp_callback = Some(Box::new(callback));
+    }
+
+    pub fn set_user_filter(&mut self, filter: Option<String>) {
+        self.user_filter = filter;
+    }
+
     pub fn start_capture_with_callback<F>(&mut self, mut callback: F) -> Result<(), DHCPError>
     where
         F: FnMut(DHCPPacket) + Send + 'static,
@@ -79,6 +397,11 @@ impl DHCPCapture {
         let (tx, rx) = mpsc::channel();
         let interface_name = self.interface_name.clone();
         let is_capturing = self.is_capturing.clone();
+        let recording = self.recording.clone();
+        let filter = self.filter();
+        let arp_callback = self.arp_callback.take();
+        let (arp_tx, arp_rx) = mpsc::channel();
+        let arp_tx = arp_callback.as_ref().map(|_| arp_tx);
         
         // Créer un thread séparé pour la capture
         let capture_handle = thread::spawn(move || {
@@ -99,12 +422,15 @@ impl DHCPCapture {
                 
                 match Capture::from_device(device.clone()) {
                     Ok(cap) => {
-                        match cap.open() {
+                        // Horodatage nanoseconde si libpcap / le pilote le permettent
+                        match cap.precision(Precision::Nano).open() {
                             Ok(mut cap) => {
                                 println!("Capture démarrée sur {}", device.name);
+                                let precision = Self::granted_precision(&cap);
+                                println!("⏱️ Précision d'horodatage: {:?}", precision);
                                 
                                 // Filtre DHCP avec gestion d'erreur améliorée
-                                match cap.filter("udp and (port 67 or port 68)", true) {
+                                match cap.filter(&filter, true) {
                                     Ok(_) => println!("✅ Filtre DHCP appliqué avec succès"),
                                     Err(e) => {
                                         println!("⚠️ Erreur filtre DHCP: {}, tentative sans filtre...", e);
@@ -117,6 +443,19 @@ impl DHCPCapture {
                                     }
                                 }
 
+                                let link_type = cap.get_datalink().0;
+                                println!("🔗 Type de lien: {}", link_type);
+
+                                let mut recorder = recording.and_then(|options| {
+                                    match CaptureRecorder::new(options, &device.name, device.desc.as_deref(), link_type) {
+                                        Ok(recorder) => Some(recorder),
+                                        Err(e) => {
+                                            eprintln!("❌ Enregistrement pcapng désactivé: {}", e);
+                                            None
+                                        }
+                                    }
+                                });
+
                                 let mut packet_count = 0;
                                 let start_time = std::time::Instant::now();
                                 
@@ -151,17 +490,42 @@ impl DHCPCapture {
                                             println!("✅ Paquet #{} reçu après {:?}: {} octets", 
                                                      packet_count, elapsed, packet_data.len());
 
-                                            Self::debug_packet_analysis(&packet_data);
-                                            
-                                            if let Some(dhcp_info) = Self::parse_dhcp_packet(&packet_data) {
-                                                println!("🎯 DHCP détecté et parsé avec succès!");
-                                                if let Err(e) = tx.send(dhcp_info) {
-                                                    eprintln!("❌ Erreur envoi paquet: {}", e);
-                                                } else {
-                                                    println!("📤 Paquet envoyé au callback");
+                                            let Some(timestamp) = Self::header_timestamp(packet.header, precision) else {
+                                                println!("⚠️ Paquet #{} ignoré: horodatage invalide", packet_count);
+                                                continue;
+                                            };
+                                            match Self::parse_dhcp_packet(link_type, packet_data, timestamp) {
+                                                Ok(dhcp_info) => {
+                                                    println!("🎯 DHCP détecté et parsé avec succès!");
+                                                    if let Some(ref mut recorder) = recorder {
+                                                        if let Err(e) = recorder.write_packet(dhcp_info.timestamp, packet_data, packet.header.len) {
+                                                            eprintln!("❌ Erreur d'écriture pcapng: {}", e);
+                                                        }
+                                                    }
+                                                    if let Err(e) = tx.send(dhcp_info) {
+                                                        eprintln!("❌ Erreur envoi paquet: {}", e);
+                                                    } else {
+                                                        println!("📤 Paquet envoyé au callback");
+                                                    }
+                                                }
+                                                Err(DecodeError::NotIpv4(ETHERTYPE_ARP)) if arp_tx.is_some() => {
+                                                    match Self::parse_arp_packet(link_type, packet_data, timestamp) {
+                                                        Ok(arp) => {
+                                                            if let Some(ref mut recorder) = recorder {
+                                                                if let Err(e) = recorder.write_packet(arp.timestamp, packet_data, packet.header.len) {
+                                                                    eprintln!("❌ Erreur d'écriture pcapng: {}", e);
+                                                                }
+                                                            }
+                                                            if let Some(ref arp_tx) = arp_tx {
+                                                                let _ = arp_tx.send(arp);
+                                                            }
+                                                        }
+                                                        Err(e) => println!("⚠️ Trame ARP rejetée: {}", e),
+                                                    }
+                                                }
+                                                Err(e) => {
+                                                    println!("⚠️ Paquet reçu mais rejeté par le décodeur: {}", e);
                                                 }
-                                            } else {
-                                                println!("⚠️ Paquet reçu mais pas parsé comme DHCP valide");
                                             }
                                         }
                                         Err(e) => {
@@ -217,10 +581,36 @@ impl DHCPCapture {
                 callback(packet_data.clone());
             }
         });
+
+        if let Some(mut arp_callback) = arp_callback {
+            thread::spawn(move || {
+                for arp in arp_rx {
+                    arp_callback(arp);
+                }
+            });
+        }
         
         Ok(())
     }
 
+    // Filtre BPF appliqué par `start_capture_with_callback` avec la configuration actuelle
+    pub fn filter(&self) -> String {
+        let base = if self.arp_callback.is_some() { DHCP_AND_ARP_FILTER } else { DHCP_FILTER };
+        match self.user_filter {
+            Some(ref user_filter) => combine_filters(base, user_filter),
+            None => base.to_string(),
+        }
+    }
+
+    // Vérifie qu'un filtre utilisateur compile, sans ouvrir d'interface
+    pub fn check_user_filter(filter: &str) -> Result<(), DHCPError> {
+        let capture = Capture::dead(Linktype::ETHERNET).map_err(DHCPError::Filter)?;
+        capture
+            .compile(&combine_filters(DHCP_AND_ARP_FILTER, filter), true)
+            .map(|_| ())
+            .map_err(DHCPError::Filter)
+    }
+
     pub fn stop_capture(&mut self) {
         if let Ok(mut capturing) = self.is_capturing.lock() {
             *capturing = false;
@@ -230,145 +620,411 @@ impl DHCPCapture {
     
 
 
-    fn debug_packet_analysis(packet_data: &[u8]) {
-        if packet_data.len() >= 14 {
-            let eth_type = ((packet_data[12] as u16) << 8) | (packet_data[13] as u16);
-            
-            if eth_type == 0x0800 && packet_data.len() >= 34 {
-                let protocol = packet_data[23];
-                
-                if protocol == 17 && packet_data.len() >= 38 {
-                    let src_port = ((packet_data[34] as u16) << 8) | (packet_data[35] as u16);
-                    let dst_port = ((packet_data[36] as u16) << 8) | (packet_data[37] as u16);
-                    
-                    if (src_port == 67 || src_port == 68) || (dst_port == 67 || dst_port == 68) {
-                        println!("PAQUET DHCP DÉTECTÉ!");
+    // Analyse hors ligne d'un fichier pcap/pcapng: même décodage que la capture live,
+    // sans droits administrateur ni pilote de capture
+    pub fn read_capture_file<F, G>(path: &str, mut callback: F, mut arp_callback: G) -> Result<usize, DHCPError>
+    where
+        F: FnMut(DHCPPacket),
+        G: FnMut(ArpPacket),
+    {
+        println!("📂 Analyse du fichier de capture {}", path);
+
+        // libpcap convertit les horodatages du fichier dans la précision demandée
+        let mut cap = Capture::from_file_with_precision(path, Precision::Nano).map_err(|source| DHCPError::OpenFile {
+            path: path.to_string(),
+            source,
+        })?;
+        let precision = Self::granted_precision(&cap);
+        let link_type = cap.get_datalink().0;
+
+        let mut frame_count = 0;
+        let mut dhcp_count = 0;
+        loop {
+            match cap.next_packet() {
+                Ok(packet) => {
+                    frame_count += 1;
+                    let Some(timestamp) = Self::header_timestamp(packet.header, precision) else {
+                        println!("⚠️ Trame #{} ignorée: horodatage invalide", frame_count);
+                        continue;
+                    };
+                    match Self::parse_dhcp_packet(link_type, packet.data, timestamp) {
+                        Ok(dhcp_packet) => {
+                            dhcp_count += 1;
+                            callback(dhcp_packet);
+                        }
+                        Err(DecodeError::NotIpv4(ETHERTYPE_ARP)) => {
+                            match Self::parse_arp_packet(link_type, packet.data, timestamp) {
+                                Ok(arp) => arp_callback(arp),
+                                Err(e) => println!("⚠️ Trame ARP #{} ignorée: {}", frame_count, e),
+                            }
+                        }
+                        Err(e) => println!("⚠️ Trame #{} ignorée: {}", frame_count, e),
                     }
                 }
+                Err(pcap::Error::NoMorePackets) => break,
+                Err(e) => return Err(DHCPError::ReadFile(e)),
             }
         }
+
+        println!("✅ Fichier analysé: {} trames, {} paquets DHCP", frame_count, dhcp_count);
+        Ok(dhcp_count)
     }
 
-    fn parse_dhcp_packet(packet_data: &[u8]) -> Option<DHCPPacket> {
-        // Vérifier la taille minimale d'un paquet DHCP (plus flexible)
-        if packet_data.len() < 236 { // Reduced from 240 for better compatibility
-            println!("⚠️ Paquet trop court: {} octets (minimum 236)", packet_data.len());
-            return None;
+    // pcap n'expose pas pcap_get_tstamp_precision: une demande Nano refusée par le pilote
+    // laisse silencieusement des microsecondes dans tv_usec
+    fn granted_precision<T: pcap::State + ?Sized>(cap: &Capture<T>) -> Precision {
+        extern "C" {
+            fn pcap_get_tstamp_precision(p: *mut std::ffi::c_void) -> std::os::raw::c_int;
         }
-        
-        // Chercher l'option 53 (DHCP Message Type) dans les options
-        let mut message_type_byte = 0;
-        let mut option_50_ip: Option<Ipv4Addr> = None;
-        
-        // Try different starting positions for DHCP options (more flexible parsing)
-        let possible_starts = [278, 282, 286, 290];
-        let mut options_start = None;
-        
-        // Find the actual start of DHCP options
-        for &start_pos in &possible_starts {
-            if start_pos < packet_data.len() - 3 {
-                let option_code = packet_data[start_pos];
-                if option_code == 53 || option_code == 0 || option_code == 255 {
-                    options_start = Some(start_pos);
-                    println!("🎯 Options DHCP trouvées à l'octet {}", start_pos);
-                    break;
-                }
-            }
+
+        // SAFETY: as_ptr() renvoie le pcap_t possédé par `cap`, ouvert et non libéré tant que
+        // l'emprunt `&Capture` court, donc valide pendant tout l'appel; la fonction ne fait que lire
+        // la précision stockée et ne conserve pas le pointeur
+        match unsafe { pcap_get_tstamp_precision(cap.as_ptr() as *mut std::ffi::c_void) } {
+            1 => Precision::Nano,
+            _ => Precision::Micro,
         }
-        
-        let options_start = options_start?;
-        
-        // Debug: afficher les octets autour du début des options
-        println!("Debug options DHCP (octets {}-{}):", options_start, options_start + 20);
-        for j in options_start..std::cmp::min(options_start + 20, packet_data.len()) {
-            print!("{:02x} ", packet_data[j]);
+    }
+
+    // Les champs de timeval sont des c_long sous Windows et des i64 ailleurs.
+    // None si la fraction dépasse une seconde ou si la date n'est pas représentable
+    #[allow(clippy::unnecessary_cast)]
+    fn header_timestamp(header: &PacketHeader, precision: Precision) -> Option<DateTime<Utc>> {
+        let seconds = header.ts.tv_sec as i64;
+        let fraction = u32::try_from(header.ts.tv_usec as i64).ok()?;
+        let nanos = match precision {
+            Precision::Nano if fraction < 1_000_000_000 => fraction,
+            Precision::Micro if fraction < 1_000_000 => fraction * 1_000,
+            _ => return None,
+        };
+        DateTime::from_timestamp(seconds, nanos)
+    }
+
+    fn parse_arp_packet(link_type: i32, packet_data: &[u8], timestamp: DateTime<Utc>) -> Result<ArpPacket, DecodeError> {
+        let frame = decode_arp(link_type, packet_data)?;
+        Ok(ArpPacket {
+            timestamp,
+            vlan_ids: frame.vlan_ids,
+            operation: frame.operation,
+            sender_mac: frame.sender_mac,
+            sender_ip: frame.sender_ip,
+            target_mac: frame.target_mac,
+            target_ip: frame.target_ip,
+        })
+    }
+
+    fn parse_dhcp_packet(link_type: i32, packet_data: &[u8], timestamp: DateTime<Utc>) -> Result<DHCPPacket, DecodeError> {
+        let frame = decode_frame(link_type, packet_data)?;
+
+        let assembled = assemble_options(frame.options, frame.file_field, frame.sname_field);
+        if assembled.malformed {
+            println!("⚠️ Options DHCP mal formées, décodage arrêté à la première option invalide");
         }
-        println!();
-        
-        // Parcourir les options DHCP
-        let mut i = options_start;
-        while i < packet_data.len() - 1 {
-            let option_code = packet_data[i];
-            let option_length = if i + 1 < packet_data.len() { packet_data[i + 1] } else { 0 };
-            
-            // Option 255 (End) - fin des options
-            if option_code == 255 {
-                println!("🏁 Fin des options DHCP");
-                break;
-            }
-            
-            // Option 0 (Padding) - ignorer
-            if option_code == 0 {
-                i += 1;
-                continue;
-            }
-            
-            // Vérifier que l'option a une longueur valide
-            if option_length == 0 || i + 2 + option_length as usize > packet_data.len() {
-                println!("⚠️ Option invalide à l'octet {}: code={}, longueur={}", i, option_code, option_length);
-                i += 1;
-                continue;
-            }
-            
-            // Option 53 = DHCP Message Type
-            if option_code == 53 && option_length == 1 && i + 2 < packet_data.len() {
-                message_type_byte = packet_data[i + 2];
-                println!("✅ Option 53 (Message Type) trouvée à l'octet {}: {}", i, message_type_byte);
+
+        // Si sname/file portent des options (option 52), ce ne sont plus des chaînes
+        let mut bootp = frame.bootp;
+        if let Some(overload) = assembled.overload {
+            println!("📎 Option overload {} : options lues dans sname/file", overload);
+            if overload & OVERLOAD_FILE != 0 {
+                bootp.file.clear();
             }
-            
-            // Option 50 = Requested IP Address
-            if option_code == 50 && option_length == 4 && i + 6 <= packet_data.len() {
-                let ip_bytes = [
-                    packet_data[i + 2],
-                    packet_data[i + 3],
-                    packet_data[i + 4],
-                    packet_data[i + 5],
-                ];
-                option_50_ip = Some(Ipv4Addr::new(ip_bytes[0], ip_bytes[1], ip_bytes[2], ip_bytes[3]));
-                println!("✅ Option 50 (Requested IP) trouvée: {}", option_50_ip.unwrap());
+            if overload & OVERLOAD_SNAME != 0 {
+                bootp.sname.clear();
             }
-            
-            i += 2 + option_length as usize;
         }
-        
-        // Extraire les adresses IP source et destination
-        let source_ip = if packet_data.len() >= 30 {
-            Ipv4Addr::new(
-                packet_data[26],
-                packet_data[27],
-                packet_data[28],
-                packet_data[29],
-            )
-        } else {
-            Ipv4Addr::new(0, 0, 0, 0)
-        };
-        
-        let destination_ip = if packet_data.len() >= 30 {
-            Ipv4Addr::new(
-                packet_data[30],
-                packet_data[31],
-                packet_data[32],
-                packet_data[33],
-            )
-        } else {
-            Ipv4Addr::new(0, 0, 0, 0)
-        };
+        let options = assembled.options;
+
+        let message_type_byte = options.iter().find_map(|option| match option {
+            DhcpOption::MessageType(value) => Some(*value),
+            _ => None,
+        }).unwrap_or(0);
+
+        let option_50_ip = options.iter().find_map(|option| match option {
+            DhcpOption::RequestedIpAddress(ip) => Some(*ip),
+            _ => None,
+        });
+
+        println!("✅ {} options décodées (type {}, option 50: {:?})", options.len(), message_type_byte, option_50_ip);
         
         // Créer le paquet DHCP
         let dhcp_packet = DHCPPacket {
-            timestamp: Utc::now(),
+            timestamp,
             message_type: DHCPMessageType::from(message_type_byte),
-            source_ip,
-            destination_ip,
+            source_mac: frame.source_mac,
+            destination_mac: frame.destination_mac,
+            vlan_ids: frame.vlan_ids,
+            source_ip: frame.source_ip,
+            destination_ip: frame.destination_ip,
+            source_port: frame.source_port,
+            destination_port: frame.destination_port,
+            bootp,
+            options,
+            option_overload: assembled.overload.is_some(),
+            options_concatenated: assembled.concatenated,
             option_50: option_50_ip,
             raw_data: packet_data.to_vec(),
         };
         
-        println!("🎉 Paquet DHCP parsé avec succès: {:?} de {} vers {}", 
-                 dhcp_packet.message_type, source_ip, destination_ip);
+        println!("🎉 Paquet DHCP parsé avec succès: {:?} de {} vers {} (xid=0x{:08x}, chaddr={})", 
+                 dhcp_packet.message_type, dhcp_packet.source_ip, dhcp_packet.destination_ip,
+                 dhcp_packet.bootp.xid, dhcp_packet.bootp.chaddr);
         
-        Some(dhcp_packet)
+        Ok(dhcp_packet)
+    }
+}
+
+// Trames synthétiques partagées par les tests des analyseurs
+#[cfg(test)]
+pub mod test_support {
+    use super::*;
+    use chrono::Duration;
+
+    pub const SERVER_IP: Ipv4Addr = Ipv4Addr::new(192, 168, 1, 1);
+    pub const SERVER_MAC: MacAddress = MacAddress([0x00, 0xaa, 0xbb, 0xcc, 0xdd, 0x01]);
+
+    pub fn at(offset_ms: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap() + Duration::milliseconds(offset_ms)
+    }
+
+    pub fn mac(last: u8) -> MacAddress {
+        MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, last])
+    }
+
+    // Message client diffusé en broadcast, ou réponse du serveur SERVER_IP
+    pub fn packet(message_type: DHCPMessageType, chaddr: MacAddress, xid: u32, offset_ms: i64) -> DHCPPacket {
+        let from_client = matches!(
+            message_type,
+            DHCPMessageType::Discover
+                | DHCPMessageType::Request
+                | DHCPMessageType::Decline
+                | DHCPMessageType::Release
+                | DHCPMessageType::Inform
+        );
+        let (source_mac, source_ip, destination_ip) = if from_client {
+            (chaddr, Ipv4Addr::UNSPECIFIED, Ipv4Addr::BROADCAST)
+        } else {
+            (SERVER_MAC, SERVER_IP, Ipv4Addr::BROADCAST)
+        };
+        let mut options = vec![DhcpOption::MessageType(u8::from(message_type))];
+        if !from_client {
+            options.push(DhcpOption::ServerIdentifier(SERVER_IP));
+        }
+
+        DHCPPacket {
+            timestamp: at(offset_ms),
+            message_type,
+            source_mac: Some(source_mac),
+            destination_mac: Some(MacAddress([0xff; 6])),
+            vlan_ids: Vec::new(),
+            source_ip,
+            destination_ip,
+            source_port: if from_client { 68 } else { 67 },
+            destination_port: if from_client { 67 } else { 68 },
+            bootp: BootpHeader {
+                op: if from_client { 1 } else { 2 },
+                htype: 1,
+                hlen: 6,
+                hops: 0,
+                xid,
+                secs: 0,
+                flags: 0x8000,
+                ciaddr: Ipv4Addr::UNSPECIFIED,
+                yiaddr: Ipv4Addr::UNSPECIFIED,
+                siaddr: Ipv4Addr::UNSPECIFIED,
+                giaddr: Ipv4Addr::UNSPECIFIED,
+                chaddr,
+                sname: String::new(),
+                file: String::new(),
+            },
+            options,
+            option_overload: false,
+            options_concatenated: false,
+            option_50: None,
+            raw_data: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(tv_sec: i64, tv_usec: i64) -> PacketHeader {
+        // SAFETY: PacketHeader ne contient que des entiers, pour lesquels zéro est une valeur valide
+        let mut header: PacketHeader = unsafe { std::mem::zeroed() };
+        header.ts.tv_sec = tv_sec as _;
+        header.ts.tv_usec = tv_usec as _;
+        header
+    }
+
+    #[test]
+    fn scales_header_fraction_to_the_granted_precision() {
+        let expected = DateTime::<Utc>::from_timestamp(1_700_000_000, 123_456_789).unwrap();
+        assert_eq!(DHCPCapture::header_timestamp(&header(1_700_000_000, 123_456_789), Precision::Nano), Some(expected));
+
+        let expected = DateTime::<Utc>::from_timestamp(1_700_000_000, 123_456_000).unwrap();
+        assert_eq!(DHCPCapture::header_timestamp(&header(1_700_000_000, 123_456), Precision::Micro), Some(expected));
+        assert_eq!(
+            DHCPCapture::header_timestamp(&header(1_700_000_000, 999_999), Precision::Micro),
+            DateTime::<Utc>::from_timestamp(1_700_000_000, 999_999_000)
+        );
     }
 
+    #[test]
+    fn rejects_out_of_range_header_fractions() {
+        // Des nanosecondes lues comme des microsecondes dépasseraient la seconde
+        assert_eq!(DHCPCapture::header_timestamp(&header(1_700_000_000, 123_456_789), Precision::Micro), None);
+        assert_eq!(DHCPCapture::header_timestamp(&header(1_700_000_000, 1_000_000_000), Precision::Nano), None);
+        assert_eq!(DHCPCapture::header_timestamp(&header(1_700_000_000, -1), Precision::Nano), None);
+        assert_eq!(DHCPCapture::header_timestamp(&header(i64::MAX, 0), Precision::Micro), None);
+    }
+
+    #[test]
+    fn message_type_round_trips_every_code() {
+        for code in 0..=u8::MAX {
+            let message_type = DHCPMessageType::from(code);
+            assert_eq!(u8::from(message_type), code, "code {} ne fait pas l'aller-retour", code);
+
+            let is_known = (1..=18).contains(&code);
+            assert_eq!(message_type != DHCPMessageType::Unknown(code), is_known, "code {}", code);
+        }
+    }
+
+    #[test]
+    fn message_type_matches_rfc_numbering() {
+        let expected = [
+            (1, DHCPMessageType::Discover),
+            (2, DHCPMessageType::Offer),
+            (3, DHCPMessageType::Request),
+            (4, DHCPMessageType::Decline),
+            (5, DHCPMessageType::Ack),
+            (6, DHCPMessageType::Nack),
+            (7, DHCPMessageType::Release),
+            (8, DHCPMessageType::Inform),
+            (9, DHCPMessageType::ForceRenew),
+            (10, DHCPMessageType::LeaseQuery),
+            (11, DHCPMessageType::LeaseUnassigned),
+            (12, DHCPMessageType::LeaseUnknown),
+            (13, DHCPMessageType::LeaseActive),
+            (14, DHCPMessageType::BulkLeaseQuery),
+            (15, DHCPMessageType::LeaseQueryDone),
+            (16, DHCPMessageType::ActiveLeaseQuery),
+            (17, DHCPMessageType::LeaseQueryStatus),
+            (18, DHCPMessageType::Tls),
+        ];
+        for (code, message_type) in expected {
+            assert_eq!(DHCPMessageType::from(code), message_type);
+        }
+        assert_eq!(DHCPMessageType::from(0), DHCPMessageType::Unknown(0));
+        assert_eq!(DHCPMessageType::from(200), DHCPMessageType::Unknown(200));
+    }
+
+    // Trame IPv4 brute portant un Discover dont le nom d'hôte est réparti sur options, file et sname
+    fn overloaded_discover() -> Vec<u8> {
+        let mut bootp = vec![0u8; BOOTP_HEADER_LEN];
+        bootp[0] = 1;
+        bootp[1] = 1;
+        bootp[2] = 6;
+        bootp[BOOTP_SNAME_RANGE][..4].copy_from_slice(&[12, 2, b'e', b'f']);
+        bootp[BOOTP_FILE_RANGE][..4].copy_from_slice(&[12, 2, b'c', b'd']);
+        bootp.extend_from_slice(&crate::packet_decoder::DHCP_MAGIC_COOKIE.to_be_bytes());
+        bootp.extend_from_slice(&[53, 1, 1, 12, 2, b'a', b'b', 52, 1, 3, 255]);
+
+        let mut udp = Vec::new();
+        udp.extend_from_slice(&68u16.to_be_bytes());
+        udp.extend_from_slice(&67u16.to_be_bytes());
+        udp.extend_from_slice(&((8 + bootp.len()) as u16).to_be_bytes());
+        udp.extend_from_slice(&[0, 0]);
+        udp.extend_from_slice(&bootp);
+
+        let mut ip = vec![0x45, 0, 0, 0, 0, 0, 0, 0, 64, 17, 0, 0, 0, 0, 0, 0, 255, 255, 255, 255];
+        ip[2..4].copy_from_slice(&((20 + udp.len()) as u16).to_be_bytes());
+        ip.extend_from_slice(&udp);
+        ip
+    }
+
+    fn ethernet(ethertype: u16, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0xff; 6];
+        frame.extend_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x01]);
+        frame.extend_from_slice(&ethertype.to_be_bytes());
+        frame.extend_from_slice(payload);
+        frame
+    }
 
-} 
\ No newline at end of file
+    fn arp_reply() -> Vec<u8> {
+        let mut arp = vec![0, 1, 0x08, 0x00, 6, 4, 0, 2];
+        arp.extend_from_slice(&[0x00, 0x11, 0x22, 0x33, 0x44, 0x01, 192, 168, 1, 50]);
+        arp.extend_from_slice(&[0x00, 0xaa, 0xbb, 0xcc, 0xdd, 0x01, 192, 168, 1, 1]);
+        ethernet(ETHERTYPE_ARP, &arp)
+    }
+
+    #[test]
+    fn reads_dhcp_and_arp_frames_from_a_recording() {
+        let directory = tempfile::tempdir().unwrap();
+        let options = RecordingOptions {
+            directory: directory.path().display().to_string(),
+            max_file_size_mb: None,
+            rotate_every_minutes: None,
+            max_files: 1,
+        };
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 123_456_789).unwrap();
+        let dhcp = ethernet(0x0800, &overloaded_discover());
+        let arp = arp_reply();
+        let frames = [
+            (start, dhcp.clone()),
+            (start + chrono::Duration::milliseconds(250), arp.clone()),
+            // Trame non décodable: ignorée sans interrompre la lecture
+            (start + chrono::Duration::milliseconds(300), ethernet(0x86dd, &[0; 40])),
+            (start + chrono::Duration::seconds(2), dhcp),
+        ];
+        let mut recorder = CaptureRecorder::new(options, "eth0", None, crate::packet_decoder::LINKTYPE_ETHERNET).unwrap();
+        for (timestamp, data) in &frames {
+            recorder.write_packet(*timestamp, data, data.len() as u32).unwrap();
+        }
+        drop(recorder);
+
+        let path = std::fs::read_dir(directory.path()).unwrap().next().unwrap().unwrap().path();
+        let mut packets = Vec::new();
+        let mut arps = Vec::new();
+        let count = DHCPCapture::read_capture_file(
+            &path.display().to_string(),
+            |packet| packets.push(packet),
+            |arp| arps.push(arp),
+        )
+        .unwrap();
+
+        assert_eq!(count, 2);
+        // Horodatages du fichier, à la nanoseconde, et non l'heure de lecture
+        let timestamps: Vec<DateTime<Utc>> = packets.iter().map(|packet| packet.timestamp).collect();
+        assert_eq!(timestamps, vec![start, start + chrono::Duration::seconds(2)]);
+        assert_eq!(packets[0].message_type, DHCPMessageType::Discover);
+        assert_eq!(packets[0].source_mac, Some(MacAddress([0x00, 0x11, 0x22, 0x33, 0x44, 0x01])));
+
+        assert_eq!(arps.len(), 1);
+        assert_eq!(arps[0].timestamp, start + chrono::Duration::milliseconds(250));
+        assert_eq!(arps[0].operation, 2);
+        assert_eq!(arps[0].sender_ip, Ipv4Addr::new(192, 168, 1, 50));
+        assert_eq!(arps[0].target_ip, Ipv4Addr::new(192, 168, 1, 1));
+    }
+
+    #[test]
+    fn reports_a_missing_capture_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("absent.pcapng").display().to_string();
+        let result = DHCPCapture::read_capture_file(&path, |_| {}, |_| {});
+        assert!(matches!(result, Err(DHCPError::OpenFile { .. })));
+    }
+
+    #[test]
+    fn flags_overloaded_and_concatenated_options() {
+        let frame = overloaded_discover();
+        let packet = DHCPCapture::parse_dhcp_packet(crate::packet_decoder::LINKTYPE_RAW, &frame, Utc::now()).unwrap();
+        assert!(packet.option_overload);
+        assert!(packet.options_concatenated);
+        assert_eq!(packet.message_type, DHCPMessageType::Discover);
+        assert!(packet.options.contains(&DhcpOption::HostName("abcdef".to_string())));
+        // sname et file portent des options, pas des chaînes
+        assert!(packet.bootp.sname.is_empty());
+        assert!(packet.bootp.file.is_empty());
+    }
+}
@@ -0,0 +1,753 @@
+use std::fmt;
+use std::net::Ipv4Addr;
+use crate::dhcp_capture::DHCPMessageType;
+
+pub const OPTION_PAD: u8 = 0;
+pub const OPTION_SUBNET_MASK: u8 = 1;
+pub const OPTION_TIME_OFFSET: u8 = 2;
+pub const OPTION_ROUTER: u8 = 3;
+pub const OPTION_TIME_SERVER: u8 = 4;
+pub const OPTION_DNS: u8 = 6;
+pub const OPTION_HOSTNAME: u8 = 12;
+pub const OPTION_DOMAIN_NAME: u8 = 15;
+pub const OPTION_INTERFACE_MTU: u8 = 26;
+pub const OPTION_BROADCAST_ADDRESS: u8 = 28;
+pub const OPTION_NTP_SERVERS: u8 = 42;
+pub const OPTION_VENDOR_SPECIFIC: u8 = 43;
+pub const OPTION_NETBIOS_NAME_SERVERS: u8 = 44;
+pub const OPTION_REQUESTED_IP: u8 = 50;
+pub const OPTION_LEASE_TIME: u8 = 51;
+pub const OPTION_OVERLOAD: u8 = 52;
+pub const OPTION_MESSAGE_TYPE: u8 = 53;
+pub const OPTION_SERVER_IDENTIFIER: u8 = 54;
+pub const OPTION_PARAMETER_REQUEST_LIST: u8 = 55;
+pub const OPTION_MESSAGE: u8 = 56;
+pub const OPTION_MAX_MESSAGE_SIZE: u8 = 57;
+pub const OPTION_RENEWAL_TIME: u8 = 58;
+pub const OPTION_REBINDING_TIME: u8 = 59;
+pub const OPTION_VENDOR_CLASS: u8 = 60;
+pub const OPTION_CLIENT_IDENTIFIER: u8 = 61;
+pub const OPTION_TFTP_SERVER_NAME: u8 = 66;
+pub const OPTION_BOOTFILE_NAME: u8 = 67;
+pub const OPTION_RELAY_AGENT_INFORMATION: u8 = 82;
+pub const OPTION_END: u8 = 255;
+
+// Sous-options de l'option 82 (RFC 3046, 3527, 3993, 5010, 5107, 6607)
+pub const RELAY_CIRCUIT_ID: u8 = 1;
+pub const RELAY_REMOTE_ID: u8 = 2;
+pub const RELAY_LINK_SELECTION: u8 = 5;
+pub const RELAY_SUBSCRIBER_ID: u8 = 6;
+pub const RELAY_AGENT_FLAGS: u8 = 10;
+pub const RELAY_SERVER_IDENTIFIER_OVERRIDE: u8 = 11;
+pub const RELAY_VIRTUAL_SUBNET_SELECTION: u8 = 151;
+const RELAY_FLAG_UNICAST: u8 = 0x80;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DhcpOption {
+    SubnetMask(Ipv4Addr),
+    TimeOffset(i32),
+    Router(Vec<Ipv4Addr>),
+    TimeServer(Vec<Ipv4Addr>),
+    DomainNameServer(Vec<Ipv4Addr>),
+    HostName(String),
+    DomainName(String),
+    InterfaceMtu(u16),
+    BroadcastAddress(Ipv4Addr),
+    NtpServers(Vec<Ipv4Addr>),
+    VendorSpecific(Vec<u8>),
+    NetbiosNameServers(Vec<Ipv4Addr>),
+    RequestedIpAddress(Ipv4Addr),
+    LeaseTime(u32),
+    OptionOverload(u8),
+    MessageType(u8),
+    ServerIdentifier(Ipv4Addr),
+    ParameterRequestList(Vec<u8>),
+    Message(String),
+    MaxMessageSize(u16),
+    RenewalTime(u32),
+    RebindingTime(u32),
+    VendorClassIdentifier(String),
+    ClientIdentifier(Vec<u8>),
+    TftpServerName(String),
+    BootfileName(String),
+    RelayAgentInformation(Vec<RelaySubOption>),
+    Other { code: u8, data: Vec<u8> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RelaySubOption {
+    CircuitId(Vec<u8>),
+    RemoteId(Vec<u8>),
+    LinkSelection(Ipv4Addr),
+    SubscriberId(String),
+    RelayAgentFlags(u8),
+    ServerIdentifierOverride(Ipv4Addr),
+    VirtualSubnetSelection { kind: u8, data: Vec<u8> },
+    Other { code: u8, data: Vec<u8> },
+}
+
+impl RelaySubOption {
+    pub fn decode(code: u8, data: &[u8]) -> Self {
+        let decoded = match code {
+            RELAY_CIRCUIT_ID => Some(RelaySubOption::CircuitId(data.to_vec())),
+            RELAY_REMOTE_ID => Some(RelaySubOption::RemoteId(data.to_vec())),
+            RELAY_LINK_SELECTION => read_ip(data).map(RelaySubOption::LinkSelection),
+            RELAY_SUBSCRIBER_ID => Some(RelaySubOption::SubscriberId(read_string(data))),
+            RELAY_AGENT_FLAGS => read_u8(data).map(RelaySubOption::RelayAgentFlags),
+            RELAY_SERVER_IDENTIFIER_OVERRIDE => read_ip(data).map(RelaySubOption::ServerIdentifierOverride),
+            RELAY_VIRTUAL_SUBNET_SELECTION => data.split_first().map(|(kind, rest)| {
+                RelaySubOption::VirtualSubnetSelection { kind: *kind, data: rest.to_vec() }
+            }),
+            _ => None,
+        };
+
+        decoded.unwrap_or_else(|| RelaySubOption::Other { code, data: data.to_vec() })
+    }
+}
+
+impl fmt::Display for RelaySubOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelaySubOption::CircuitId(data) => write!(f, "Circuit ID: {}", format_identifier(data)),
+            RelaySubOption::RemoteId(data) => write!(f, "Remote ID: {}", format_identifier(data)),
+            RelaySubOption::LinkSelection(ip) => write!(f, "Link Selection: {}", ip),
+            RelaySubOption::SubscriberId(text) => write!(f, "Subscriber ID: {}", text),
+            RelaySubOption::RelayAgentFlags(flags) => {
+                let mode = if flags & RELAY_FLAG_UNICAST != 0 { "unicast" } else { "broadcast" };
+                write!(f, "Relay Agent Flags: 0x{:02x} ({})", flags, mode)
+            }
+            RelaySubOption::ServerIdentifierOverride(ip) => write!(f, "Server Identifier Override: {}", ip),
+            RelaySubOption::VirtualSubnetSelection { kind, data } => match kind {
+                // Type 0: nom VPN NVT ASCII, 1: RFC 2685 VPN-ID, 255: VRF global
+                0 => write!(f, "Virtual Subnet Selection: {}", read_string(data)),
+                255 => write!(f, "Virtual Subnet Selection: global"),
+                _ => write!(f, "Virtual Subnet Selection (type {}): {}", kind, to_hex(data, " ")),
+            },
+            RelaySubOption::Other { code, data } => write!(f, "Sub-option {}: {}", code, to_hex(data, " ")),
+        }
+    }
+}
+
+impl DhcpOption {
+    // Décode la valeur d'une option; une longueur incohérente retombe sur Other
+    pub fn decode(code: u8, data: &[u8]) -> Self {
+        let decoded = match code {
+            OPTION_SUBNET_MASK => read_ip(data).map(DhcpOption::SubnetMask),
+            OPTION_TIME_OFFSET => read_u32(data).map(|v| DhcpOption::TimeOffset(v as i32)),
+            OPTION_ROUTER => read_ip_list(data).map(DhcpOption::Router),
+            OPTION_TIME_SERVER => read_ip_list(data).map(DhcpOption::TimeServer),
+            OPTION_DNS => read_ip_list(data).map(DhcpOption::DomainNameServer),
+            OPTION_HOSTNAME => Some(DhcpOption::HostName(read_string(data))),
+            OPTION_DOMAIN_NAME => Some(DhcpOption::DomainName(read_string(data))),
+            OPTION_INTERFACE_MTU => read_u16(data).map(DhcpOption::InterfaceMtu),
+            OPTION_BROADCAST_ADDRESS => read_ip(data).map(DhcpOption::BroadcastAddress),
+            OPTION_NTP_SERVERS => read_ip_list(data).map(DhcpOption::NtpServers),
+            OPTION_VENDOR_SPECIFIC => Some(DhcpOption::VendorSpecific(data.to_vec())),
+            OPTION_NETBIOS_NAME_SERVERS => read_ip_list(data).map(DhcpOption::NetbiosNameServers),
+            OPTION_REQUESTED_IP => read_ip(data).map(DhcpOption::RequestedIpAddress),
+            OPTION_LEASE_TIME => read_u32(data).map(DhcpOption::LeaseTime),
+            OPTION_OVERLOAD => read_u8(data).map(DhcpOption::OptionOverload),
+            OPTION_MESSAGE_TYPE => read_u8(data).map(DhcpOption::MessageType),
+            OPTION_SERVER_IDENTIFIER => read_ip(data).map(DhcpOption::ServerIdentifier),
+            OPTION_PARAMETER_REQUEST_LIST => Some(DhcpOption::ParameterRequestList(data.to_vec())),
+            OPTION_MESSAGE => Some(DhcpOption::Message(read_string(data))),
+            OPTION_MAX_MESSAGE_SIZE => read_u16(data).map(DhcpOption::MaxMessageSize),
+            OPTION_RENEWAL_TIME => read_u32(data).map(DhcpOption::RenewalTime),
+            OPTION_REBINDING_TIME => read_u32(data).map(DhcpOption::RebindingTime),
+            OPTION_VENDOR_CLASS => Some(DhcpOption::VendorClassIdentifier(read_string(data))),
+            OPTION_CLIENT_IDENTIFIER if !data.is_empty() => Some(DhcpOption::ClientIdentifier(data.to_vec())),
+            OPTION_TFTP_SERVER_NAME => Some(DhcpOption::TftpServerName(read_string(data))),
+            OPTION_BOOTFILE_NAME => Some(DhcpOption::BootfileName(read_string(data))),
+            OPTION_RELAY_AGENT_INFORMATION => parse_relay_sub_options(data).map(DhcpOption::RelayAgentInformation),
+            _ => None,
+        };
+
+        decoded.unwrap_or_else(|| DhcpOption::Other { code, data: data.to_vec() })
+    }
+
+    pub fn code(&self) -> u8 {
+        match self {
+            DhcpOption::SubnetMask(_) => OPTION_SUBNET_MASK,
+            DhcpOption::TimeOffset(_) => OPTION_TIME_OFFSET,
+            DhcpOption::Router(_) => OPTION_ROUTER,
+            DhcpOption::TimeServer(_) => OPTION_TIME_SERVER,
+            DhcpOption::DomainNameServer(_) => OPTION_DNS,
+            DhcpOption::HostName(_) => OPTION_HOSTNAME,
+            DhcpOption::DomainName(_) => OPTION_DOMAIN_NAME,
+            DhcpOption::InterfaceMtu(_) => OPTION_INTERFACE_MTU,
+            DhcpOption::BroadcastAddress(_) => OPTION_BROADCAST_ADDRESS,
+            DhcpOption::NtpServers(_) => OPTION_NTP_SERVERS,
+            DhcpOption::VendorSpecific(_) => OPTION_VENDOR_SPECIFIC,
+            DhcpOption::NetbiosNameServers(_) => OPTION_NETBIOS_NAME_SERVERS,
+            DhcpOption::RequestedIpAddress(_) => OPTION_REQUESTED_IP,
+            DhcpOption::LeaseTime(_) => OPTION_LEASE_TIME,
+            DhcpOption::OptionOverload(_) => OPTION_OVERLOAD,
+            DhcpOption::MessageType(_) => OPTION_MESSAGE_TYPE,
+            DhcpOption::ServerIdentifier(_) => OPTION_SERVER_IDENTIFIER,
+            DhcpOption::ParameterRequestList(_) => OPTION_PARAMETER_REQUEST_LIST,
+            DhcpOption::Message(_) => OPTION_MESSAGE,
+            DhcpOption::MaxMessageSize(_) => OPTION_MAX_MESSAGE_SIZE,
+            DhcpOption::RenewalTime(_) => OPTION_RENEWAL_TIME,
+            DhcpOption::RebindingTime(_) => OPTION_REBINDING_TIME,
+            DhcpOption::VendorClassIdentifier(_) => OPTION_VENDOR_CLASS,
+            DhcpOption::ClientIdentifier(_) => OPTION_CLIENT_IDENTIFIER,
+            DhcpOption::TftpServerName(_) => OPTION_TFTP_SERVER_NAME,
+            DhcpOption::BootfileName(_) => OPTION_BOOTFILE_NAME,
+            DhcpOption::RelayAgentInformation(_) => OPTION_RELAY_AGENT_INFORMATION,
+            DhcpOption::Other { code, .. } => *code,
+        }
+    }
+
+    pub fn name(&self) -> String {
+        option_name(self.code())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("Option {}", self.code()))
+    }
+}
+
+impl fmt::Display for DhcpOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DhcpOption::SubnetMask(ip)
+            | DhcpOption::BroadcastAddress(ip)
+            | DhcpOption::RequestedIpAddress(ip)
+            | DhcpOption::ServerIdentifier(ip) => write!(f, "{}", ip),
+            DhcpOption::Router(ips)
+            | DhcpOption::TimeServer(ips)
+            | DhcpOption::DomainNameServer(ips)
+            | DhcpOption::NtpServers(ips)
+            | DhcpOption::NetbiosNameServers(ips) => {
+                let list: Vec<String> = ips.iter().map(|ip| ip.to_string()).collect();
+                write!(f, "{}", list.join(", "))
+            }
+            DhcpOption::TimeOffset(seconds) => write!(f, "{} s", seconds),
+            DhcpOption::LeaseTime(seconds)
+            | DhcpOption::RenewalTime(seconds)
+            | DhcpOption::RebindingTime(seconds) => {
+                if *seconds == u32::MAX {
+                    write!(f, "infini")
+                } else {
+                    write!(f, "{} s ({})", seconds, format_duration(*seconds))
+                }
+            }
+            DhcpOption::HostName(text)
+            | DhcpOption::DomainName(text)
+            | DhcpOption::Message(text)
+            | DhcpOption::VendorClassIdentifier(text)
+            | DhcpOption::TftpServerName(text)
+            | DhcpOption::BootfileName(text) => write!(f, "{}", text),
+            DhcpOption::InterfaceMtu(size) | DhcpOption::MaxMessageSize(size) => write!(f, "{}", size),
+            DhcpOption::OptionOverload(value) => match value {
+                1 => write!(f, "file"),
+                2 => write!(f, "sname"),
+                3 => write!(f, "file + sname"),
+                other => write!(f, "{} (invalide)", other),
+            },
+            DhcpOption::MessageType(value) => write!(f, "{:?}", DHCPMessageType::from(*value)),
+            DhcpOption::ParameterRequestList(codes) => {
+                let list: Vec<String> = codes.iter().map(|code| code.to_string()).collect();
+                write!(f, "{}", list.join(", "))
+            }
+            DhcpOption::ClientIdentifier(data) => {
+                // Type 1 = Ethernet: afficher comme une adresse MAC
+                if data.len() == 7 && data[0] == 1 {
+                    write!(f, "{} (Ethernet)", to_hex(&data[1..], ":"))
+                } else {
+                    write!(f, "{}", to_hex(data, ":"))
+                }
+            }
+            DhcpOption::RelayAgentInformation(sub_options) => {
+                let list: Vec<String> = sub_options.iter().map(|sub| sub.to_string()).collect();
+                write!(f, "{}", list.join("; "))
+            }
+            DhcpOption::VendorSpecific(data) | DhcpOption::Other { data, .. } => {
+                write!(f, "{}", to_hex(data, " "))
+            }
+        }
+    }
+}
+
+pub fn option_name(code: u8) -> Option<&'static str> {
+    let name = match code {
+        OPTION_PAD => "Pad",
+        OPTION_SUBNET_MASK => "Subnet Mask",
+        OPTION_TIME_OFFSET => "Time Offset",
+        OPTION_ROUTER => "Router",
+        OPTION_TIME_SERVER => "Time Server",
+        OPTION_DNS => "DNS Server",
+        OPTION_HOSTNAME => "Hostname",
+        OPTION_DOMAIN_NAME => "Domain Name",
+        OPTION_INTERFACE_MTU => "Interface MTU",
+        OPTION_BROADCAST_ADDRESS => "Broadcast Address",
+        OPTION_NTP_SERVERS => "NTP Servers",
+        OPTION_VENDOR_SPECIFIC => "Vendor Specific Information",
+        OPTION_NETBIOS_NAME_SERVERS => "NetBIOS Name Servers",
+        OPTION_REQUESTED_IP => "Requested IP Address",
+        OPTION_LEASE_TIME => "IP Address Lease Time",
+        OPTION_OVERLOAD => "Option Overload",
+        OPTION_MESSAGE_TYPE => "DHCP Message Type",
+        OPTION_SERVER_IDENTIFIER => "Server Identifier",
+        OPTION_PARAMETER_REQUEST_LIST => "Parameter Request List",
+        OPTION_MESSAGE => "Message",
+        OPTION_MAX_MESSAGE_SIZE => "Maximum DHCP Message Size",
+        OPTION_RENEWAL_TIME => "Renewal (T1) Time",
+        OPTION_REBINDING_TIME => "Rebinding (T2) Time",
+        OPTION_VENDOR_CLASS => "Vendor Class Identifier",
+        OPTION_CLIENT_IDENTIFIER => "Client Identifier",
+        OPTION_TFTP_SERVER_NAME => "TFTP Server Name",
+        OPTION_BOOTFILE_NAME => "Bootfile Name",
+        81 => "Client FQDN",
+        OPTION_RELAY_AGENT_INFORMATION => "Agent Information",
+        119 => "Domain Search",
+        121 => "Classless Static Route",
+        OPTION_END => "End",
+        _ => return None,
+    };
+    Some(name)
+}
+
+pub const OVERLOAD_FILE: u8 = 1;
+pub const OVERLOAD_SNAME: u8 = 2;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AssembledOptions {
+    pub options: Vec<DhcpOption>,
+    pub overload: Option<u8>,
+    pub concatenated: bool,
+    pub malformed: bool,
+}
+
+// Assemble les options selon RFC 2131 §4.1 et RFC 3396: zone options, puis file et sname si
+// l'option 52 le demande, les instances répétées d'un même code étant concaténées dans l'ordre
+pub fn assemble_options(options_field: &[u8], file_field: &[u8], sname_field: &[u8]) -> AssembledOptions {
+    let mut assembled = AssembledOptions::default();
+    let mut instances = scan_options(options_field, &mut assembled.malformed);
+
+    // L'option 52 n'est valide que dans la zone options
+    assembled.overload = instances.iter().find_map(|(code, data)| match (*code, *data) {
+        (OPTION_OVERLOAD, [value]) => Some(*value),
+        _ => None,
+    });
+
+    if let Some(overload) = assembled.overload {
+        if overload & OVERLOAD_FILE != 0 {
+            instances.extend(scan_options(file_field, &mut assembled.malformed));
+        }
+        if overload & OVERLOAD_SNAME != 0 {
+            instances.extend(scan_options(sname_field, &mut assembled.malformed));
+        }
+    }
+
+    let mut merged: Vec<(u8, Vec<u8>)> = Vec::new();
+    for (code, data) in instances {
+        match merged.iter_mut().find(|(existing, _)| *existing == code) {
+            Some((_, buffer)) => {
+                buffer.extend_from_slice(data);
+                assembled.concatenated = true;
+            }
+            None => merged.push((code, data.to_vec())),
+        }
+    }
+
+    assembled.options = merged
+        .iter()
+        .map(|(code, data)| DhcpOption::decode(*code, data))
+        .collect();
+    assembled
+}
+
+// Découpe une zone en instances TLV brutes jusqu'à l'option End; une longueur qui déborde
+// de la zone invalide tout ce qui suit, on s'arrête donc au lieu de resynchroniser octet par octet
+fn scan_options<'a>(data: &'a [u8], malformed: &mut bool) -> Vec<(u8, &'a [u8])> {
+    let mut instances = Vec::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        let code = data[i];
+        match code {
+            OPTION_PAD => {
+                i += 1;
+                continue;
+            }
+            OPTION_END => return instances,
+            _ => {}
+        }
+
+        let Some(&length) = data.get(i + 1) else {
+            println!("⚠️ Option {} sans longueur à l'octet {}", code, i);
+            *malformed = true;
+            break;
+        };
+
+        let value_start = i + 2;
+        let value_end = value_start + length as usize;
+        let Some(value) = data.get(value_start..value_end) else {
+            println!("⚠️ Option {} tronquée à l'octet {}: longueur={}, restant={}",
+                     code, i, length, data.len() - value_start);
+            *malformed = true;
+            break;
+        };
+
+        instances.push((code, value));
+        i = value_end;
+    }
+
+    instances
+}
+
+// Les sous-options de l'option 82 sont des TLV sans Pad ni End; None si la structure est incohérente
+fn parse_relay_sub_options(data: &[u8]) -> Option<Vec<RelaySubOption>> {
+    let mut sub_options = Vec::new();
+    let mut i = 0;
+
+    while i < data.len() {
+        let code = data[i];
+        let length = *data.get(i + 1)? as usize;
+        let value = data.get(i + 2..i + 2 + length)?;
+        sub_options.push(RelaySubOption::decode(code, value));
+        i += 2 + length;
+    }
+
+    Some(sub_options)
+}
+
+// Circuit ID / Remote ID: texte si imprimable (ex. "Gi1/0/12"), sinon hexadécimal
+pub fn format_identifier(data: &[u8]) -> String {
+    let printable = !data.is_empty() && data.iter().all(|b| b.is_ascii_graphic() || *b == b' ');
+    if printable {
+        String::from_utf8_lossy(data).into_owned()
+    } else {
+        to_hex(data, ":")
+    }
+}
+
+fn read_u8(data: &[u8]) -> Option<u8> {
+    match data {
+        [value] => Some(*value),
+        _ => None,
+    }
+}
+
+fn read_u16(data: &[u8]) -> Option<u16> {
+    match data {
+        [a, b] => Some(u16::from_be_bytes([*a, *b])),
+        _ => None,
+    }
+}
+
+fn read_u32(data: &[u8]) -> Option<u32> {
+    match data {
+        [a, b, c, d] => Some(u32::from_be_bytes([*a, *b, *c, *d])),
+        _ => None,
+    }
+}
+
+fn read_ip(data: &[u8]) -> Option<Ipv4Addr> {
+    match data {
+        [a, b, c, d] => Some(Ipv4Addr::new(*a, *b, *c, *d)),
+        _ => None,
+    }
+}
+
+fn read_ip_list(data: &[u8]) -> Option<Vec<Ipv4Addr>> {
+    if data.is_empty() || data.len() % 4 != 0 {
+        return None;
+    }
+    Some(data.chunks_exact(4).map(|c| Ipv4Addr::new(c[0], c[1], c[2], c[3])).collect())
+}
+
+// Les chaînes DHCP ne sont pas forcément terminées par un NUL, mais certains clients en ajoutent un
+fn read_string(data: &[u8]) -> String {
+    let end = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+    String::from_utf8_lossy(&data[..end]).into_owned()
+}
+
+fn to_hex(data: &[u8], separator: &str) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(separator)
+}
+
+fn format_duration(seconds: u32) -> String {
+    let days = seconds / 86_400;
+    let hours = (seconds % 86_400) / 3_600;
+    let minutes = (seconds % 3_600) / 60;
+    if days > 0 {
+        format!("{}j {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m {}s", minutes, seconds % 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DISCOVER: [u8; 3] = [OPTION_MESSAGE_TYPE, 1, 1];
+
+    fn field(parts: &[&[u8]]) -> Vec<u8> {
+        let mut field: Vec<u8> = parts.concat();
+        field.push(OPTION_END);
+        field
+    }
+
+    fn hostname(text: &str) -> Vec<u8> {
+        let mut option = vec![OPTION_HOSTNAME, text.len() as u8];
+        option.extend_from_slice(text.as_bytes());
+        option
+    }
+
+    #[test]
+    fn reads_file_then_sname_after_options() {
+        let options = field(&[&DISCOVER, &hostname("ab"), &[OPTION_OVERLOAD, 1, 3]]);
+        let file = field(&[&hostname("cd")]);
+        let sname = field(&[&hostname("ef"), &[OPTION_DOMAIN_NAME, 3, b'l', b'a', b'n']]);
+
+        let assembled = assemble_options(&options, &file, &sname);
+        assert_eq!(assembled.overload, Some(3));
+        assert!(assembled.concatenated);
+        assert!(!assembled.malformed);
+        assert_eq!(assembled.options, vec![
+            DhcpOption::MessageType(1),
+            DhcpOption::HostName("abcdef".to_string()),
+            DhcpOption::OptionOverload(3),
+            DhcpOption::DomainName("lan".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn reads_only_the_overloaded_fields() {
+        let file = field(&[&hostname("file")]);
+        let sname = field(&[&hostname("sname")]);
+        let cases = [
+            (None, None),
+            (Some(OVERLOAD_FILE), Some("file")),
+            (Some(OVERLOAD_SNAME), Some("sname")),
+        ];
+        for (overload, expected) in cases {
+            let options = match overload {
+                Some(value) => field(&[&DISCOVER, &[OPTION_OVERLOAD, 1, value]]),
+                None => field(&[&DISCOVER]),
+            };
+            let assembled = assemble_options(&options, &file, &sname);
+            assert_eq!(assembled.overload, overload);
+            assert!(!assembled.concatenated);
+            let hostname = assembled.options.iter().find_map(|option| match option {
+                DhcpOption::HostName(name) => Some(name.as_str()),
+                _ => None,
+            });
+            assert_eq!(hostname, expected, "overload {:?}", overload);
+        }
+    }
+
+    #[test]
+    fn ignores_overload_outside_the_options_field() {
+        let file = field(&[&[OPTION_OVERLOAD, 1, 2], &hostname("file")]);
+        let sname = field(&[&hostname("sname")]);
+        let assembled = assemble_options(&field(&[&DISCOVER]), &file, &sname);
+        assert_eq!(assembled.overload, None);
+        assert_eq!(assembled.options, vec![DhcpOption::MessageType(1)]);
+
+        // Une option 52 de longueur incorrecte n'active rien
+        let assembled = assemble_options(&field(&[&DISCOVER, &[OPTION_OVERLOAD, 2, 3, 3]]), &file, &sname);
+        assert_eq!(assembled.overload, None);
+    }
+
+    #[test]
+    fn concatenates_split_instances_before_decoding() {
+        // RFC 3396: un masque découpé en deux instances n'est valide qu'une fois réassemblé
+        let options = field(&[&[OPTION_SUBNET_MASK, 2, 255, 255], &DISCOVER, &[OPTION_SUBNET_MASK, 2, 255, 0]]);
+        let assembled = assemble_options(&options, &[], &[]);
+        assert!(assembled.concatenated);
+        assert_eq!(assembled.options, vec![
+            DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)),
+            DhcpOption::MessageType(1),
+        ]);
+    }
+
+    #[test]
+    fn skips_pad_and_stops_at_end() {
+        let options = [OPTION_PAD, OPTION_PAD, OPTION_MESSAGE_TYPE, 1, 3, OPTION_PAD, OPTION_END, OPTION_HOSTNAME, 1, b'x'];
+        let assembled = assemble_options(&options, &[], &[]);
+        assert_eq!(assembled.options, vec![DhcpOption::MessageType(3)]);
+        assert!(!assembled.malformed);
+
+        // Zone sans End: la fin des données termine aussi le parcours
+        let assembled = assemble_options(&DISCOVER, &[], &[]);
+        assert_eq!(assembled.options, vec![DhcpOption::MessageType(1)]);
+        assert!(!assembled.malformed);
+    }
+
+    #[test]
+    fn stops_at_a_truncated_length() {
+        let options = [&DISCOVER[..], &[OPTION_HOSTNAME, 10, b'a', b'b'], &[OPTION_DOMAIN_NAME, 1, b'x']].concat();
+        let assembled = assemble_options(&options, &[], &[]);
+        assert!(assembled.malformed);
+        assert_eq!(assembled.options, vec![DhcpOption::MessageType(1)]);
+
+        // Code sans octet de longueur
+        let assembled = assemble_options(&[&DISCOVER[..], &[OPTION_HOSTNAME]].concat(), &[], &[]);
+        assert!(assembled.malformed);
+        assert_eq!(assembled.options, vec![DhcpOption::MessageType(1)]);
+
+        // Une zone overloadée tronquée conserve ce qui a été lu avant elle
+        let options = field(&[&DISCOVER, &[OPTION_OVERLOAD, 1, OVERLOAD_FILE]]);
+        let assembled = assemble_options(&options, &[OPTION_HOSTNAME, 4, b'a'], &[]);
+        assert!(assembled.malformed);
+        assert_eq!(assembled.options, vec![DhcpOption::MessageType(1), DhcpOption::OptionOverload(1)]);
+    }
+
+    #[test]
+    fn decodes_each_typed_option() {
+        let ip = [192, 168, 1, 1];
+        let ips = [192, 168, 1, 1, 8, 8, 8, 8];
+        let list = vec![Ipv4Addr::new(192, 168, 1, 1), Ipv4Addr::new(8, 8, 8, 8)];
+        let cases: Vec<(u8, &[u8], DhcpOption, &str)> = vec![
+            (OPTION_SUBNET_MASK, &[255, 255, 255, 0], DhcpOption::SubnetMask(Ipv4Addr::new(255, 255, 255, 0)), "255.255.255.0"),
+            (OPTION_TIME_OFFSET, &[0xff, 0xff, 0xf1, 0xf0], DhcpOption::TimeOffset(-3600), "-3600 s"),
+            (OPTION_ROUTER, &ips, DhcpOption::Router(list.clone()), "192.168.1.1, 8.8.8.8"),
+            (OPTION_TIME_SERVER, &ips, DhcpOption::TimeServer(list.clone()), "192.168.1.1, 8.8.8.8"),
+            (OPTION_DNS, &ips, DhcpOption::DomainNameServer(list.clone()), "192.168.1.1, 8.8.8.8"),
+            (OPTION_HOSTNAME, b"laptop\0", DhcpOption::HostName("laptop".to_string()), "laptop"),
+            (OPTION_DOMAIN_NAME, b"corp.local", DhcpOption::DomainName("corp.local".to_string()), "corp.local"),
+            (OPTION_INTERFACE_MTU, &[0x05, 0xdc], DhcpOption::InterfaceMtu(1500), "1500"),
+            (OPTION_BROADCAST_ADDRESS, &[192, 168, 1, 255], DhcpOption::BroadcastAddress(Ipv4Addr::new(192, 168, 1, 255)), "192.168.1.255"),
+            (OPTION_NTP_SERVERS, &ips, DhcpOption::NtpServers(list.clone()), "192.168.1.1, 8.8.8.8"),
+            (OPTION_VENDOR_SPECIFIC, &[1, 2, 0xab], DhcpOption::VendorSpecific(vec![1, 2, 0xab]), "01 02 ab"),
+            (OPTION_NETBIOS_NAME_SERVERS, &ips, DhcpOption::NetbiosNameServers(list.clone()), "192.168.1.1, 8.8.8.8"),
+            (OPTION_REQUESTED_IP, &ip, DhcpOption::RequestedIpAddress(Ipv4Addr::new(192, 168, 1, 1)), "192.168.1.1"),
+            (OPTION_LEASE_TIME, &[0, 1, 0x51, 0x80], DhcpOption::LeaseTime(86_400), "86400 s (1j 0h 0m)"),
+            (OPTION_LEASE_TIME, &[0xff; 4], DhcpOption::LeaseTime(u32::MAX), "infini"),
+            (OPTION_OVERLOAD, &[3], DhcpOption::OptionOverload(3), "file + sname"),
+            (OPTION_OVERLOAD, &[7], DhcpOption::OptionOverload(7), "7 (invalide)"),
+            (OPTION_MESSAGE_TYPE, &[5], DhcpOption::MessageType(5), "Ack"),
+            (OPTION_SERVER_IDENTIFIER, &ip, DhcpOption::ServerIdentifier(Ipv4Addr::new(192, 168, 1, 1)), "192.168.1.1"),
+            (OPTION_PARAMETER_REQUEST_LIST, &[1, 3, 6], DhcpOption::ParameterRequestList(vec![1, 3, 6]), "1, 3, 6"),
+            (OPTION_MESSAGE, b"address in use", DhcpOption::Message("address in use".to_string()), "address in use"),
+            (OPTION_MAX_MESSAGE_SIZE, &[0x05, 0xc0], DhcpOption::MaxMessageSize(1472), "1472"),
+            (OPTION_RENEWAL_TIME, &[0, 0, 0x0e, 0x10], DhcpOption::RenewalTime(3600), "3600 s (1h 0m)"),
+            (OPTION_REBINDING_TIME, &[0, 0, 0, 90], DhcpOption::RebindingTime(90), "90 s (1m 30s)"),
+            (OPTION_VENDOR_CLASS, b"MSFT 5.0", DhcpOption::VendorClassIdentifier("MSFT 5.0".to_string()), "MSFT 5.0"),
+            (OPTION_CLIENT_IDENTIFIER, &[1, 0, 0x11, 0x22, 0x33, 0x44, 0x55], DhcpOption::ClientIdentifier(vec![1, 0, 0x11, 0x22, 0x33, 0x44, 0x55]), "00:11:22:33:44:55 (Ethernet)"),
+            (OPTION_CLIENT_IDENTIFIER, &[0xff, 0xab], DhcpOption::ClientIdentifier(vec![0xff, 0xab]), "ff:ab"),
+            (OPTION_TFTP_SERVER_NAME, b"10.0.0.5", DhcpOption::TftpServerName("10.0.0.5".to_string()), "10.0.0.5"),
+            (OPTION_BOOTFILE_NAME, b"pxelinux.0", DhcpOption::BootfileName("pxelinux.0".to_string()), "pxelinux.0"),
+        ];
+
+        for (code, data, expected, display) in cases {
+            let decoded = DhcpOption::decode(code, data);
+            assert_eq!(decoded, expected, "option {}", code);
+            assert_eq!(decoded.code(), code);
+            assert_eq!(decoded.to_string(), display, "option {}", code);
+            assert_eq!(decoded.name(), option_name(code).unwrap());
+        }
+    }
+
+    #[test]
+    fn unknown_codes_fall_back_to_other() {
+        let decoded = DhcpOption::decode(250, &[0xde, 0xad]);
+        assert_eq!(decoded, DhcpOption::Other { code: 250, data: vec![0xde, 0xad] });
+        assert_eq!(decoded.code(), 250);
+        assert_eq!(decoded.name(), "Option 250");
+        assert_eq!(decoded.to_string(), "de ad");
+
+        // Code connu sans variante typée: le nom reste celui du tableau
+        let decoded = DhcpOption::decode(81, &[0, 0, 0]);
+        assert_eq!(decoded, DhcpOption::Other { code: 81, data: vec![0, 0, 0] });
+        assert_eq!(decoded.name(), "Client FQDN");
+    }
+
+    #[test]
+    fn wrong_length_fixed_size_options_fall_back_to_other() {
+        let cases: Vec<(u8, &[u8])> = vec![
+            (OPTION_SUBNET_MASK, &[255, 255, 255]),
+            (OPTION_TIME_OFFSET, &[0, 0, 0, 0, 0]),
+            (OPTION_ROUTER, &[]),
+            (OPTION_DNS, &[8, 8, 8, 8, 8, 8]),
+            (OPTION_INTERFACE_MTU, &[5]),
+            (OPTION_REQUESTED_IP, &[192, 168, 1]),
+            (OPTION_LEASE_TIME, &[0, 1, 0x51]),
+            (OPTION_OVERLOAD, &[1, 2]),
+            (OPTION_MESSAGE_TYPE, &[]),
+            (OPTION_SERVER_IDENTIFIER, &[192, 168, 1, 1, 0]),
+            (OPTION_MAX_MESSAGE_SIZE, &[0x05, 0xc0, 0]),
+            (OPTION_RENEWAL_TIME, &[0; 2]),
+            (OPTION_CLIENT_IDENTIFIER, &[]),
+        ];
+
+        for (code, data) in cases {
+            let decoded = DhcpOption::decode(code, data);
+            assert_eq!(decoded, DhcpOption::Other { code, data: data.to_vec() }, "option {}", code);
+            // Le nom reste lisible même si la valeur est incohérente
+            assert_eq!(decoded.name(), option_name(code).unwrap());
+        }
+    }
+
+    #[test]
+    fn decodes_relay_sub_options() {
+        let cases: Vec<(u8, &[u8], RelaySubOption, &str)> = vec![
+            (RELAY_CIRCUIT_ID, b"Gi1/0/12", RelaySubOption::CircuitId(b"Gi1/0/12".to_vec()), "Circuit ID: Gi1/0/12"),
+            (RELAY_CIRCUIT_ID, &[0, 4, 0, 12], RelaySubOption::CircuitId(vec![0, 4, 0, 12]), "Circuit ID: 00:04:00:0c"),
+            (RELAY_REMOTE_ID, &[0, 0x1b, 0x2c], RelaySubOption::RemoteId(vec![0, 0x1b, 0x2c]), "Remote ID: 00:1b:2c"),
+            (RELAY_LINK_SELECTION, &[10, 1, 2, 0], RelaySubOption::LinkSelection(Ipv4Addr::new(10, 1, 2, 0)), "Link Selection: 10.1.2.0"),
+            (RELAY_SUBSCRIBER_ID, b"client-42", RelaySubOption::SubscriberId("client-42".to_string()), "Subscriber ID: client-42"),
+            (RELAY_AGENT_FLAGS, &[0x80], RelaySubOption::RelayAgentFlags(0x80), "Relay Agent Flags: 0x80 (unicast)"),
+            (RELAY_AGENT_FLAGS, &[0x00], RelaySubOption::RelayAgentFlags(0), "Relay Agent Flags: 0x00 (broadcast)"),
+            (RELAY_SERVER_IDENTIFIER_OVERRIDE, &[10, 1, 2, 1], RelaySubOption::ServerIdentifierOverride(Ipv4Addr::new(10, 1, 2, 1)), "Server Identifier Override: 10.1.2.1"),
+            (RELAY_VIRTUAL_SUBNET_SELECTION, b"\0blue", RelaySubOption::VirtualSubnetSelection { kind: 0, data: b"blue".to_vec() }, "Virtual Subnet Selection: blue"),
+            (RELAY_VIRTUAL_SUBNET_SELECTION, &[255], RelaySubOption::VirtualSubnetSelection { kind: 255, data: Vec::new() }, "Virtual Subnet Selection: global"),
+            (RELAY_VIRTUAL_SUBNET_SELECTION, &[1, 0xab, 0xcd], RelaySubOption::VirtualSubnetSelection { kind: 1, data: vec![0xab, 0xcd] }, "Virtual Subnet Selection (type 1): ab cd"),
+        ];
+
+        for (code, data, expected, display) in cases {
+            let decoded = RelaySubOption::decode(code, data);
+            assert_eq!(decoded, expected, "sous-option {}", code);
+            assert_eq!(decoded.to_string(), display);
+        }
+    }
+
+    #[test]
+    fn unknown_or_malformed_relay_sub_options_fall_back_to_other() {
+        let cases: Vec<(u8, &[u8])> = vec![
+            (9, &[0, 0, 0x0d, 0xe9]),
+            (RELAY_LINK_SELECTION, &[10, 1, 2]),
+            (RELAY_AGENT_FLAGS, &[]),
+            (RELAY_SERVER_IDENTIFIER_OVERRIDE, &[10, 1, 2, 1, 0]),
+            (RELAY_VIRTUAL_SUBNET_SELECTION, &[]),
+        ];
+
+        for (code, data) in cases {
+            assert_eq!(RelaySubOption::decode(code, data), RelaySubOption::Other { code, data: data.to_vec() });
+        }
+        assert_eq!(RelaySubOption::decode(9, &[1, 2]).to_string(), "Sub-option 9: 01 02");
+    }
+
+    #[test]
+    fn decodes_relay_agent_information() {
+        let data = [&[RELAY_CIRCUIT_ID, 3][..], b"p12", &[RELAY_REMOTE_ID, 2, 0xaa, 0xbb]].concat();
+        let decoded = DhcpOption::decode(OPTION_RELAY_AGENT_INFORMATION, &data);
+        assert_eq!(decoded, DhcpOption::RelayAgentInformation(vec![
+            RelaySubOption::CircuitId(b"p12".to_vec()),
+            RelaySubOption::RemoteId(vec![0xaa, 0xbb]),
+        ]));
+        assert_eq!(decoded.to_string(), "Circuit ID: p12; Remote ID: aa:bb");
+
+        // Une sous-option qui déborde rend toute l'option 82 illisible
+        let truncated = [RELAY_CIRCUIT_ID, 3, b'p', RELAY_REMOTE_ID, 2, 0xaa, 0xbb, RELAY_AGENT_FLAGS, 1];
+        assert_eq!(
+            DhcpOption::decode(OPTION_RELAY_AGENT_INFORMATION, &truncated),
+            DhcpOption::Other { code: OPTION_RELAY_AGENT_INFORMATION, data: truncated.to_vec() }
+        );
+        assert_eq!(
+            DhcpOption::decode(OPTION_RELAY_AGENT_INFORMATION, &[RELAY_CIRCUIT_ID]),
+            DhcpOption::Other { code: OPTION_RELAY_AGENT_INFORMATION, data: vec![RELAY_CIRCUIT_ID] }
+        );
+    }
+
+    #[test]
+    fn formats_identifiers_as_text_or_hex() {
+        assert_eq!(format_identifier(b"vlan 10 eth 1/1"), "vlan 10 eth 1/1");
+        assert_eq!(format_identifier(&[0x01, 0x41]), "01:41");
+        assert_eq!(format_identifier(&[]), "");
+    }
+}
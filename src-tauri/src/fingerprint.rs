@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+use crate::dhcp_capture::MacAddress;
+
+pub const FINGERPRINT_DATABASE_FILE: &str = "fingerprints.json";
+const EMBEDDED_DATABASE: &str = include_str!("../data/fingerprints.json");
+const DATABASE_VERSION: u32 = 1;
+
+#[derive(Error, Debug)]
+pub enum FingerprintError {
+    #[error("Impossible de lire {path}: {source}")]
+    Io { path: String, source: std::io::Error },
+    #[error("Base d'empreintes invalide: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("Version de base d'empreintes non supportée: {0}")]
+    UnsupportedVersion(u32),
+    #[error("L'empreinte \"{0}\" ne définit aucun critère")]
+    EmptyEntry(String),
+}
+
+// Signature d'un client: options 55, 60 et 57 telles qu'émises
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ClientFingerprint {
+    pub parameter_request_list: Vec<u8>,
+    pub vendor_class: Option<String>,
+    pub max_message_size: Option<u16>,
+}
+
+impl ClientFingerprint {
+    pub fn signature(&self) -> String {
+        self.parameter_request_list
+            .iter()
+            .map(|code| code.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+// Entrée de la base: les critères absents sont ignorés lors de la comparaison
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FingerprintEntry {
+    pub name: String,
+    pub category: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter_request_list: Option<Vec<u8>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vendor_class_prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_message_size: Option<u16>,
+}
+
+impl FingerprintEntry {
+    // Spécificité de la correspondance, None si un critère diffère
+    fn score(&self, fingerprint: &ClientFingerprint) -> Option<u32> {
+        let mut score = 0;
+        if let Some(ref list) = self.parameter_request_list {
+            if *list != fingerprint.parameter_request_list {
+                return None;
+            }
+            score += 4;
+        }
+        if let Some(ref prefix) = self.vendor_class_prefix {
+            let vendor_class = fingerprint.vendor_class.as_deref()?;
+            if !vendor_class.to_lowercase().starts_with(&prefix.to_lowercase()) {
+                return None;
+            }
+            score += 2;
+        }
+        if let Some(size) = self.max_message_size {
+            if fingerprint.max_message_size != Some(size) {
+                return None;
+            }
+            score += 1;
+        }
+        Some(score)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FingerprintDatabase {
+    pub version: u32,
+    pub fingerprints: Vec<FingerprintEntry>,
+}
+
+impl FingerprintDatabase {
+    pub fn embedded() -> Self {
+        Self::parse(EMBEDDED_DATABASE).expect("base d'empreintes embarquée invalide")
+    }
+
+    pub fn parse(json: &str) -> Result<Self, FingerprintError> {
+        let database: Self = serde_json::from_str(json)?;
+        if database.version != DATABASE_VERSION {
+            return Err(FingerprintError::UnsupportedVersion(database.version));
+        }
+        if let Some(entry) = database.fingerprints.iter().find(|entry| {
+            entry.parameter_request_list.is_none()
+                && entry.vendor_class_prefix.is_none()
+                && entry.max_message_size.is_none()
+        }) {
+            return Err(FingerprintError::EmptyEntry(entry.name.clone()));
+        }
+        Ok(database)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, FingerprintError> {
+        let json = fs::read_to_string(path).map_err(|source| FingerprintError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::parse(&json)
+    }
+
+    // Réécrit la base validée: la source peut être le fichier de destination lui-même
+    pub fn save(&self, path: &Path) -> Result<(), FingerprintError> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json).map_err(|source| FingerprintError::Io {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+
+    pub fn lookup(&self, fingerprint: &ClientFingerprint) -> Option<&FingerprintEntry> {
+        self.fingerprints
+            .iter()
+            .filter_map(|entry| entry.score(fingerprint).map(|score| (score, entry)))
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, entry)| entry)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnmatchedFingerprint {
+    pub fingerprint: ClientFingerprint,
+    pub signature: String,
+    pub count: u64,
+    pub first_seen: String,
+    pub example_chaddr: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceGuess {
+    pub name: String,
+    pub category: String,
+}
+
+pub struct FingerprintMatcher {
+    database: FingerprintDatabase,
+    unmatched: HashMap<ClientFingerprint, UnmatchedFingerprint>,
+}
+
+impl FingerprintMatcher {
+    pub fn new(database: FingerprintDatabase) -> Self {
+        Self {
+            database,
+            unmatched: HashMap::new(),
+        }
+    }
+
+    pub fn database_len(&self) -> usize {
+        self.database.fingerprints.len()
+    }
+
+    pub fn set_database(&mut self, database: FingerprintDatabase) {
+        self.database = database;
+        // Les empreintes inconnues peuvent désormais correspondre
+        self.unmatched.clear();
+    }
+
+    pub fn identify(&mut self, fingerprint: &ClientFingerprint, chaddr: MacAddress, timestamp: DateTime<Utc>) -> Option<DeviceGuess> {
+        if let Some(entry) = self.database.lookup(fingerprint) {
+            return Some(DeviceGuess {
+                name: entry.name.clone(),
+                category: entry.category.clone(),
+            });
+        }
+
+        self.unmatched
+            .entry(fingerprint.clone())
+            .or_insert_with(|| UnmatchedFingerprint {
+                fingerprint: fingerprint.clone(),
+                signature: fingerprint.signature(),
+                count: 0,
+                first_seen: timestamp.to_rfc3339(),
+                example_chaddr: chaddr.to_string(),
+            })
+            .count += 1;
+        None
+    }
+
+    pub fn unmatched(&self) -> Vec<UnmatchedFingerprint> {
+        let mut unmatched: Vec<UnmatchedFingerprint> = self.unmatched.values().cloned().collect();
+        unmatched.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.signature.cmp(&b.signature)));
+        unmatched
+    }
+
+    // Exporte les empreintes inconnues au format de la base, à compléter puis à recharger
+    pub fn export_unmatched(&self, path: &Path) -> Result<usize, FingerprintError> {
+        let unmatched = self.unmatched();
+        let export = FingerprintDatabase {
+            version: DATABASE_VERSION,
+            fingerprints: unmatched
+                .iter()
+                .map(|entry| FingerprintEntry {
+                    name: format!("Inconnu ({} occurrences, ex. {})", entry.count, entry.example_chaddr),
+                    category: String::new(),
+                    parameter_request_list: Some(entry.fingerprint.parameter_request_list.clone()),
+                    vendor_class_prefix: entry.fingerprint.vendor_class.clone(),
+                    max_message_size: entry.fingerprint.max_message_size,
+                })
+                .collect(),
+        };
+
+        let json = serde_json::to_string_pretty(&export)?;
+        fs::write(path, json).map_err(|source| FingerprintError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Ok(unmatched.len())
+    }
+
+    pub fn clear(&mut self) {
+        self.unmatched.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fingerprint(parameter_request_list: &[u8], vendor_class: Option<&str>, max_message_size: Option<u16>) -> ClientFingerprint {
+        ClientFingerprint {
+            parameter_request_list: parameter_request_list.to_vec(),
+            vendor_class: vendor_class.map(str::to_string),
+            max_message_size,
+        }
+    }
+
+    fn identify(fingerprint: &ClientFingerprint) -> Option<String> {
+        FingerprintDatabase::embedded().lookup(fingerprint).map(|entry| entry.name.clone())
+    }
+
+    #[test]
+    fn embedded_database_parses() {
+        let database = FingerprintDatabase::embedded();
+        assert_eq!(database.version, DATABASE_VERSION);
+        assert!(!database.fingerprints.is_empty());
+    }
+
+    #[test]
+    fn identifies_windows_by_option_55_order() {
+        let windows_10 = fingerprint(&[1, 3, 6, 15, 31, 33, 43, 44, 46, 47, 119, 121, 249, 252], Some("MSFT 5.0"), None);
+        assert_eq!(identify(&windows_10).as_deref(), Some("Windows 10/11"));
+
+        // Même liste dans l'ordre de Windows 7: l'ordre fait partie de l'empreinte
+        let windows_7 = fingerprint(&[1, 15, 3, 6, 44, 46, 47, 31, 33, 121, 249, 43, 252], Some("MSFT 5.0"), None);
+        assert_eq!(identify(&windows_7).as_deref(), Some("Windows 7/8"));
+
+        // Liste inconnue: seule la classe fournisseur correspond
+        let unknown_windows = fingerprint(&[1, 3, 6], Some("msft 5.0"), None);
+        assert_eq!(identify(&unknown_windows).as_deref(), Some("Windows"));
+    }
+
+    #[test]
+    fn identifies_ios_with_max_message_size() {
+        let iphone = fingerprint(&[1, 121, 3, 6, 15, 108, 114, 119, 252], None, Some(1500));
+        assert_eq!(identify(&iphone).as_deref(), Some("iOS 14+ (iPhone/iPad)"));
+        // Un critère présent dans l'entrée et différent exclut la correspondance
+        let other_size = fingerprint(&[1, 121, 3, 6, 15, 108, 114, 119, 252], None, Some(576));
+        assert_ne!(identify(&other_size).as_deref(), Some("iOS 14+ (iPhone/iPad)"));
+    }
+
+    #[test]
+    fn scores_more_specific_entries_higher() {
+        let entry = |parameter_request_list: Option<Vec<u8>>, vendor_class_prefix: Option<&str>| FingerprintEntry {
+            name: String::new(),
+            category: String::new(),
+            parameter_request_list,
+            vendor_class_prefix: vendor_class_prefix.map(str::to_string),
+            max_message_size: None,
+        };
+        let client = fingerprint(&[1, 3, 6], Some("MSFT 5.0"), Some(1500));
+        assert_eq!(entry(Some(vec![1, 3, 6]), Some("MSFT")).score(&client), Some(6));
+        assert_eq!(entry(None, Some("msft")).score(&client), Some(2));
+        assert_eq!(entry(Some(vec![1, 6, 3]), None).score(&client), None);
+        assert_eq!(entry(None, Some("MSFT")).score(&fingerprint(&[], None, None)), None);
+    }
+
+    #[test]
+    fn rejects_empty_entries_and_other_versions() {
+        assert!(matches!(
+            FingerprintDatabase::parse(r#"{"version": 2, "fingerprints": []}"#),
+            Err(FingerprintError::UnsupportedVersion(2))
+        ));
+        assert!(matches!(
+            FingerprintDatabase::parse(r#"{"version": 1, "fingerprints": [{"name": "vide", "category": ""}]}"#),
+            Err(FingerprintError::EmptyEntry(name)) if name == "vide"
+        ));
+    }
+
+    #[test]
+    fn saves_over_its_own_source() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(FINGERPRINT_DATABASE_FILE);
+        fs::write(&path, EMBEDDED_DATABASE).unwrap();
+
+        let database = FingerprintDatabase::load(&path).unwrap();
+        database.save(&path).unwrap();
+        let reloaded = FingerprintDatabase::load(&path).unwrap();
+        assert_eq!(reloaded.fingerprints.len(), database.fingerprints.len());
+    }
+}
@@ -0,0 +1,361 @@
+use std::collections::{BTreeMap, HashMap};
+use std::net::Ipv4Addr;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Serialize, Deserialize};
+
+// Histogramme log-linéaire façon HDR: 64 sous-intervalles par puissance de deux (erreur relative < 1,6 %)
+const SUB_BUCKET_BITS: u32 = 6;
+const SUB_BUCKET_COUNT: u64 = 1 << SUB_BUCKET_BITS;
+// Une journée de tranches d'une minute par groupe
+const MAX_MINUTE_BUCKETS: usize = 1440;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatencyKind {
+    DiscoverOffer,
+    RequestAck,
+    RequestNak,
+}
+
+// Délai de réponse mesuré par le suivi des transactions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencySample {
+    pub kind: LatencyKind,
+    pub latency: Duration,
+    pub server: Ipv4Addr,
+    pub giaddr: Ipv4Addr,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct LatencyHistogram {
+    counts: BTreeMap<u64, u64>,
+    total: u64,
+    sum_us: u64,
+    min_us: u64,
+    max_us: u64,
+}
+
+fn bucket_index(value: u64) -> u64 {
+    if value < SUB_BUCKET_COUNT {
+        return value;
+    }
+    let exponent = 63 - value.leading_zeros() as u64;
+    let shift = exponent - SUB_BUCKET_BITS as u64;
+    let sub_bucket = (value >> shift) & (SUB_BUCKET_COUNT - 1);
+    (shift + 1) * SUB_BUCKET_COUNT + sub_bucket
+}
+
+// Milieu de l'intervalle couvert par un index
+fn bucket_midpoint(index: u64) -> u64 {
+    if index < SUB_BUCKET_COUNT {
+        return index;
+    }
+    let shift = index / SUB_BUCKET_COUNT - 1;
+    let sub_bucket = index % SUB_BUCKET_COUNT;
+    let lower = (SUB_BUCKET_COUNT + sub_bucket) << shift;
+    lower + (1u64 << shift) / 2
+}
+
+impl LatencyHistogram {
+    fn record(&mut self, value_us: u64) {
+        *self.counts.entry(bucket_index(value_us)).or_insert(0) += 1;
+        if self.total == 0 || value_us < self.min_us {
+            self.min_us = value_us;
+        }
+        self.max_us = self.max_us.max(value_us);
+        self.total += 1;
+        self.sum_us = self.sum_us.saturating_add(value_us);
+    }
+
+    fn percentile(&self, quantile: f64) -> u64 {
+        let rank = ((quantile * self.total as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, count) in &self.counts {
+            seen += count;
+            if seen >= rank {
+                return bucket_midpoint(*index).clamp(self.min_us, self.max_us);
+            }
+        }
+        self.max_us
+    }
+
+    fn summary(&self) -> LatencySummary {
+        let to_ms = |us: u64| us as f64 / 1000.0;
+        LatencySummary {
+            count: self.total,
+            min_ms: to_ms(self.min_us),
+            max_ms: to_ms(self.max_us),
+            mean_ms: if self.total == 0 { 0.0 } else { to_ms(self.sum_us) / self.total as f64 },
+            p50_ms: to_ms(self.percentile(0.50)),
+            p90_ms: to_ms(self.percentile(0.90)),
+            p99_ms: to_ms(self.percentile(0.99)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub count: u64,
+    pub min_ms: f64,
+    pub max_ms: f64,
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p90_ms: f64,
+    pub p99_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinuteLatency {
+    pub minute: String,
+    pub summary: LatencySummary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyGroup {
+    // Serveur (option 54) ou relais (giaddr) selon le regroupement
+    pub key: String,
+    pub kind: LatencyKind,
+    pub overall: LatencySummary,
+    pub minutes: Vec<MinuteLatency>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyReport {
+    pub by_server: Vec<LatencyGroup>,
+    pub by_relay: Vec<LatencyGroup>,
+}
+
+#[derive(Default)]
+struct GroupHistograms {
+    overall: LatencyHistogram,
+    minutes: BTreeMap<DateTime<Utc>, LatencyHistogram>,
+}
+
+impl GroupHistograms {
+    fn record(&mut self, minute: DateTime<Utc>, value_us: u64) {
+        self.overall.record(value_us);
+        self.minutes.entry(minute).or_default().record(value_us);
+        while self.minutes.len() > MAX_MINUTE_BUCKETS {
+            self.minutes.pop_first();
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LatencyStats {
+    by_server: HashMap<(Ipv4Addr, LatencyKind), GroupHistograms>,
+    by_relay: HashMap<(Ipv4Addr, LatencyKind), GroupHistograms>,
+}
+
+impl LatencyStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, sample: &LatencySample) {
+        // Horloges incohérentes (fichiers fusionnés): ignorer les délais négatifs
+        let Some(value_us) = sample.latency.num_microseconds().and_then(|us| u64::try_from(us).ok()) else {
+            return;
+        };
+        let minute = sample.timestamp
+            .duration_trunc(Duration::minutes(1))
+            .unwrap_or(sample.timestamp);
+
+        self.by_server
+            .entry((sample.server, sample.kind))
+            .or_default()
+            .record(minute, value_us);
+
+        // Le trafic non relayé n'a pas de giaddr
+        if sample.giaddr != Ipv4Addr::UNSPECIFIED {
+            self.by_relay
+                .entry((sample.giaddr, sample.kind))
+                .or_default()
+                .record(minute, value_us);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.by_server.clear();
+        self.by_relay.clear();
+    }
+
+    pub fn report(&self) -> LatencyReport {
+        LatencyReport {
+            by_server: Self::groups(&self.by_server),
+            by_relay: Self::groups(&self.by_relay),
+        }
+    }
+
+    fn groups(histograms: &HashMap<(Ipv4Addr, LatencyKind), GroupHistograms>) -> Vec<LatencyGroup> {
+        let mut keys: Vec<&(Ipv4Addr, LatencyKind)> = histograms.keys().collect();
+        keys.sort();
+        keys.into_iter()
+            .map(|key| {
+                let group = &histograms[key];
+                LatencyGroup {
+                    key: key.0.to_string(),
+                    kind: key.1,
+                    overall: group.overall.summary(),
+                    minutes: group.minutes
+                        .iter()
+                        .map(|(minute, histogram)| MinuteLatency {
+                            minute: minute.to_rfc3339(),
+                            summary: histogram.summary(),
+                        })
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(latency_ms: i64, seconds: i64) -> LatencySample {
+        LatencySample {
+            kind: LatencyKind::DiscoverOffer,
+            latency: Duration::milliseconds(latency_ms),
+            server: Ipv4Addr::new(192, 168, 1, 1),
+            giaddr: Ipv4Addr::UNSPECIFIED,
+            timestamp: DateTime::<Utc>::from_timestamp(1_700_000_000 + seconds, 0).unwrap(),
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        // Tolérance de l'histogramme: un sous-intervalle sur 64
+        assert!((actual - expected).abs() <= expected / 64.0, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn bucket_index_is_exact_below_sub_bucket_count() {
+        for value in 0..SUB_BUCKET_COUNT {
+            assert_eq!(bucket_index(value), value);
+            assert_eq!(bucket_midpoint(value), value);
+        }
+        assert_eq!(bucket_index(64), 64);
+        assert_eq!(bucket_index(127), 127);
+        // Au-delà de 128, chaque intervalle couvre deux valeurs
+        assert_eq!(bucket_index(128), 128);
+        assert_eq!(bucket_index(129), 128);
+        assert_eq!(bucket_index(130), 129);
+        assert_eq!(bucket_midpoint(128), 129);
+    }
+
+    #[test]
+    fn bucket_midpoint_stays_within_relative_error() {
+        let mut previous = 0;
+        for value in (0..10_000_000u64).step_by(997) {
+            let index = bucket_index(value);
+            assert!(index >= previous, "index non monotone pour {}", value);
+            previous = index;
+
+            let midpoint = bucket_midpoint(index);
+            assert_eq!(bucket_index(midpoint), index, "milieu hors de l'intervalle pour {}", value);
+            assert!(midpoint.abs_diff(value) * SUB_BUCKET_COUNT <= value.max(1), "{} -> {}", value, midpoint);
+        }
+    }
+
+    #[test]
+    fn identical_values_give_exact_percentiles() {
+        let mut histogram = LatencyHistogram::default();
+        for _ in 0..100 {
+            histogram.record(5_000);
+        }
+        let summary = histogram.summary();
+
+        assert_eq!(summary.count, 100);
+        assert_eq!(summary.min_ms, 5.0);
+        assert_eq!(summary.max_ms, 5.0);
+        assert_eq!(summary.mean_ms, 5.0);
+        assert_eq!(summary.p50_ms, 5.0);
+        assert_eq!(summary.p90_ms, 5.0);
+        assert_eq!(summary.p99_ms, 5.0);
+    }
+
+    #[test]
+    fn uniform_values_give_expected_percentiles() {
+        let mut histogram = LatencyHistogram::default();
+        for ms in 1..=1000u64 {
+            histogram.record(ms * 1000);
+        }
+        let summary = histogram.summary();
+
+        assert_eq!(summary.count, 1000);
+        assert_eq!(summary.min_ms, 1.0);
+        assert_eq!(summary.max_ms, 1000.0);
+        assert_close(summary.mean_ms, 500.5);
+        assert_close(summary.p50_ms, 500.0);
+        assert_close(summary.p90_ms, 900.0);
+        assert_close(summary.p99_ms, 990.0);
+    }
+
+    #[test]
+    fn empty_histogram_summary_is_zero() {
+        let summary = LatencyHistogram::default().summary();
+        assert_eq!(summary.count, 0);
+        assert_eq!(summary.mean_ms, 0.0);
+        assert_eq!(summary.p99_ms, 0.0);
+    }
+
+    #[test]
+    fn samples_are_bucketed_per_minute() {
+        let mut stats = LatencyStats::new();
+        // 1_700_000_000 = 22:13:20, la minute suivante commence 40 s plus tard
+        stats.record(&sample(10, 0));
+        stats.record(&sample(20, 39));
+        stats.record(&sample(30, 40));
+        stats.record(&sample(40, 99));
+        stats.record(&sample(50, 100));
+
+        let report = stats.report();
+        assert_eq!(report.by_server.len(), 1);
+        let group = &report.by_server[0];
+        assert_eq!(group.key, "192.168.1.1");
+        assert_eq!(group.overall.count, 5);
+
+        let minutes: Vec<(&str, u64)> = group.minutes
+            .iter()
+            .map(|minute| (minute.minute.as_str(), minute.summary.count))
+            .collect();
+        assert_eq!(minutes, vec![
+            ("2023-11-14T22:13:00+00:00", 2),
+            ("2023-11-14T22:14:00+00:00", 2),
+            ("2023-11-14T22:15:00+00:00", 1),
+        ]);
+        assert_eq!(group.minutes[1].summary.min_ms, 30.0);
+        assert_eq!(group.minutes[1].summary.max_ms, 40.0);
+    }
+
+    #[test]
+    fn minute_buckets_are_capped() {
+        let mut stats = LatencyStats::new();
+        for minute in 0..(MAX_MINUTE_BUCKETS as i64 + 10) {
+            stats.record(&sample(10, minute * 60));
+        }
+
+        let group = &stats.report().by_server[0];
+        assert_eq!(group.minutes.len(), MAX_MINUTE_BUCKETS);
+        // Les tranches les plus anciennes sont abandonnées, pas le total
+        assert_eq!(group.overall.count, MAX_MINUTE_BUCKETS as u64 + 10);
+        assert_eq!(group.minutes[0].minute, "2023-11-14T22:23:00+00:00");
+    }
+
+    #[test]
+    fn relay_groups_and_negative_latencies() {
+        let mut stats = LatencyStats::new();
+        stats.record(&sample(10, 0));
+        stats.record(&LatencySample { giaddr: Ipv4Addr::new(10, 0, 0, 1), ..sample(20, 0) });
+        // Horloges incohérentes: délai négatif ignoré
+        stats.record(&sample(-5, 0));
+
+        let report = stats.report();
+        assert_eq!(report.by_server[0].overall.count, 2);
+        assert_eq!(report.by_relay.len(), 1);
+        assert_eq!(report.by_relay[0].key, "10.0.0.1");
+        assert_eq!(report.by_relay[0].overall.count, 1);
+    }
+}
@@ -0,0 +1,406 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use crate::dhcp_capture::{DHCPMessageType, DHCPPacket, MacAddress};
+use crate::dhcp_options::format_identifier;
+
+// Durée de bail "infinie" (RFC 2132 §9.2)
+const INFINITE_LEASE: u32 = 0xffff_ffff;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaseState {
+    Active,
+    Expired,
+    Released,
+    Declined,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lease {
+    pub ip: String,
+    pub chaddr: String,
+    pub client_id: Option<String>,
+    pub hostname: Option<String>,
+    pub server: String,
+    pub state: LeaseState,
+    pub lease_time: Option<u32>,
+    pub renewal_time: Option<u32>,
+    pub rebinding_time: Option<u32>,
+    pub granted_at: String,
+    pub last_renewed: String,
+    pub expires_at: Option<String>,
+    pub renew_count: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaseSortField {
+    #[default]
+    Ip,
+    Chaddr,
+    Hostname,
+    Server,
+    LastRenewed,
+    ExpiresAt,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LeaseQuery {
+    #[serde(default)]
+    pub state: Option<LeaseState>,
+    #[serde(default)]
+    pub server: Option<String>,
+    // Recherche libre sur l'IP, la MAC, le client-id et le nom d'hôte
+    #[serde(default)]
+    pub search: Option<String>,
+    #[serde(default)]
+    pub sort_by: LeaseSortField,
+    #[serde(default)]
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone)]
+struct LeaseEntry {
+    ip: Ipv4Addr,
+    chaddr: MacAddress,
+    client_id: Option<String>,
+    hostname: Option<String>,
+    server: Ipv4Addr,
+    lease_time: Option<u32>,
+    renewal_time: Option<u32>,
+    rebinding_time: Option<u32>,
+    granted_at: DateTime<Utc>,
+    last_renewed: DateTime<Utc>,
+    // Fin de bail révoquée par un RELEASE ou un DECLINE
+    ended: Option<LeaseState>,
+    renew_count: u32,
+}
+
+impl LeaseEntry {
+    fn expires_at(&self) -> Option<DateTime<Utc>> {
+        match self.lease_time {
+            Some(INFINITE_LEASE) | None => None,
+            Some(seconds) => Some(self.last_renewed + Duration::seconds(seconds as i64)),
+        }
+    }
+
+    fn state(&self, now: DateTime<Utc>) -> LeaseState {
+        if let Some(ended) = self.ended {
+            return ended;
+        }
+        match self.expires_at() {
+            Some(expires_at) if expires_at <= now => LeaseState::Expired,
+            _ => LeaseState::Active,
+        }
+    }
+
+    fn to_lease(&self, now: DateTime<Utc>) -> Lease {
+        Lease {
+            ip: self.ip.to_string(),
+            chaddr: self.chaddr.to_string(),
+            client_id: self.client_id.clone(),
+            hostname: self.hostname.clone(),
+            server: self.server.to_string(),
+            state: self.state(now),
+            lease_time: self.lease_time,
+            renewal_time: self.renewal_time,
+            rebinding_time: self.rebinding_time,
+            granted_at: self.granted_at.to_rfc3339(),
+            last_renewed: self.last_renewed.to_rfc3339(),
+            expires_at: self.expires_at().map(|expires_at| expires_at.to_rfc3339()),
+            renew_count: self.renew_count,
+        }
+    }
+
+    fn matches(&self, query: &LeaseQuery, now: DateTime<Utc>) -> bool {
+        if query.state.is_some_and(|state| state != self.state(now)) {
+            return false;
+        }
+        if let Some(server) = query.server.as_deref() {
+            if self.server.to_string() != server {
+                return false;
+            }
+        }
+        let Some(search) = query.search.as_deref().map(str::to_lowercase) else {
+            return true;
+        };
+        self.ip.to_string().contains(&search)
+            || self.chaddr.to_string().contains(&search)
+            || self.client_id.as_deref().is_some_and(|id| id.to_lowercase().contains(&search))
+            || self.hostname.as_deref().is_some_and(|name| name.to_lowercase().contains(&search))
+    }
+
+    fn compare(&self, other: &Self, field: LeaseSortField) -> Ordering {
+        let ordering = match field {
+            LeaseSortField::Ip => Ordering::Equal,
+            LeaseSortField::Chaddr => self.chaddr.cmp(&other.chaddr),
+            LeaseSortField::Hostname => self.hostname.cmp(&other.hostname),
+            LeaseSortField::Server => self.server.cmp(&other.server),
+            LeaseSortField::LastRenewed => self.last_renewed.cmp(&other.last_renewed),
+            LeaseSortField::ExpiresAt => self.expires_at().cmp(&other.expires_at()),
+        };
+        ordering.then_with(|| self.ip.cmp(&other.ip))
+    }
+}
+
+// Vue passive de la base de baux des serveurs, reconstruite à partir des Ack observés
+#[derive(Default)]
+pub struct LeaseTable {
+    leases: HashMap<Ipv4Addr, LeaseEntry>,
+}
+
+impl LeaseTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, packet: &DHCPPacket) {
+        match packet.message_type {
+            DHCPMessageType::Ack => self.record_ack(packet),
+            // Le client libère l'adresse portée dans ciaddr
+            DHCPMessageType::Release => self.end_lease(packet.bootp.ciaddr, packet.bootp.chaddr, LeaseState::Released),
+            // Le client refuse l'adresse demandée en option 50
+            DHCPMessageType::Decline => {
+                if let Some(ip) = packet.option_50 {
+                    self.end_lease(ip, packet.bootp.chaddr, LeaseState::Declined);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn record_ack(&mut self, packet: &DHCPPacket) {
+        let ip = packet.bootp.yiaddr;
+        // Ack d'un INFORM: aucune adresse attribuée
+        if ip == Ipv4Addr::UNSPECIFIED {
+            return;
+        }
+
+        let timestamp = packet.timestamp;
+        let chaddr = packet.bootp.chaddr;
+        let entry = self.leases.entry(ip).or_insert_with(|| LeaseEntry {
+            ip,
+            chaddr,
+            client_id: None,
+            hostname: None,
+            server: packet.server_address(),
+            lease_time: None,
+            renewal_time: None,
+            rebinding_time: None,
+            granted_at: timestamp,
+            last_renewed: timestamp,
+            ended: None,
+            renew_count: 0,
+        });
+
+        // Adresse réattribuée à un autre client ou après la fin du bail précédent
+        if entry.chaddr != chaddr || entry.ended.is_some() {
+            entry.chaddr = chaddr;
+            entry.client_id = None;
+            entry.hostname = None;
+            entry.granted_at = timestamp;
+            entry.ended = None;
+            entry.renew_count = 0;
+        } else if timestamp > entry.granted_at {
+            entry.renew_count += 1;
+        }
+
+        entry.server = packet.server_address();
+        entry.last_renewed = timestamp;
+        entry.lease_time = packet.lease_time();
+        entry.renewal_time = packet.renewal_time();
+        entry.rebinding_time = packet.rebinding_time();
+        if let Some(client_id) = packet.client_identifier() {
+            entry.client_id = Some(format_identifier(client_id));
+        }
+        if let Some(hostname) = packet.host_name() {
+            entry.hostname = Some(hostname.to_string());
+        }
+    }
+
+    fn end_lease(&mut self, ip: Ipv4Addr, chaddr: MacAddress, state: LeaseState) {
+        if let Some(entry) = self.leases.get_mut(&ip).filter(|entry| entry.chaddr == chaddr) {
+            entry.ended = Some(state);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.leases.clear();
+    }
+
+    pub fn query(&self, query: &LeaseQuery, now: DateTime<Utc>) -> Vec<Lease> {
+        let mut entries: Vec<&LeaseEntry> = self.leases
+            .values()
+            .filter(|entry| entry.matches(query, now))
+            .collect();
+
+        entries.sort_by(|a, b| {
+            let ordering = a.compare(b, query.sort_by);
+            if query.descending { ordering.reverse() } else { ordering }
+        });
+
+        entries.into_iter().map(|entry| entry.to_lease(now)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dhcp_capture::test_support::{at, mac, packet, SERVER_IP};
+    use crate::dhcp_options::DhcpOption;
+
+    fn ip(last: u8) -> Ipv4Addr {
+        Ipv4Addr::new(192, 168, 1, last)
+    }
+
+    fn ack(chaddr: MacAddress, yiaddr: Ipv4Addr, lease_time: u32, offset_ms: i64) -> DHCPPacket {
+        let mut packet = packet(DHCPMessageType::Ack, chaddr, 1, offset_ms);
+        packet.bootp.yiaddr = yiaddr;
+        packet.options.extend([
+            DhcpOption::LeaseTime(lease_time),
+            DhcpOption::RenewalTime(lease_time / 2),
+            DhcpOption::RebindingTime(lease_time / 8 * 7),
+        ]);
+        packet
+    }
+
+    fn all(table: &LeaseTable, now: DateTime<Utc>) -> Vec<Lease> {
+        table.query(&LeaseQuery::default(), now)
+    }
+
+    #[test]
+    fn ack_creates_a_lease_and_renewals_extend_it() {
+        let mut table = LeaseTable::new();
+        let mut first = ack(mac(1), ip(10), 3600, 0);
+        first.options.push(DhcpOption::HostName("laptop".to_string()));
+        first.options.push(DhcpOption::ClientIdentifier(vec![1, 0, 0x11, 0x22, 0x33, 0x44, 0x01]));
+        table.record(&first);
+
+        let lease = &all(&table, at(0))[0];
+        assert_eq!(lease.ip, "192.168.1.10");
+        assert_eq!(lease.chaddr, mac(1).to_string());
+        assert_eq!(lease.server, SERVER_IP.to_string());
+        assert_eq!(lease.hostname.as_deref(), Some("laptop"));
+        assert_eq!(lease.client_id.as_deref(), Some("01:00:11:22:33:44:01"));
+        assert_eq!((lease.lease_time, lease.renewal_time, lease.rebinding_time), (Some(3600), Some(1800), Some(3150)));
+        assert_eq!(lease.expires_at, Some((at(0) + Duration::seconds(3600)).to_rfc3339()));
+        assert_eq!(lease.state, LeaseState::Active);
+        assert_eq!(lease.renew_count, 0);
+
+        // Renouvellement à mi-bail: l'échéance repart de l'Ack, le nom d'hôte est conservé
+        table.record(&ack(mac(1), ip(10), 3600, 1_800_000));
+        let lease = &all(&table, at(1_800_000))[0];
+        assert_eq!(lease.renew_count, 1);
+        assert_eq!(lease.granted_at, at(0).to_rfc3339());
+        assert_eq!(lease.last_renewed, at(1_800_000).to_rfc3339());
+        assert_eq!(lease.expires_at, Some((at(1_800_000) + Duration::seconds(3600)).to_rfc3339()));
+        assert_eq!(lease.hostname.as_deref(), Some("laptop"));
+    }
+
+    #[test]
+    fn state_is_computed_against_the_given_clock() {
+        let mut table = LeaseTable::new();
+        table.record(&ack(mac(1), ip(10), 60, 0));
+        table.record(&ack(mac(2), ip(11), INFINITE_LEASE, 0));
+
+        let states = |now| all(&table, now).into_iter().map(|lease| lease.state).collect::<Vec<_>>();
+        assert_eq!(states(at(59_999)), vec![LeaseState::Active, LeaseState::Active]);
+        assert_eq!(states(at(60_000)), vec![LeaseState::Expired, LeaseState::Active]);
+        // Un bail infini n'expire jamais
+        assert_eq!(all(&table, at(0))[1].expires_at, None);
+    }
+
+    #[test]
+    fn release_and_decline_end_the_clients_lease_only() {
+        let mut table = LeaseTable::new();
+        table.record(&ack(mac(1), ip(10), 3600, 0));
+        table.record(&ack(mac(2), ip(11), 3600, 0));
+
+        // Un autre client ne peut pas libérer le bail
+        let mut release = packet(DHCPMessageType::Release, mac(3), 2, 1000);
+        release.bootp.ciaddr = ip(10);
+        table.record(&release);
+        assert_eq!(all(&table, at(1000))[0].state, LeaseState::Active);
+
+        let mut release = packet(DHCPMessageType::Release, mac(1), 2, 1000);
+        release.bootp.ciaddr = ip(10);
+        table.record(&release);
+
+        let mut decline = packet(DHCPMessageType::Decline, mac(2), 3, 1000);
+        decline.option_50 = Some(ip(11));
+        table.record(&decline);
+
+        let states: Vec<LeaseState> = all(&table, at(1000)).into_iter().map(|lease| lease.state).collect();
+        assert_eq!(states, vec![LeaseState::Released, LeaseState::Declined]);
+        // La fin explicite prime sur l'expiration
+        assert_eq!(all(&table, at(7_200_000))[0].state, LeaseState::Released);
+    }
+
+    #[test]
+    fn reassignment_starts_a_new_lease() {
+        let mut table = LeaseTable::new();
+        let mut first = ack(mac(1), ip(10), 3600, 0);
+        first.options.push(DhcpOption::HostName("old".to_string()));
+        table.record(&first);
+        table.record(&ack(mac(1), ip(10), 3600, 1000));
+
+        table.record(&ack(mac(2), ip(10), 3600, 5000));
+        let lease = &all(&table, at(5000))[0];
+        assert_eq!(lease.chaddr, mac(2).to_string());
+        assert_eq!(lease.hostname, None);
+        assert_eq!(lease.renew_count, 0);
+        assert_eq!(lease.granted_at, at(5000).to_rfc3339());
+
+        // Nouvel Ack après un RELEASE: bail neuf pour le même client
+        let mut release = packet(DHCPMessageType::Release, mac(2), 2, 6000);
+        release.bootp.ciaddr = ip(10);
+        table.record(&release);
+        table.record(&ack(mac(2), ip(10), 3600, 7000));
+        let lease = &all(&table, at(7000))[0];
+        assert_eq!(lease.state, LeaseState::Active);
+        assert_eq!(lease.granted_at, at(7000).to_rfc3339());
+    }
+
+    #[test]
+    fn inform_ack_is_ignored() {
+        let mut table = LeaseTable::new();
+        table.record(&ack(mac(1), Ipv4Addr::UNSPECIFIED, 3600, 0));
+        assert!(all(&table, at(0)).is_empty());
+    }
+
+    #[test]
+    fn query_filters_and_sorts() {
+        let mut table = LeaseTable::new();
+        let mut printer = ack(mac(3), ip(30), 600, 2000);
+        printer.options.push(DhcpOption::HostName("Printer-2F".to_string()));
+        table.record(&printer);
+        table.record(&ack(mac(1), ip(2), 3600, 0));
+        table.record(&ack(mac(2), ip(100), 60, 1000));
+
+        let ips = |query: LeaseQuery, now| table.query(&query, now).into_iter().map(|lease| lease.ip).collect::<Vec<_>>();
+
+        assert_eq!(ips(LeaseQuery::default(), at(0)), vec!["192.168.1.2", "192.168.1.30", "192.168.1.100"]);
+        assert_eq!(
+            ips(LeaseQuery { sort_by: LeaseSortField::ExpiresAt, descending: true, ..Default::default() }, at(0)),
+            vec!["192.168.1.2", "192.168.1.30", "192.168.1.100"]
+        );
+        assert_eq!(
+            ips(LeaseQuery { sort_by: LeaseSortField::LastRenewed, ..Default::default() }, at(0)),
+            vec!["192.168.1.2", "192.168.1.100", "192.168.1.30"]
+        );
+
+        // Recherche insensible à la casse sur le nom d'hôte, puis sur la MAC
+        assert_eq!(ips(LeaseQuery { search: Some("printer".to_string()), ..Default::default() }, at(0)), vec!["192.168.1.30"]);
+        assert_eq!(ips(LeaseQuery { search: Some(mac(2).to_string()), ..Default::default() }, at(0)), vec!["192.168.1.100"]);
+
+        assert_eq!(
+            ips(LeaseQuery { state: Some(LeaseState::Expired), ..Default::default() }, at(61_000)),
+            vec!["192.168.1.100"]
+        );
+        assert!(ips(LeaseQuery { server: Some("10.0.0.1".to_string()), ..Default::default() }, at(0)).is_empty());
+        assert_eq!(ips(LeaseQuery { server: Some(SERVER_IP.to_string()), ..Default::default() }, at(0)).len(), 3);
+    }
+}
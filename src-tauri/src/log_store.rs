@@ -0,0 +1,814 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+use crate::dhcp_capture::MacAddress;
+use crate::DHCPLog;
+
+const OPTION_HOSTNAME: u8 = 12;
+
+// Bornes du champ "Nombre maximum de logs" des paramètres
+pub const DEFAULT_LOG_CAPACITY: usize = 1000;
+pub const MIN_LOG_CAPACITY: usize = 100;
+pub const MAX_LOG_CAPACITY: usize = 10_000;
+// Journal JSON Lines des logs évincés, dans le dossier de données de l'application
+pub const SPILL_FILE: &str = "evicted_logs.jsonl";
+// Au-delà, le journal est renommé en SPILL_FILE.1 (remplaçant le précédent) et recommencé
+const MAX_SPILL_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum LogQueryError {
+    #[error("Adresse ou réseau invalide: {0}")]
+    Network(String),
+    #[error("{0}")]
+    Mac(String),
+    #[error("Horodatage invalide: {0}")]
+    Timestamp(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSortField {
+    #[default]
+    Timestamp,
+    PacketType,
+    SourceIp,
+    DestinationIp,
+    Chaddr,
+    Xid,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogQuery {
+    // Types de message acceptés (Discover, Offer...), sans distinction de casse
+    #[serde(default)]
+    pub message_types: Vec<String>,
+    // Adresse ou réseau CIDR, comparé à toutes les adresses IP du paquet
+    #[serde(default)]
+    pub ip: Option<String>,
+    // chaddr ou MAC Ethernet source/destination
+    #[serde(default)]
+    pub mac: Option<String>,
+    // Codes d'option qui doivent tous être présents
+    #[serde(default)]
+    pub options: Vec<u8>,
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    // Recherche libre sur le nom d'hôte (option 12)
+    #[serde(default)]
+    pub hostname: Option<String>,
+    // Sous-chaîne cherchée dans le type, les adresses IP et MAC, le xid et le nom d'hôte; non indexée
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub sort_by: LogSortField,
+    #[serde(default)]
+    pub descending: bool,
+    #[serde(default)]
+    pub offset: usize,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogPage {
+    pub total: usize,
+    pub logs: Vec<DHCPLog>,
+}
+
+// Listes d'ids triées par clé, alimentées à l'insertion
+#[derive(Default)]
+struct LogIndexes {
+    by_type: HashMap<String, Vec<u64>>,
+    by_ip: BTreeMap<Ipv4Addr, Vec<u64>>,
+    by_mac: HashMap<MacAddress, Vec<u64>>,
+    by_option: HashMap<u8, Vec<u64>>,
+    by_hostname: BTreeMap<String, Vec<u64>>,
+}
+
+impl LogIndexes {
+    fn insert(&mut self, log: &DHCPLog) {
+        self.by_type.entry(log.packet_type.to_lowercase()).or_default().push(log.id);
+
+        let mut ips: Vec<Ipv4Addr> = [&log.source_ip, &log.destination_ip, &log.ciaddr, &log.yiaddr, &log.siaddr, &log.giaddr]
+            .into_iter()
+            .chain(log.option_50.as_ref())
+            .filter_map(|ip| ip.parse().ok())
+            .filter(|ip| *ip != Ipv4Addr::UNSPECIFIED)
+            .collect();
+        ips.sort_unstable();
+        ips.dedup();
+        for ip in ips {
+            self.by_ip.entry(ip).or_default().push(log.id);
+        }
+
+        let mut macs: Vec<MacAddress> = std::iter::once(&log.chaddr)
+            .chain(log.source_mac.as_ref())
+            .chain(log.destination_mac.as_ref())
+            .filter_map(|mac| mac.parse().ok())
+            .collect();
+        macs.sort_unstable();
+        macs.dedup();
+        for mac in macs {
+            self.by_mac.entry(mac).or_default().push(log.id);
+        }
+
+        let mut codes: Vec<u8> = log.options.iter().map(|option| option.code).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        for code in codes {
+            self.by_option.entry(code).or_default().push(log.id);
+        }
+
+        if let Some(hostname) = log.options.iter().find(|option| option.code == OPTION_HOSTNAME) {
+            self.by_hostname.entry(hostname.value.to_lowercase()).or_default().push(log.id);
+        }
+    }
+
+    // Retire les ids évincés du tampon; les listes sont triées
+    fn prune(&mut self, first: u64) {
+        let prune = |ids: &mut Vec<u64>| {
+            ids.drain(..ids.partition_point(|id| *id < first));
+            !ids.is_empty()
+        };
+        self.by_type.retain(|_, ids| prune(ids));
+        self.by_ip.retain(|_, ids| prune(ids));
+        self.by_mac.retain(|_, ids| prune(ids));
+        self.by_option.retain(|_, ids| prune(ids));
+        self.by_hostname.retain(|_, ids| prune(ids));
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// Ajoute les logs évincés à un fichier JSON Lines
+struct LogSpill {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    // Taille du fichier courant, tampon compris
+    bytes: u64,
+    max_bytes: u64,
+}
+
+impl LogSpill {
+    fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            bytes: file.metadata()?.len(),
+            writer: BufWriter::new(file),
+            max_bytes: MAX_SPILL_BYTES,
+        })
+    }
+
+    fn write(&mut self, log: &DHCPLog) -> io::Result<()> {
+        let mut line = serde_json::to_vec(log)?;
+        line.push(b'\n');
+        if self.bytes > 0 && self.bytes + line.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        self.writer.write_all(&line)?;
+        self.bytes += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        fs::rename(&self.path, rotated_path(&self.path))?;
+        let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.writer = BufWriter::new(file);
+        self.bytes = 0;
+        Ok(())
+    }
+}
+
+impl Drop for LogSpill {
+    fn drop(&mut self) {
+        if let Err(e) = self.writer.flush() {
+            eprintln!("❌ Écriture de {} impossible: {}", self.path.display(), e);
+        }
+    }
+}
+
+fn rotated_path(path: &Path) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(".1");
+    PathBuf::from(rotated)
+}
+
+// Réunion de listes triées
+fn union<'a>(lists: impl Iterator<Item = &'a Vec<u64>>) -> Vec<u64> {
+    let mut ids: Vec<u64> = lists.flatten().copied().collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+// Intersection en partant de la liste la plus courte
+fn intersect(mut lists: Vec<Vec<u64>>) -> Vec<u64> {
+    lists.sort_by_key(|ids| ids.len());
+    let mut lists = lists.into_iter();
+    let Some(mut ids) = lists.next() else {
+        return Vec::new();
+    };
+    for other in lists {
+        ids.retain(|id| other.binary_search(id).is_ok());
+    }
+    ids
+}
+
+fn parse_network(value: &str) -> Result<(Ipv4Addr, Ipv4Addr), LogQueryError> {
+    let invalid = || LogQueryError::Network(value.to_string());
+    let (address, prefix) = match value.trim().split_once('/') {
+        Some((address, prefix)) => (address, prefix.parse::<u32>().map_err(|_| invalid())?),
+        None => (value.trim(), 32),
+    };
+    if prefix > 32 {
+        return Err(invalid());
+    }
+    let address: Ipv4Addr = address.parse().map_err(|_| invalid())?;
+    let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+    let first = u32::from(address) & mask;
+    Ok((Ipv4Addr::from(first), Ipv4Addr::from(first | !mask)))
+}
+
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, LogQueryError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| LogQueryError::Timestamp(value.to_string()))
+}
+
+// `text` est déjà en minuscules
+fn matches_text(log: &DHCPLog, text: &str) -> bool {
+    [&log.packet_type, &log.source_ip, &log.destination_ip, &log.chaddr, &log.xid]
+        .into_iter()
+        .chain(log.option_50.as_ref())
+        .chain(log.source_mac.as_ref())
+        .chain(log.options.iter().filter(|option| option.code == OPTION_HOSTNAME).map(|option| &option.value))
+        .any(|field| field.to_lowercase().contains(text))
+}
+
+fn compare_ip(a: &str, b: &str) -> Ordering {
+    match (a.parse::<Ipv4Addr>(), b.parse::<Ipv4Addr>()) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+// Lot de logs remis au frontend; `cursor` est l'id du dernier log connu.
+// Un changement de `session` signale que les logs détenus par le frontend ont été effacés.
+// Le frontend ne garde que les `capacity` derniers logs, comme le backend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogBatch {
+    pub session: u64,
+    pub cursor: u64,
+    pub capacity: usize,
+    pub overwritten: u64,
+    pub logs: Vec<DHCPLog>,
+}
+
+// État du tampon au moment d'une insertion, repris dans les lots émis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogWindow {
+    pub session: u64,
+    pub capacity: usize,
+    pub overwritten: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogSettings {
+    pub max_logs: usize,
+    #[serde(default)]
+    pub spill_to_disk: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogStatus {
+    pub count: usize,
+    pub capacity: usize,
+    pub overwritten: u64,
+    pub spill_path: Option<String>,
+}
+
+// Logs de la session dans un tampon circulaire; les ids restent croissants après un effacement
+// pour que les curseurs détenus par le frontend ne désignent jamais un autre paquet
+pub struct LogStore {
+    logs: VecDeque<DHCPLog>,
+    // Horodatages décodés, alignés sur `logs` et croissants comme eux: une plage se
+    // résout par recherche dichotomique
+    timestamps: VecDeque<DateTime<Utc>>,
+    indexes: LogIndexes,
+    next_id: u64,
+    session: u64,
+    capacity: usize,
+    // Logs évincés depuis le dernier effacement
+    overwritten: u64,
+    // Évictions pas encore retirées des index
+    stale: usize,
+    spill: Option<LogSpill>,
+}
+
+impl Default for LogStore {
+    fn default() -> Self {
+        Self {
+            logs: VecDeque::new(),
+            timestamps: VecDeque::new(),
+            indexes: LogIndexes::default(),
+            next_id: 0,
+            session: 0,
+            capacity: DEFAULT_LOG_CAPACITY,
+            overwritten: 0,
+            stale: 0,
+            spill: None,
+        }
+    }
+}
+
+impl LogStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, mut log: DHCPLog) -> DHCPLog {
+        if self.logs.len() >= self.capacity {
+            self.evict();
+        }
+        self.next_id += 1;
+        log.id = self.next_id;
+        self.indexes.insert(&log);
+        // Un horodatage illisible reprend le précédent pour garder la suite croissante
+        let timestamp = parse_timestamp(&log.timestamp)
+            .ok()
+            .or_else(|| self.timestamps.back().copied())
+            .unwrap_or_default();
+        self.timestamps.push_back(timestamp);
+        self.logs.push_back(log.clone());
+        log
+    }
+
+    fn evict(&mut self) {
+        let Some(log) = self.logs.pop_front() else {
+            return;
+        };
+        self.timestamps.pop_front();
+        self.overwritten += 1;
+
+        if let Some(spill) = self.spill.as_mut() {
+            if let Err(e) = spill.write(&log) {
+                eprintln!("❌ Écriture de {} impossible, débordement désactivé: {}", spill.path.display(), e);
+                self.spill = None;
+            }
+        }
+
+        // Les index ne sont nettoyés qu'après un tampon entier d'évictions
+        self.stale += 1;
+        if self.stale >= self.capacity {
+            self.indexes.prune(log.id + 1);
+            self.stale = 0;
+        }
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.logs.len() > self.capacity {
+            self.evict();
+        }
+    }
+
+    // Ouvre (ou ferme avec None) le fichier recevant les logs évincés
+    pub fn set_spill(&mut self, path: Option<&Path>) -> io::Result<()> {
+        if let Some(mut spill) = self.spill.take() {
+            spill.writer.flush()?;
+        }
+        self.spill = path.map(LogSpill::open).transpose()?;
+        Ok(())
+    }
+
+    // Recharge une session enregistrée; les logs reçoivent de nouveaux ids
+    pub fn restore(&mut self, logs: Vec<DHCPLog>) {
+        self.clear();
+        // Ces logs sont déjà en base: inutile de déborder ceux qui ne tiennent pas dans le tampon
+        let spill = self.spill.take();
+        for log in logs {
+            self.push(log);
+        }
+        self.spill = spill;
+    }
+
+    pub fn count(&self) -> usize {
+        self.logs.len()
+    }
+
+    pub fn window(&self) -> LogWindow {
+        LogWindow {
+            session: self.session,
+            capacity: self.capacity,
+            overwritten: self.overwritten,
+        }
+    }
+
+    pub fn status(&self) -> LogStatus {
+        LogStatus {
+            count: self.logs.len(),
+            capacity: self.capacity,
+            overwritten: self.overwritten,
+            spill_path: self.spill.as_ref().map(|spill| spill.path.display().to_string()),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.logs.clear();
+        self.timestamps.clear();
+        self.indexes.clear();
+        self.overwritten = 0;
+        self.stale = 0;
+        if let Some(spill) = self.spill.as_mut() {
+            if let Err(e) = spill.writer.flush() {
+                eprintln!("❌ Écriture de {} impossible: {}", spill.path.display(), e);
+            }
+        }
+        self.session += 1;
+    }
+
+    pub fn all(&self) -> Vec<DHCPLog> {
+        self.logs.iter().cloned().collect()
+    }
+
+    pub fn since(&self, cursor: u64) -> LogBatch {
+        // Un curseur inconnu vient d'une instance précédente: tout renvoyer
+        let cursor = if cursor > self.next_id { 0 } else { cursor };
+        // Les ids sont attribués dans l'ordre d'insertion: recherche dichotomique
+        let start = self.logs.partition_point(|log| log.id <= cursor);
+        LogBatch {
+            session: self.session,
+            cursor: self.next_id,
+            capacity: self.capacity,
+            overwritten: self.overwritten,
+            logs: self.logs.range(start..).cloned().collect(),
+        }
+    }
+
+    // Position d'un log: les ids conservés sont contigus, les ids évincés sont ignorés
+    fn position(&self, id: u64) -> Option<usize> {
+        let first = self.logs.front()?.id;
+        let position = usize::try_from(id.checked_sub(first)?).ok()?;
+        (position < self.logs.len()).then_some(position)
+    }
+
+    pub fn query(&self, query: &LogQuery) -> Result<LogPage, LogQueryError> {
+        let indexes = &self.indexes;
+        let mut candidates: Vec<Vec<u64>> = Vec::new();
+
+        if !query.message_types.is_empty() {
+            candidates.push(union(query.message_types
+                .iter()
+                .filter_map(|kind| indexes.by_type.get(&kind.to_lowercase()))));
+        }
+
+        if let Some(ref network) = query.ip {
+            let (first, last) = parse_network(network)?;
+            candidates.push(union(indexes.by_ip.range(first..=last).map(|(_, ids)| ids)));
+        }
+
+        if let Some(ref mac) = query.mac {
+            let mac: MacAddress = mac.parse().map_err(LogQueryError::Mac)?;
+            candidates.push(indexes.by_mac.get(&mac).cloned().unwrap_or_default());
+        }
+
+        for code in &query.options {
+            candidates.push(indexes.by_option.get(code).cloned().unwrap_or_default());
+        }
+
+        if let Some(ref hostname) = query.hostname {
+            // Recherche sur les noms distincts, bien moins nombreux que les paquets
+            let needle = hostname.to_lowercase();
+            candidates.push(union(indexes.by_hostname
+                .iter()
+                .filter(|(name, _)| name.contains(&needle))
+                .map(|(_, ids)| ids)));
+        }
+
+        let text = query.text.as_deref().map(str::to_lowercase).filter(|text| !text.is_empty());
+        let from = query.from.as_deref().map(parse_timestamp).transpose()?;
+        let to = query.to.as_deref().map(parse_timestamp).transpose()?;
+
+        // Les logs arrivent dans l'ordre chronologique: la plage horaire est un intervalle de positions
+        let start = from.map_or(0, |from| self.timestamps.partition_point(|timestamp| *timestamp < from));
+        let end = to.map_or(self.logs.len(), |to| self.timestamps.partition_point(|timestamp| *timestamp <= to));
+        let range = start..end.max(start);
+
+        let positions: Box<dyn Iterator<Item = usize>> = if candidates.is_empty() {
+            Box::new(range)
+        } else {
+            Box::new(intersect(candidates)
+                .into_iter()
+                .filter_map(|id| self.position(id))
+                .filter(move |position| range.contains(position)))
+        };
+
+        let mut matches: Vec<&DHCPLog> = positions
+            .map(|position| &self.logs[position])
+            .filter(|log| match text {
+                Some(ref text) => matches_text(log, text),
+                None => true,
+            })
+            .collect();
+
+        // Les logs sont déjà dans l'ordre d'arrivée
+        if query.sort_by != LogSortField::Timestamp {
+            matches.sort_by(|a, b| {
+                let ordering = match query.sort_by {
+                    LogSortField::Timestamp => Ordering::Equal,
+                    LogSortField::PacketType => a.packet_type.cmp(&b.packet_type),
+                    LogSortField::SourceIp => compare_ip(&a.source_ip, &b.source_ip),
+                    LogSortField::DestinationIp => compare_ip(&a.destination_ip, &b.destination_ip),
+                    LogSortField::Chaddr => a.chaddr.cmp(&b.chaddr),
+                    LogSortField::Xid => a.xid.cmp(&b.xid),
+                };
+                ordering.then_with(|| a.id.cmp(&b.id))
+            });
+        }
+        if query.descending {
+            matches.reverse();
+        }
+
+        let total = matches.len();
+        let logs = matches
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+
+        Ok(LogPage { total, logs })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DHCPOptionLog;
+
+    fn log(offset_secs: i64, packet_type: &str, source_ip: &str, chaddr: &str) -> DHCPLog {
+        let timestamp = DateTime::<Utc>::from_timestamp(1_700_000_000 + offset_secs, 0).unwrap();
+        DHCPLog {
+            timestamp: timestamp.to_rfc3339(),
+            packet_type: packet_type.to_string(),
+            source_ip: source_ip.to_string(),
+            destination_ip: "255.255.255.255".to_string(),
+            chaddr: chaddr.to_string(),
+            xid: format!("0x{:08x}", offset_secs),
+            ..Default::default()
+        }
+    }
+
+    fn with_hostname(mut log: DHCPLog, hostname: &str) -> DHCPLog {
+        log.options.push(DHCPOptionLog {
+            code: OPTION_HOSTNAME,
+            name: "Host Name".to_string(),
+            value: hostname.to_string(),
+        });
+        log
+    }
+
+    fn ids(page: &LogPage) -> Vec<u64> {
+        page.logs.iter().map(|log| log.id).collect()
+    }
+
+    // 1 Discover, 2 Offer, 3 Request, 4 Ack, 5 Discover d'un autre client
+    fn store() -> LogStore {
+        let mut store = LogStore::new();
+        store.push(with_hostname(log(0, "Discover", "0.0.0.0", "00:11:22:33:44:01"), "Laptop-Alice"));
+        store.push(log(1, "Offer", "192.168.1.1", "00:11:22:33:44:01"));
+        store.push(with_hostname(log(2, "Request", "0.0.0.0", "00:11:22:33:44:01"), "laptop-alice"));
+        store.push(log(3, "Ack", "192.168.1.1", "00:11:22:33:44:01"));
+        store.push(with_hostname(log(4, "Discover", "10.0.0.7", "00:11:22:33:44:02"), "printer"));
+        store
+    }
+
+    #[test]
+    fn keeps_the_newest_logs_in_order() {
+        let mut store = LogStore::new();
+        store.set_capacity(3);
+        for offset in 0..5 {
+            store.push(log(offset, "Discover", "0.0.0.0", "00:11:22:33:44:01"));
+        }
+        assert_eq!(store.all().iter().map(|log| log.id).collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!(store.status().overwritten, 2);
+        assert_eq!(store.window(), LogWindow { session: 0, capacity: 3, overwritten: 2 });
+
+        // Les ids continuent après un effacement, le compteur d'écrasement repart de zéro
+        store.clear();
+        let log = store.push(log(5, "Offer", "192.168.1.1", "00:11:22:33:44:01"));
+        assert_eq!(log.id, 6);
+        assert_eq!(store.window(), LogWindow { session: 1, capacity: 3, overwritten: 0 });
+    }
+
+    #[test]
+    fn locates_ids_after_wrap_around() {
+        let mut store = LogStore::new();
+        store.set_capacity(3);
+        for offset in 0..7 {
+            store.push(log(offset, "Discover", "0.0.0.0", "00:11:22:33:44:01"));
+        }
+        assert_eq!(store.position(4), None);
+        assert_eq!(store.position(5), Some(0));
+        assert_eq!(store.position(7), Some(2));
+        assert_eq!(store.position(8), None);
+        assert_eq!(store.since(5).logs.iter().map(|log| log.id).collect::<Vec<_>>(), vec![6, 7]);
+    }
+
+    #[test]
+    fn prunes_indexes_of_evicted_logs() {
+        let mut store = LogStore::new();
+        store.set_capacity(2);
+        for last in 1..=5u8 {
+            store.push(log(i64::from(last), "Discover", "0.0.0.0", &format!("00:11:22:33:44:{:02x}", last)));
+        }
+        // Index nettoyés toutes les `capacity` évictions: l'id 3, évincé depuis, y figure encore
+        assert_eq!(store.stale, 1);
+        assert!(store.indexes.by_mac.values().flatten().all(|id| *id >= 3));
+        assert_eq!(store.indexes.by_type["discover"], vec![3, 4, 5]);
+
+        // Les ids encore indexés mais évincés ne remontent pas dans les résultats
+        let query = |mac: &str| LogQuery { mac: Some(mac.to_string()), ..Default::default() };
+        assert_eq!(store.query(&query("00:11:22:33:44:03")).unwrap().total, 0);
+        assert_eq!(ids(&store.query(&query("00:11:22:33:44:05")).unwrap()), vec![5]);
+        let discovers = LogQuery { message_types: vec!["discover".to_string()], ..Default::default() };
+        assert_eq!(ids(&store.query(&discovers).unwrap()), vec![4, 5]);
+    }
+
+    #[test]
+    fn shrinking_evicts_the_oldest_logs_to_the_spill() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(SPILL_FILE);
+        let mut store = store();
+        store.set_spill(Some(&path)).unwrap();
+        store.set_capacity(2);
+        assert_eq!(store.all().iter().map(|log| log.id).collect::<Vec<_>>(), vec![4, 5]);
+        assert_eq!(store.status().overwritten, 3);
+
+        // Le journal est vidé à la fermeture du tampon
+        drop(store);
+        let spilled: Vec<DHCPLog> = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(spilled.iter().map(|log| log.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn rotates_the_spill_file() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(SPILL_FILE);
+        let mut store = LogStore::new();
+        store.set_capacity(1);
+        store.set_spill(Some(&path)).unwrap();
+        let line_len = serde_json::to_vec(&log(0, "Discover", "0.0.0.0", "00:11:22:33:44:01")).unwrap().len() as u64 + 1;
+        store.spill.as_mut().unwrap().max_bytes = 2 * line_len;
+
+        for offset in 0..6 {
+            store.push(log(offset, "Discover", "0.0.0.0", "00:11:22:33:44:01"));
+        }
+        store.set_spill(None).unwrap();
+
+        // 5 logs évincés: 2 dans l'archive remplacée, 2 dans .1, le dernier dans le fichier courant
+        let line_count = |path: &Path| fs::read_to_string(path).unwrap().lines().count();
+        assert_eq!(line_count(&rotated_path(&path)), 2);
+        assert_eq!(line_count(&path), 1);
+        assert!(fs::metadata(&path).unwrap().len() <= 2 * line_len);
+    }
+
+    #[test]
+    fn parses_network_bounds() {
+        let ip = |text: &str| text.parse::<Ipv4Addr>().unwrap();
+        assert_eq!(parse_network("192.168.1.77/24").unwrap(), (ip("192.168.1.0"), ip("192.168.1.255")));
+        assert_eq!(parse_network("192.168.1.77/32").unwrap(), (ip("192.168.1.77"), ip("192.168.1.77")));
+        assert_eq!(parse_network("192.168.1.77").unwrap(), (ip("192.168.1.77"), ip("192.168.1.77")));
+        assert_eq!(parse_network("10.1.2.3/0").unwrap(), (Ipv4Addr::UNSPECIFIED, Ipv4Addr::BROADCAST));
+        assert!(parse_network("10.1.2.3/33").is_err());
+        assert!(parse_network("10.1.2").is_err());
+    }
+
+    #[test]
+    fn union_and_intersect_sorted_lists() {
+        let lists = [vec![1, 3, 5], vec![2, 3, 6]];
+        assert_eq!(union(lists.iter()), vec![1, 2, 3, 5, 6]);
+        assert_eq!(intersect(vec![vec![1, 3, 5, 7], vec![3, 4, 7], vec![3, 7, 8]]), vec![3, 7]);
+        assert_eq!(intersect(vec![vec![1, 2], Vec::new()]), Vec::<u64>::new());
+        assert_eq!(intersect(Vec::new()), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn matches_message_type_case_insensitively() {
+        let store = store();
+        let query = LogQuery { message_types: vec!["DISCOVER".to_string(), "ack".to_string()], ..Default::default() };
+        assert_eq!(ids(&store.query(&query).unwrap()), vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn filters_by_network_and_mac() {
+        let store = store();
+        let query = LogQuery { ip: Some("192.168.0.0/16".to_string()), ..Default::default() };
+        assert_eq!(ids(&store.query(&query).unwrap()), vec![2, 4]);
+
+        // /0 couvre toutes les adresses, dont la destination broadcast de chaque log
+        let query = LogQuery { ip: Some("0.0.0.0/0".to_string()), ..Default::default() };
+        assert_eq!(store.query(&query).unwrap().total, 5);
+
+        let query = LogQuery { ip: Some("10.0.0.7".to_string()), ..Default::default() };
+        assert_eq!(ids(&store.query(&query).unwrap()), vec![5]);
+
+        let query = LogQuery { mac: Some("00-11-22-33-44-02".to_string()), ..Default::default() };
+        assert_eq!(ids(&store.query(&query).unwrap()), vec![5]);
+        assert!(store.query(&LogQuery { mac: Some("zz".to_string()), ..Default::default() }).is_err());
+    }
+
+    #[test]
+    fn intersects_several_filters() {
+        let store = store();
+        let query = LogQuery {
+            message_types: vec!["discover".to_string(), "request".to_string()],
+            mac: Some("00:11:22:33:44:01".to_string()),
+            options: vec![OPTION_HOSTNAME],
+            ..Default::default()
+        };
+        assert_eq!(ids(&store.query(&query).unwrap()), vec![1, 3]);
+
+        let query = LogQuery {
+            message_types: vec!["offer".to_string()],
+            options: vec![OPTION_HOSTNAME],
+            ..Default::default()
+        };
+        assert_eq!(store.query(&query).unwrap().total, 0);
+    }
+
+    #[test]
+    fn searches_hostname_substrings() {
+        let store = store();
+        let query = LogQuery { hostname: Some("ALICE".to_string()), ..Default::default() };
+        assert_eq!(ids(&store.query(&query).unwrap()), vec![1, 3]);
+        let query = LogQuery { hostname: Some("nothing".to_string()), ..Default::default() };
+        assert_eq!(store.query(&query).unwrap().total, 0);
+    }
+
+    #[test]
+    fn searches_free_text_across_fields() {
+        let store = store();
+        let text = |text: &str| LogQuery { text: Some(text.to_string()), ..Default::default() };
+        assert_eq!(ids(&store.query(&text("192.168")).unwrap()), vec![2, 4]);
+        assert_eq!(ids(&store.query(&text("44:02")).unwrap()), vec![5]);
+        assert_eq!(ids(&store.query(&text("0x00000003")).unwrap()), vec![4]);
+        assert_eq!(ids(&store.query(&text("print")).unwrap()), vec![5]);
+    }
+
+    #[test]
+    fn filters_time_range_inclusively() {
+        let store = store();
+        let at = |offset_secs: i64| DateTime::<Utc>::from_timestamp(1_700_000_000 + offset_secs, 0).unwrap().to_rfc3339();
+        let query = LogQuery { from: Some(at(1)), to: Some(at(3)), ..Default::default() };
+        assert_eq!(ids(&store.query(&query).unwrap()), vec![2, 3, 4]);
+
+        let query = LogQuery { from: Some(at(3)), message_types: vec!["discover".to_string()], ..Default::default() };
+        assert_eq!(ids(&store.query(&query).unwrap()), vec![5]);
+
+        let query = LogQuery { from: Some(at(4)), to: Some(at(1)), ..Default::default() };
+        assert_eq!(store.query(&query).unwrap().total, 0);
+        assert!(store.query(&LogQuery { from: Some("hier".to_string()), ..Default::default() }).is_err());
+    }
+
+    #[test]
+    fn sorts_and_reverses() {
+        let store = store();
+        let query = LogQuery { sort_by: LogSortField::SourceIp, ..Default::default() };
+        assert_eq!(ids(&store.query(&query).unwrap()), vec![1, 3, 5, 2, 4]);
+
+        let query = LogQuery { sort_by: LogSortField::SourceIp, descending: true, ..Default::default() };
+        assert_eq!(ids(&store.query(&query).unwrap()), vec![4, 2, 5, 3, 1]);
+
+        let query = LogQuery { descending: true, ..Default::default() };
+        assert_eq!(ids(&store.query(&query).unwrap()), vec![5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn paginates_against_total() {
+        let store = store();
+        let page = |offset: usize| LogQuery { offset, limit: Some(2), ..Default::default() };
+        let first = store.query(&page(0)).unwrap();
+        assert_eq!((first.total, ids(&first)), (5, vec![1, 2]));
+        let last = store.query(&page(4)).unwrap();
+        assert_eq!((last.total, ids(&last)), (5, vec![5]));
+        let beyond = store.query(&page(10)).unwrap();
+        assert_eq!((beyond.total, beyond.logs.len()), (5, 0));
+    }
+}
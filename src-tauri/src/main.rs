@@ -1,20 +1,340 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod alerts;
+mod capture_clock;
+mod conflicts;
 mod dhcp_capture;
+mod dhcp_options;
+mod fingerprint;
+mod latency_stats;
+mod leases;
+mod log_store;
+mod nak_analysis;
 mod network_interfaces;
+mod option50_history;
+mod oui;
+mod packet_decoder;
+mod packet_events;
+mod pcapng_recorder;
+mod relay_topology;
+mod retransmissions;
+mod session_store;
+mod session_writer;
+mod settings;
+mod starvation;
+mod transactions;
 
-use dhcp_capture::DHCPCapture;
-use network_interfaces::get_network_interfaces;
+use capture_clock::CaptureClock;
+use alerts::{Alert, AlertStore, ServerAllowlist, ALERT_EVENT};
+use conflicts::{AddressConflict, ConflictAnalyzer};
+use dhcp_capture::{ArpPacket, DHCPCapture, DHCPPacket, MacAddress};
+use fingerprint::{FingerprintDatabase, FingerprintMatcher, UnmatchedFingerprint, FINGERPRINT_DATABASE_FILE};
+use latency_stats::{LatencyReport, LatencyStats};
+use leases::{Lease, LeaseQuery, LeaseTable};
+use log_store::{LogBatch, LogPage, LogQuery, LogSettings, LogStatus, LogStore, MAX_LOG_CAPACITY, MIN_LOG_CAPACITY, SPILL_FILE};
+use nak_analysis::{NakAnalyzer, NakReport, Subnet};
+use network_interfaces::{get_network_interfaces, interface_ipv4_networks};
+use oui::{MacVendorInfo, OuiDatabase, OUI_DATABASE_FILE};
+use option50_history::{ClientRequestHistory, Option50History};
+use packet_events::{PacketEvents, DEFAULT_COALESCE_INTERVAL_MS, MAX_COALESCE_INTERVAL_MS, PACKET_EVENT};
+use pcapng_recorder::RecordingOptions;
+use relay_topology::{RelayPort, RelayTopology};
+use retransmissions::{RetransmissionDetector, StrugglingClient};
+use settings::{check_starvation_config, AppSettings, SETTINGS_FILE};
+use session_store::{RetentionPolicy, SessionSource, SessionStore, SessionSummary, StorageError, SESSION_DATABASE_FILE};
+use session_writer::SessionWriter;
+use starvation::{StarvationConfig, StarvationDetector};
+use transactions::{Transaction, TransactionTracker};
 use std::sync::{Mutex, Arc};
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
 use serde::{Serialize, Deserialize};
 use reqwest;
 
 // Application state
 struct AppState {
-    logs: Arc<Mutex<Vec<DHCPLog>>>,
+    logs: Arc<Mutex<LogStore>>,
+    packet_events: PacketEvents,
     capture: Arc<Mutex<Option<DHCPCapture>>>,
+    relay_topology: Arc<Mutex<RelayTopology>>,
+    transactions: Arc<Mutex<TransactionTracker>>,
+    alerts: Arc<Mutex<AlertStore>>,
+    server_allowlist: Arc<Mutex<ServerAllowlist>>,
+    starvation: Arc<Mutex<StarvationDetector>>,
+    leases: Arc<Mutex<LeaseTable>>,
+    option50_history: Arc<Mutex<Option50History>>,
+    fingerprints: Arc<Mutex<FingerprintMatcher>>,
+    oui: Arc<Mutex<OuiDatabase>>,
+    conflicts: Arc<Mutex<ConflictAnalyzer>>,
+    latency: Arc<Mutex<LatencyStats>>,
+    retransmissions: Arc<Mutex<RetransmissionDetector>>,
+    nak_analysis: Arc<Mutex<NakAnalyzer>>,
+    clock: Arc<Mutex<CaptureClock>>,
+    // None si la base de sessions n'a pas pu être ouverte: la capture fonctionne sans persistance
+    sessions: Arc<Mutex<Option<SessionStore>>>,
+    session_writer: SessionWriter,
+    retention: Arc<Mutex<RetentionPolicy>>,
+    // Copie de ce qui est enregistré dans settings.json
+    settings: Arc<Mutex<AppSettings>>,
+}
+
+impl AppState {
+    // Référence des délais affichés, voir CaptureClock
+    fn capture_now(&self) -> chrono::DateTime<chrono::Utc> {
+        capture_now(&self.clock)
+    }
+
+    fn pipeline(&self, app: AppHandle) -> PacketPipeline {
+        PacketPipeline {
+            app,
+            logs: self.logs.clone(),
+            packet_events: self.packet_events.clone(),
+            relay_topology: self.relay_topology.clone(),
+            transactions: self.transactions.clone(),
+            alerts: self.alerts.clone(),
+            server_allowlist: self.server_allowlist.clone(),
+            starvation: self.starvation.clone(),
+            leases: self.leases.clone(),
+            option50_history: self.option50_history.clone(),
+            fingerprints: self.fingerprints.clone(),
+            oui: self.oui.clone(),
+            conflicts: self.conflicts.clone(),
+            latency: self.latency.clone(),
+            retransmissions: self.retransmissions.clone(),
+            nak_analysis: self.nak_analysis.clone(),
+            clock: self.clock.clone(),
+            sessions: self.sessions.clone(),
+            session_writer: self.session_writer.clone(),
+            retention: self.retention.clone(),
+        }
+    }
+}
+
+// Traitement commun des paquets décodés, que la source soit une interface ou un fichier
+#[derive(Clone)]
+struct PacketPipeline {
+    app: AppHandle,
+    logs: Arc<Mutex<LogStore>>,
+    packet_events: PacketEvents,
+    relay_topology: Arc<Mutex<RelayTopology>>,
+    transactions: Arc<Mutex<TransactionTracker>>,
+    alerts: Arc<Mutex<AlertStore>>,
+    server_allowlist: Arc<Mutex<ServerAllowlist>>,
+    starvation: Arc<Mutex<StarvationDetector>>,
+    leases: Arc<Mutex<LeaseTable>>,
+    option50_history: Arc<Mutex<Option50History>>,
+    fingerprints: Arc<Mutex<FingerprintMatcher>>,
+    oui: Arc<Mutex<OuiDatabase>>,
+    conflicts: Arc<Mutex<ConflictAnalyzer>>,
+    latency: Arc<Mutex<LatencyStats>>,
+    retransmissions: Arc<Mutex<RetransmissionDetector>>,
+    nak_analysis: Arc<Mutex<NakAnalyzer>>,
+    clock: Arc<Mutex<CaptureClock>>,
+    sessions: Arc<Mutex<Option<SessionStore>>>,
+    session_writer: SessionWriter,
+    retention: Arc<Mutex<RetentionPolicy>>,
+}
+
+fn capture_now(clock: &Mutex<CaptureClock>) -> chrono::DateTime<chrono::Utc> {
+    clock.lock().map(|clock| clock.now()).unwrap_or_else(|_| chrono::Utc::now())
+}
+
+impl PacketPipeline {
+    fn process(&self, packet: DHCPPacket, interface: &str) {
+        if let Ok(mut clock) = self.clock.lock() {
+            clock.observe(packet.timestamp);
+        }
+        
+        // Convertir le paquet DHCP en DHCPLog
+        let mut log = DHCPLog::from_packet(&packet, interface);
+        
+        if let Ok(oui) = self.oui.lock() {
+            log.chaddr_vendor = oui.vendor(&packet.bootp.chaddr).map(str::to_string);
+            log.source_mac_vendor = packet.source_mac.and_then(|mac| oui.vendor(&mac)).map(str::to_string);
+        }
+        
+        if let Some(fingerprint) = packet.fingerprint() {
+            log.fingerprint = Some(fingerprint.signature());
+            if let Ok(mut matcher) = self.fingerprints.lock() {
+                if let Some(device) = matcher.identify(&fingerprint, packet.bootp.chaddr, packet.timestamp) {
+                    log.device = Some(device.name);
+                    log.device_category = Some(device.category);
+                }
+            }
+        }
+        
+        println!("📦 Packet captured: {} from {} to {} (xid {}, client {})", 
+                 log.packet_type, log.source_ip, log.destination_ip, log.xid, log.chaddr);
+        
+        if let Ok(mut topology) = self.relay_topology.lock() {
+            topology.record(&packet);
+        }
+        
+        let latency_sample = self.transactions.lock()
+            .ok()
+            .and_then(|mut transactions| transactions.record(&packet));
+        if let Some(sample) = latency_sample {
+            if let Ok(mut latency) = self.latency.lock() {
+                latency.record(&sample);
+            }
+        }
+        
+        if let Ok(mut retransmissions) = self.retransmissions.lock() {
+            retransmissions.record(&packet);
+        }
+        
+        if let Ok(mut leases) = self.leases.lock() {
+            leases.record(&packet);
+        }
+        
+        if let Ok(mut history) = self.option50_history.lock() {
+            history.record(&packet);
+        }
+        
+        let nak_report = self.nak_analysis.lock()
+            .ok()
+            .and_then(|mut analyzer| analyzer.record(&packet));
+        if let Some(report) = nak_report {
+            println!("⛔ NAK pour {}: {}", report.chaddr, report.summary);
+            log.nak_reason = Some(report.summary);
+        }
+        
+        // Ajouter le log à l'état de l'application; l'id attribué sert de curseur au frontend
+        let log = match self.logs.lock() {
+            Ok(mut logs) => {
+                let log = logs.push(log);
+                println!("📝 Log added to state, total logs: {}", logs.count());
+                self.packet_events.publish(logs.window(), log.clone());
+                log
+            }
+            Err(_) => {
+                eprintln!("❌ Failed to lock logs for writing");
+                log
+            }
+        };
+        self.session_writer.record_packet(log.clone());
+        
+        let rogue = self.server_allowlist.lock()
+            .ok()
+            .and_then(|allowlist| allowlist.check(&packet));
+        if let Some(finding) = rogue {
+            self.raise_alert(finding, &log);
+        }
+        
+        let starvation = self.starvation.lock()
+            .map(|mut detector| detector.record(&packet))
+            .unwrap_or_default();
+        for finding in starvation {
+            self.raise_alert(finding, &log);
+        }
+        
+        let conflict = self.conflicts.lock()
+            .ok()
+            .and_then(|mut analyzer| analyzer.record(&packet));
+        if let Some(finding) = conflict {
+            self.raise_alert(finding, &log);
+        }
+    }
+
+    fn process_arp(&self, arp: ArpPacket) {
+        if let Ok(mut analyzer) = self.conflicts.lock() {
+            analyzer.record_arp(&arp);
+        }
+    }
+
+    fn raise_alert(&self, finding: alerts::Finding, log: &DHCPLog) {
+        println!("🚨 Alerte: {}", finding.message);
+        let alert = match self.alerts.lock() {
+            Ok(mut alerts) => alerts.push(finding, log.clone()),
+            Err(_) => {
+                eprintln!("❌ Failed to lock alerts for writing");
+                return;
+            }
+        };
+        self.persist(|store| store.record_alert(&alert));
+        if let Err(e) = self.app.emit(ALERT_EVENT, &alert) {
+            eprintln!("❌ Impossible d'émettre l'alerte: {}", e);
+        }
+    }
+
+    // Écrit dans la session en cours d'enregistrement, s'il y en a une
+    fn persist<F>(&self, write: F)
+    where
+        F: FnOnce(&mut SessionStore) -> Result<(), StorageError>,
+    {
+        let Ok(mut sessions) = self.sessions.lock() else {
+            eprintln!("❌ Failed to lock session store");
+            return;
+        };
+        if let Some(store) = sessions.as_mut().filter(|store| store.active_session().is_some()) {
+            if let Err(e) = write(store) {
+                eprintln!("❌ Enregistrement de la session impossible: {}", e);
+            }
+        }
+    }
+
+    fn begin_session(&self, interface: &str, source: SessionSource, filter: Option<&str>) {
+        // Les paquets encore en file appartiennent à la session précédente
+        self.session_writer.flush();
+        let policy = self.retention.lock().map(|policy| *policy).unwrap_or_default();
+        let Ok(mut sessions) = self.sessions.lock() else {
+            eprintln!("❌ Failed to lock session store");
+            return;
+        };
+        let Some(store) = sessions.as_mut() else {
+            return;
+        };
+        let now = chrono::Utc::now();
+        if let Err(e) = store.apply_retention(&policy, now) {
+            eprintln!("❌ Rétention des sessions impossible: {}", e);
+        }
+        match store.begin_session(interface, source, filter, now) {
+            Ok(id) => println!("🗄️ Session {} enregistrée ({})", id, interface),
+            Err(e) => eprintln!("❌ Impossible de créer la session: {}", e),
+        }
+    }
+
+    // Clôt la session en cours avec l'état final des transactions
+    fn end_session(&self) {
+        self.session_writer.flush();
+        // Les délais d'attente se mesurent sur la capture, la fin de session sur l'horloge murale
+        let capture_now = capture_now(&self.clock);
+        let now = chrono::Utc::now();
+        let transactions = self.transactions.lock()
+            .map(|transactions| transactions.snapshot(capture_now))
+            .unwrap_or_default();
+        self.persist(|store| store.end_session(&transactions, now));
+    }
+
+    fn reset(&self) -> Result<(), String> {
+        let mut logs = self.logs.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        logs.clear();
+        let mut topology = self.relay_topology.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        topology.clear();
+        let mut transactions = self.transactions.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        transactions.clear();
+        let mut alerts = self.alerts.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        alerts.clear();
+        let mut starvation = self.starvation.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        starvation.clear();
+        let mut leases = self.leases.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        leases.clear();
+        let mut history = self.option50_history.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        history.clear();
+        let mut fingerprints = self.fingerprints.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        fingerprints.clear();
+        let mut conflicts = self.conflicts.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        conflicts.clear();
+        let mut latency = self.latency.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        latency.clear();
+        let mut retransmissions = self.retransmissions.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        retransmissions.clear();
+        let mut nak_analysis = self.nak_analysis.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        nak_analysis.clear();
+        let mut clock = self.clock.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        clock.reset();
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,17 +347,105 @@ pub struct NetworkInterface {
     pub real_name: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DHCPLog {
+    pub id: u64,
     pub timestamp: String,
     pub packet_type: String,
     pub source_ip: String,
     pub destination_ip: String,
+    pub source_mac: Option<String>,
+    pub destination_mac: Option<String>,
+    pub vlan_ids: Vec<u16>,
+    pub source_port: u16,
+    pub destination_port: u16,
+    pub op: u8,
+    pub htype: u8,
+    pub hlen: u8,
+    pub hops: u8,
+    pub xid: String,
+    pub secs: u16,
+    pub flags: u16,
+    pub broadcast: bool,
+    pub ciaddr: String,
+    pub yiaddr: String,
+    pub siaddr: String,
+    pub giaddr: String,
+    pub chaddr: String,
+    pub chaddr_vendor: Option<String>,
+    pub chaddr_locally_administered: bool,
+    pub source_mac_vendor: Option<String>,
+    pub sname: String,
+    pub file: String,
+    pub options: Vec<DHCPOptionLog>,
+    pub option_overload: bool,
+    pub options_concatenated: bool,
     pub option_50: Option<String>,
+    pub fingerprint: Option<String>,
+    pub device: Option<String>,
+    pub device_category: Option<String>,
+    pub nak_reason: Option<String>,
     pub interface: String,
     pub raw_data: String,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DHCPOptionLog {
+    pub code: u8,
+    pub name: String,
+    pub value: String,
+}
+
+impl DHCPLog {
+    fn from_packet(packet: &DHCPPacket, interface: &str) -> Self {
+        let bootp = &packet.bootp;
+        DHCPLog {
+            id: 0,
+            timestamp: packet.timestamp.to_rfc3339(),
+            packet_type: format!("{:?}", packet.message_type),
+            source_ip: packet.source_ip.to_string(),
+            destination_ip: packet.destination_ip.to_string(),
+            source_mac: packet.source_mac.map(|mac| mac.to_string()),
+            destination_mac: packet.destination_mac.map(|mac| mac.to_string()),
+            vlan_ids: packet.vlan_ids.clone(),
+            source_port: packet.source_port,
+            destination_port: packet.destination_port,
+            op: bootp.op,
+            htype: bootp.htype,
+            hlen: bootp.hlen,
+            hops: bootp.hops,
+            xid: format!("0x{:08x}", bootp.xid),
+            secs: bootp.secs,
+            flags: bootp.flags,
+            broadcast: bootp.is_broadcast(),
+            ciaddr: bootp.ciaddr.to_string(),
+            yiaddr: bootp.yiaddr.to_string(),
+            siaddr: bootp.siaddr.to_string(),
+            giaddr: bootp.giaddr.to_string(),
+            chaddr: bootp.chaddr.to_string(),
+            chaddr_vendor: None,
+            chaddr_locally_administered: oui::is_locally_administered(&bootp.chaddr),
+            source_mac_vendor: None,
+            sname: bootp.sname.clone(),
+            file: bootp.file.clone(),
+            options: packet.options.iter().map(|option| DHCPOptionLog {
+                code: option.code(),
+                name: option.name(),
+                value: option.to_string(),
+            }).collect(),
+            option_overload: packet.option_overload,
+            options_concatenated: packet.options_concatenated,
+            option_50: packet.option_50.map(|ip| ip.to_string()),
+            fingerprint: None,
+            device: None,
+            device_category: None,
+            nak_reason: None,
+            interface: interface.to_string(),
+            raw_data: format!("{:?}", packet.raw_data),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GitHubRelease {
     pub tag_name: String,
@@ -180,7 +588,7 @@ fn setup_dll_path() {
 }
 
 #[tauri::command]
-fn start_capture(interface_name: String, state: State<AppState>) -> Result<(), String> {
+fn start_capture(interface_name: String, recording: Option<RecordingOptions>, capture_arp: Option<bool>, app: AppHandle, state: State<AppState>) -> Result<(), String> {
     println!("🔍 Starting capture on interface: {}", interface_name);
     
     // Vérifier Npcap avant de démarrer la capture
@@ -192,18 +600,59 @@ fn start_capture(interface_name: String, state: State<AppState>) -> Result<(), S
     println!("✅ Npcap check passed");
     
     // Clear any existing logs when starting new capture
-    if let Ok(mut logs) = state.logs.lock() {
-        logs.clear();
-        println!("📝 Logs cleared for new capture session");
+    let pipeline = state.pipeline(app.clone());
+    pipeline.end_session();
+    pipeline.reset()?;
+    println!("📝 Logs cleared for new capture session");
+    
+    if let Ok(mut clock) = state.clock.lock() {
+        clock.set_live(true);
+    }
+    
+    let local_subnets: Vec<Subnet> = interface_ipv4_networks(&interface_name)
+        .into_iter()
+        .map(|(address, netmask)| Subnet::new(address, netmask))
+        .collect();
+    if let Ok(mut analyzer) = state.nak_analysis.lock() {
+        analyzer.set_local_subnets(local_subnets);
     }
     
-    let capture = DHCPCapture::new(&interface_name)
+    let mut capture = DHCPCapture::new(&interface_name)
         .map_err(|e| {
             println!("❌ DHCPCapture::new failed: {}", e);
             format!("Erreur lors de l'initialisation: {}", e)
         })?;
     
+    if let Some(ref options) = recording {
+        println!("💾 Recording enabled to {}", options.directory);
+    }
+    capture.set_recording(recording);
+    
+    // Sans choix explicite, reprendre les options de la capture précédente
+    let (bpf_filter, capture_arp) = {
+        let settings = state.settings.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        (settings.bpf_filter.clone(), capture_arp.unwrap_or(settings.capture_arp))
+    };
+    if let Some(ref filter) = bpf_filter {
+        println!("🔎 Filtre BPF utilisateur: {}", filter);
+    }
+    capture.set_user_filter(bpf_filter);
+    
+    if capture_arp {
+        println!("🔎 ARP capture enabled for conflict analysis");
+        let arp_pipeline = pipeline.clone();
+        capture.set_arp_callback(move |arp| arp_pipeline.process_arp(arp));
+    }
+    
     println!("✅ DHCPCapture initialized successfully");
+    pipeline.begin_session(&interface_name, SessionSource::Live, Some(&capture.filter()));
+    
+    if let Err(e) = persist_settings(&app, &state, |settings| {
+        settings.last_interface = Some(interface_name.clone());
+        settings.capture_arp = capture_arp;
+    }) {
+        eprintln!("❌ Impossible d'enregistrer les options de capture: {}", e);
+    }
 
     // Stocker la capture dans l'état
     if let Ok(mut capture_guard) = state.capture.lock() {
@@ -211,7 +660,6 @@ fn start_capture(interface_name: String, state: State<AppState>) -> Result<(), S
     }
 
     // Démarrer la capture dans un thread séparé
-    let logs_arc = state.logs.clone();
     let capture_arc = state.capture.clone();
     let interface_name_clone = interface_name.clone();
     
@@ -219,27 +667,7 @@ fn start_capture(interface_name: String, state: State<AppState>) -> Result<(), S
         if let Ok(mut capture_guard) = capture_arc.lock() {
             if let Some(ref mut capture) = *capture_guard {
                 if let Err(e) = capture.start_capture_with_callback(move |packet| {
-                    // Convertir le paquet DHCP en DHCPLog
-                    let log = DHCPLog {
-                        timestamp: packet.timestamp.to_rfc3339(),
-                        packet_type: format!("{:?}", packet.message_type),
-                        source_ip: packet.source_ip.to_string(),
-                        destination_ip: packet.destination_ip.to_string(),
-                        option_50: packet.option_50.map(|ip| ip.to_string()),
-                        interface: interface_name_clone.clone(),
-                        raw_data: format!("{:?}", packet.raw_data),
-                    };
-                    
-                    println!("📦 Packet captured: {} from {} to {}", 
-                             log.packet_type, log.source_ip, log.destination_ip);
-                    
-                    // Ajouter le log à l'état de l'application
-                    if let Ok(mut logs) = logs_arc.lock() {
-                        logs.push(log);
-                        println!("📝 Log added to state, total logs: {}", logs.len());
-                    } else {
-                        eprintln!("❌ Failed to lock logs for writing");
-                    }
+                    pipeline.process(packet, &interface_name_clone);
                 }) {
                     eprintln!("❌ Erreur de capture: {}", e);
                 }
@@ -255,7 +683,7 @@ fn start_capture(interface_name: String, state: State<AppState>) -> Result<(), S
 }
 
 #[tauri::command]
-fn stop_capture(state: State<AppState>) -> Result<(), String> {
+fn stop_capture(app: AppHandle, state: State<AppState>) -> Result<(), String> {
     println!("Demande d'arrêt de la capture...");
     
     // Marquer l'arrêt de la capture
@@ -270,29 +698,482 @@ fn stop_capture(state: State<AppState>) -> Result<(), String> {
         println!("Capture nettoyée");
     }
     
+    state.pipeline(app).end_session();
+
+    // Hors capture, les délais se mesurent sur le dernier paquet reçu
+    if let Ok(mut clock) = state.clock.lock() {
+        clock.set_live(false);
+    }
+    
     Ok(())
 }
 
 #[tauri::command]
 fn get_logs(state: State<AppState>) -> Result<Vec<DHCPLog>, String> {
     let logs = state.logs.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
-    Ok(logs.clone())
+    Ok(logs.all())
+}
+
+#[tauri::command]
+fn get_logs_since(cursor: u64, state: State<AppState>) -> Result<LogBatch, String> {
+    let logs = state.logs.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(logs.since(cursor))
+}
+
+#[tauri::command]
+fn query_logs(query: Option<LogQuery>, state: State<AppState>) -> Result<LogPage, String> {
+    let logs = state.logs.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    logs.query(&query.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+// Taille du tampon de logs et débordement sur disque des logs évincés
+#[tauri::command]
+fn set_settings(settings: LogSettings, app: AppHandle, state: State<AppState>) -> Result<LogStatus, String> {
+    if !(MIN_LOG_CAPACITY..=MAX_LOG_CAPACITY).contains(&settings.max_logs) {
+        return Err(format!("Nombre maximum de logs hors limites ({} - {})", MIN_LOG_CAPACITY, MAX_LOG_CAPACITY));
+    }
+
+    let status = configure_log_buffer(&settings, &app, &state)?;
+    persist_settings(&app, &state, |current| {
+        current.max_logs = settings.max_logs;
+        current.spill_to_disk = settings.spill_to_disk;
+    })?;
+    Ok(status)
+}
+
+fn configure_log_buffer(settings: &LogSettings, app: &AppHandle, state: &AppState) -> Result<LogStatus, String> {
+    let spill_path = if settings.spill_to_disk {
+        let data_dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(&data_dir).map_err(|e| e.to_string())?;
+        Some(data_dir.join(SPILL_FILE))
+    } else {
+        None
+    };
+
+    let mut logs = state.logs.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    logs.set_spill(spill_path.as_deref())
+        .map_err(|e| format!("Fichier de débordement inaccessible: {}", e))?;
+    logs.set_capacity(settings.max_logs);
+    println!("📝 Tampon de logs: {} entrées maximum", settings.max_logs);
+    Ok(logs.status())
+}
+
+#[tauri::command]
+fn get_log_status(state: State<AppState>) -> Result<LogStatus, String> {
+    let logs = state.logs.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(logs.status())
+}
+
+#[tauri::command]
+fn get_packet_event_interval(state: State<AppState>) -> Result<u64, String> {
+    Ok(state.packet_events.interval_ms())
+}
+
+#[tauri::command]
+fn set_packet_event_interval(interval_ms: u64, app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    if interval_ms > MAX_COALESCE_INTERVAL_MS {
+        return Err(format!("Intervalle trop long (maximum {} ms)", MAX_COALESCE_INTERVAL_MS));
+    }
+    state.packet_events.set_interval_ms(interval_ms);
+    persist_settings(&app, &state, |settings| settings.packet_event_interval_ms = interval_ms)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_settings(state: State<AppState>) -> Result<AppSettings, String> {
+    let settings = state.settings.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(settings.clone())
+}
+
+// Remplace tous les paramètres: validés, appliqués puis enregistrés
+#[tauri::command]
+fn update_settings(settings: AppSettings, app: AppHandle, state: State<AppState>) -> Result<AppSettings, String> {
+    let settings = settings.validated().map_err(|e| e.to_string())?;
+    if let Some(ref filter) = settings.bpf_filter {
+        DHCPCapture::check_user_filter(filter).map_err(|e| e.to_string())?;
+    }
+    apply_settings(&settings, &app, &state)?;
+    persist_settings(&app, &state, |current| *current = settings)
+}
+
+// Reporte les paramètres sur les composants qui en dépendent
+fn apply_settings(settings: &AppSettings, app: &AppHandle, state: &AppState) -> Result<(), String> {
+    let log_settings = LogSettings {
+        max_logs: settings.max_logs,
+        spill_to_disk: settings.spill_to_disk,
+    };
+    configure_log_buffer(&log_settings, app, state)?;
+    state.packet_events.set_interval_ms(settings.packet_event_interval_ms);
+    *state.server_allowlist.lock().map_err(|_| "Erreur de verrouillage".to_string())? = settings.server_allowlist.clone();
+    state.starvation.lock().map_err(|_| "Erreur de verrouillage".to_string())?.set_config(settings.starvation.clone());
+    // La rétention ne supprime rien ici: elle s'applique au début de chaque session ou via set_retention_policy
+    *state.retention.lock().map_err(|_| "Erreur de verrouillage".to_string())? = settings.retention;
+    Ok(())
+}
+
+// Modifie les paramètres en mémoire puis les enregistre dans le dossier de configuration
+fn persist_settings<F>(app: &AppHandle, state: &AppState, update: F) -> Result<AppSettings, String>
+where
+    F: FnOnce(&mut AppSettings),
+{
+    let mut settings = state.settings.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    update(&mut settings);
+    let config_dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
+    settings.save(&config_dir.join(SETTINGS_FILE)).map_err(|e| e.to_string())?;
+    Ok(settings.clone())
+}
+
+#[tauri::command]
+fn get_transactions(state: State<AppState>) -> Result<Vec<Transaction>, String> {
+    let transactions = state.transactions.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(transactions.snapshot(state.capture_now()))
+}
+
+#[tauri::command]
+fn get_struggling_clients(state: State<AppState>) -> Result<Vec<StrugglingClient>, String> {
+    let retransmissions = state.retransmissions.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(retransmissions.struggling(state.capture_now()))
+}
+
+#[tauri::command]
+fn get_latency_stats(state: State<AppState>) -> Result<LatencyReport, String> {
+    let latency = state.latency.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(latency.report())
+}
+
+#[tauri::command]
+fn get_leases(query: Option<LeaseQuery>, state: State<AppState>) -> Result<Vec<Lease>, String> {
+    // Un fichier relu est jugé à sa propre date, pas à l'heure actuelle
+    let now = state.capture_now();
+    let leases = state.leases.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(leases.query(&query.unwrap_or_default(), now))
+}
+
+#[tauri::command]
+fn get_option50_history(mismatches_only: Option<bool>, state: State<AppState>) -> Result<Vec<ClientRequestHistory>, String> {
+    let history = state.option50_history.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(history.snapshot(mismatches_only.unwrap_or(false)))
+}
+
+#[tauri::command]
+fn get_nak_reports(state: State<AppState>) -> Result<Vec<NakReport>, String> {
+    let analyzer = state.nak_analysis.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(analyzer.snapshot())
+}
+
+#[tauri::command]
+fn get_address_conflicts(state: State<AppState>) -> Result<Vec<AddressConflict>, String> {
+    let analyzer = state.conflicts.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(analyzer.snapshot())
+}
+
+#[tauri::command]
+async fn open_capture_file(path: String, app: AppHandle, state: State<'_, AppState>) -> Result<usize, String> {
+    println!("📂 Opening capture file: {}", path);
+
+    // Un fichier forme sa propre session: pas de mélange avec une capture live
+    let capture_active = state.capture.lock()
+        .map_err(|_| "Erreur de verrouillage".to_string())?
+        .is_some();
+    if capture_active {
+        return Err("Arrêtez la capture en cours avant d'analyser un fichier".to_string());
+    }
+
+    let pipeline = state.pipeline(app);
+    pipeline.end_session();
+    pipeline.reset()?;
+    // Le fichier vient d'un autre réseau que l'interface de la dernière capture
+    if let Ok(mut analyzer) = state.nak_analysis.lock() {
+        analyzer.set_local_subnets(Vec::new());
+    }
+
+    let source_name = std::path::Path::new(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.clone());
+    pipeline.begin_session(&path, SessionSource::File, None);
+
+    // La lecture peut être longue sur de gros fichiers: ne pas bloquer le runtime async
+    let reader = pipeline.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        DHCPCapture::read_capture_file(
+            &path,
+            |packet| reader.process(packet, &source_name),
+            |arp| reader.process_arp(arp),
+        )
+    })
+    .await;
+    pipeline.end_session();
+
+    result
+        .map_err(|e| format!("Erreur d'analyse du fichier: {}", e))?
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn list_sessions(state: State<AppState>) -> Result<Vec<SessionSummary>, String> {
+    let sessions = state.sessions.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    let store = sessions.as_ref().ok_or("Base de sessions indisponible")?;
+    store.list_sessions().map_err(|e| e.to_string())
 }
 
+// Recharge une session enregistrée dans les vues (logs et alertes)
 #[tauri::command]
-fn clear_logs(state: State<AppState>) -> Result<(), String> {
+fn open_session(session_id: i64, app: AppHandle, state: State<AppState>) -> Result<SessionSummary, String> {
+    let capture_active = state.capture.lock().map_err(|_| "Erreur de verrouillage".to_string())?.is_some();
+    if capture_active {
+        return Err("Arrêtez la capture en cours avant d'ouvrir une session".to_string());
+    }
+
+    let pipeline = state.pipeline(app);
+    pipeline.end_session();
+    let stored = {
+        let sessions = state.sessions.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+        let store = sessions.as_ref().ok_or("Base de sessions indisponible")?;
+        store.load_session(session_id).map_err(|e| e.to_string())?
+    };
+
+    pipeline.reset()?;
     let mut logs = state.logs.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
-    logs.clear();
+    logs.restore(stored.logs);
+    let mut alerts = state.alerts.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    alerts.restore(stored.alerts);
+    println!("🗄️ Session {} rouverte: {} paquets", session_id, logs.count());
+    Ok(stored.summary)
+}
+
+#[tauri::command]
+fn get_session_transactions(session_id: i64, state: State<AppState>) -> Result<Vec<Transaction>, String> {
+    let sessions = state.sessions.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    let store = sessions.as_ref().ok_or("Base de sessions indisponible")?;
+    store.load_transactions(session_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn delete_session(session_id: i64, state: State<AppState>) -> Result<(), String> {
+    let mut sessions = state.sessions.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    let store = sessions.as_mut().ok_or("Base de sessions indisponible")?;
+    store.delete_session(session_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn get_retention_policy(state: State<AppState>) -> Result<RetentionPolicy, String> {
+    let policy = state.retention.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(*policy)
+}
+
+#[tauri::command]
+fn set_retention_policy(policy: RetentionPolicy, app: AppHandle, state: State<AppState>) -> Result<usize, String> {
+    let removed = apply_retention(policy, &state)?;
+    persist_settings(&app, &state, |settings| settings.retention = policy)?;
+    Ok(removed)
+}
+
+fn apply_retention(policy: RetentionPolicy, state: &AppState) -> Result<usize, String> {
+    *state.retention.lock().map_err(|_| "Erreur de verrouillage".to_string())? = policy;
+    let mut sessions = state.sessions.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    match sessions.as_mut() {
+        Some(store) => store.apply_retention(&policy, chrono::Utc::now()).map_err(|e| e.to_string()),
+        None => Ok(0),
+    }
+}
+
+#[tauri::command]
+fn clear_logs(app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    state.pipeline(app).reset()
+}
+
+#[tauri::command]
+fn get_relay_topology(state: State<AppState>) -> Result<Vec<RelayPort>, String> {
+    let topology = state.relay_topology.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(topology.snapshot())
+}
+
+#[tauri::command]
+fn get_alerts(state: State<AppState>) -> Result<Vec<Alert>, String> {
+    let alerts = state.alerts.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(alerts.snapshot())
+}
+
+#[tauri::command]
+fn clear_alerts(state: State<AppState>) -> Result<(), String> {
+    let mut alerts = state.alerts.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    alerts.clear();
+    Ok(())
+}
+
+#[tauri::command]
+fn get_server_allowlist(state: State<AppState>) -> Result<ServerAllowlist, String> {
+    let allowlist = state.server_allowlist.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(allowlist.clone())
+}
+
+#[tauri::command]
+fn set_server_allowlist(allowlist: ServerAllowlist, app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    let mut current = state.server_allowlist.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    if allowlist.is_empty() {
+        println!("⚠️ Liste de serveurs autorisés vide: détection des serveurs pirates désactivée");
+    } else {
+        println!("✅ {} IP(s) et {} MAC(s) de serveurs autorisés", allowlist.ips.len(), allowlist.macs.len());
+    }
+    *current = allowlist.clone();
+    drop(current);
+    persist_settings(&app, &state, |settings| settings.server_allowlist = allowlist)?;
     Ok(())
 }
 
+#[tauri::command]
+fn get_starvation_config(state: State<AppState>) -> Result<StarvationConfig, String> {
+    let detector = state.starvation.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(detector.config().clone())
+}
+
+#[tauri::command]
+fn set_starvation_config(config: StarvationConfig, app: AppHandle, state: State<AppState>) -> Result<(), String> {
+    check_starvation_config(&config).map_err(|e| e.to_string())?;
+    let mut detector = state.starvation.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    detector.set_config(config.clone());
+    drop(detector);
+    persist_settings(&app, &state, |settings| settings.starvation = config)?;
+    Ok(())
+}
+
+#[tauri::command]
+fn get_unmatched_fingerprints(state: State<AppState>) -> Result<Vec<UnmatchedFingerprint>, String> {
+    let matcher = state.fingerprints.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(matcher.unmatched())
+}
+
+#[tauri::command]
+fn export_unmatched_fingerprints(path: String, state: State<AppState>) -> Result<usize, String> {
+    let matcher = state.fingerprints.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    let count = matcher.export_unmatched(std::path::Path::new(&path)).map_err(|e| e.to_string())?;
+    println!("📤 {} empreinte(s) inconnue(s) exportée(s) vers {}", count, path);
+    Ok(count)
+}
+
+#[tauri::command]
+fn load_fingerprint_database(path: String, app: AppHandle, state: State<AppState>) -> Result<usize, String> {
+    let database = FingerprintDatabase::load(std::path::Path::new(&path)).map_err(|e| e.to_string())?;
+
+    // Conserver la base dans le dossier de configuration pour les prochains démarrages; elle est
+    // réécrite depuis sa version analysée car le fichier choisi peut être la copie elle-même
+    let config_dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
+    database.save(&config_dir.join(FINGERPRINT_DATABASE_FILE)).map_err(|e| e.to_string())?;
+
+    let mut matcher = state.fingerprints.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    matcher.set_database(database);
+    println!("🧬 Base d'empreintes chargée: {} entrées", matcher.database_len());
+    Ok(matcher.database_len())
+}
+
+#[tauri::command]
+fn lookup_mac_vendor(mac: String, state: State<AppState>) -> Result<MacVendorInfo, String> {
+    let mac: MacAddress = mac.parse()?;
+    let oui = state.oui.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    Ok(oui.lookup(&mac))
+}
+
+#[tauri::command]
+fn refresh_oui_database(path: String, app: AppHandle, state: State<AppState>) -> Result<usize, String> {
+    let database = OuiDatabase::load(std::path::Path::new(&path)).map_err(|e| e.to_string())?;
+
+    // Stocker la table sous forme compacte pour les prochains démarrages
+    let config_dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&config_dir).map_err(|e| e.to_string())?;
+    database.save(&config_dir.join(OUI_DATABASE_FILE)).map_err(|e| e.to_string())?;
+
+    let count = database.vendor_count();
+    let mut oui = state.oui.lock().map_err(|_| "Erreur de verrouillage".to_string())?;
+    *oui = database;
+    println!("🏷️ Table OUI mise à jour: {} fabricants", count);
+    Ok(count)
+}
+
+fn open_session_store(app: &AppHandle) -> Option<SessionStore> {
+    let opened = app.path().app_data_dir()
+        .map_err(|e| e.to_string())
+        .and_then(|data_dir| std::fs::create_dir_all(&data_dir).map(|_| data_dir).map_err(|e| e.to_string()))
+        .and_then(|data_dir| SessionStore::open(&data_dir.join(SESSION_DATABASE_FILE)).map_err(|e| e.to_string()));
+
+    let mut store = match opened {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("❌ Sessions non persistées, base indisponible: {}", e);
+            return None;
+        }
+    };
+
+    if let Ok(version) = store.schema_version() {
+        println!("🗄️ Base de sessions ouverte (schéma {})", version);
+    }
+    match store.close_interrupted_sessions() {
+        Ok(0) => {}
+        Ok(count) => println!("🗄️ {} session(s) interrompue(s) clôturée(s)", count),
+        Err(e) => eprintln!("❌ Clôture des sessions interrompues impossible: {}", e),
+    }
+    Some(store)
+}
+
+// Paramètres enregistrés, ou valeurs par défaut si le fichier est absent ou illisible
+fn load_user_settings(app: &AppHandle) -> AppSettings {
+    let Some(path) = app.path().app_config_dir().ok().map(|dir| dir.join(SETTINGS_FILE)) else {
+        return AppSettings::default();
+    };
+    if !path.exists() {
+        return AppSettings::default();
+    }
+    match AppSettings::load(&path) {
+        Ok((settings, corrections)) => {
+            for correction in corrections {
+                eprintln!("⚠️ Paramètre corrigé: {}", correction);
+            }
+            settings
+        }
+        Err(e) => {
+            eprintln!("❌ Paramètres enregistrés ignorés: {}", e);
+            AppSettings::default()
+        }
+    }
+}
+
+fn load_user_oui_database(app: &AppHandle) -> Option<OuiDatabase> {
+    let path = app.path().app_config_dir().ok()?.join(OUI_DATABASE_FILE);
+    if !path.exists() {
+        return None;
+    }
+    match OuiDatabase::load(&path) {
+        Ok(database) => Some(database),
+        Err(e) => {
+            eprintln!("❌ Table OUI utilisateur ignorée: {}", e);
+            None
+        }
+    }
+}
+
+fn load_user_fingerprint_database(app: &AppHandle) -> Option<FingerprintDatabase> {
+    let path = app.path().app_config_dir().ok()?.join(FINGERPRINT_DATABASE_FILE);
+    if !path.exists() {
+        return None;
+    }
+    match FingerprintDatabase::load(&path) {
+        Ok(database) => Some(database),
+        Err(e) => {
+            eprintln!("❌ Base d'empreintes utilisateur ignorée: {}", e);
+            None
+        }
+    }
+}
+
 #[tauri::command]
 fn test_capture(state: State<AppState>) -> Result<String, String> {
     println!("🧪 Testing capture functionality...");
     
     // Check if we have any logs
     let log_count = if let Ok(logs) = state.logs.lock() {
-        logs.len()
+        logs.count()
     } else {
         return Err("Failed to access logs".to_string());
     };
@@ -372,20 +1253,108 @@ fn main() {
     // Setup DLL path for Windows
     setup_dll_path();
     
+    let sessions = Arc::new(Mutex::new(None));
     let app_state = AppState {
-        logs: Arc::new(Mutex::new(Vec::new())),
+        logs: Arc::new(Mutex::new(LogStore::new())),
+        packet_events: PacketEvents::new(DEFAULT_COALESCE_INTERVAL_MS),
         capture: Arc::new(Mutex::new(None)),
+        relay_topology: Arc::new(Mutex::new(RelayTopology::new())),
+        transactions: Arc::new(Mutex::new(TransactionTracker::new())),
+        alerts: Arc::new(Mutex::new(AlertStore::new())),
+        server_allowlist: Arc::new(Mutex::new(ServerAllowlist::default())),
+        starvation: Arc::new(Mutex::new(StarvationDetector::new(StarvationConfig::default()))),
+        leases: Arc::new(Mutex::new(LeaseTable::new())),
+        option50_history: Arc::new(Mutex::new(Option50History::new())),
+        fingerprints: Arc::new(Mutex::new(FingerprintMatcher::new(FingerprintDatabase::embedded()))),
+        oui: Arc::new(Mutex::new(OuiDatabase::embedded())),
+        conflicts: Arc::new(Mutex::new(ConflictAnalyzer::new())),
+        latency: Arc::new(Mutex::new(LatencyStats::new())),
+        retransmissions: Arc::new(Mutex::new(RetransmissionDetector::new())),
+        nak_analysis: Arc::new(Mutex::new(NakAnalyzer::new())),
+        clock: Arc::new(Mutex::new(CaptureClock::default())),
+        session_writer: SessionWriter::start(sessions.clone()),
+        sessions,
+        retention: Arc::new(Mutex::new(RetentionPolicy::default())),
+        settings: Arc::new(Mutex::new(AppSettings::default())),
     };
 
     tauri::Builder::default()
         .manage(app_state)
+        .setup(|app| {
+            let handle = app.handle().clone();
+            app.state::<AppState>().packet_events.start(move |batch| {
+                if let Err(e) = handle.emit(PACKET_EVENT, &batch) {
+                    eprintln!("❌ Impossible d'émettre les paquets: {}", e);
+                }
+            });
+            if let Some(store) = open_session_store(app.handle()) {
+                let state = app.state::<AppState>();
+                *state.sessions.lock().map_err(|_| "Erreur de verrouillage")? = Some(store);
+            }
+            {
+                let settings = load_user_settings(app.handle());
+                let state = app.state::<AppState>();
+                if let Err(e) = apply_settings(&settings, app.handle(), &state) {
+                    eprintln!("❌ Paramètres partiellement appliqués: {}", e);
+                }
+                println!("⚙️ Paramètres chargés (version {})", settings.version);
+                *state.settings.lock().map_err(|_| "Erreur de verrouillage")? = settings;
+            }
+            if let Some(database) = load_user_fingerprint_database(app.handle()) {
+                let state = app.state::<AppState>();
+                let mut matcher = state.fingerprints.lock().map_err(|_| "Erreur de verrouillage")?;
+                matcher.set_database(database);
+                println!("🧬 Base d'empreintes utilisateur chargée: {} entrées", matcher.database_len());
+            }
+            if let Some(database) = load_user_oui_database(app.handle()) {
+                let state = app.state::<AppState>();
+                let mut oui = state.oui.lock().map_err(|_| "Erreur de verrouillage")?;
+                println!("🏷️ Table OUI utilisateur chargée: {} fabricants", database.vendor_count());
+                *oui = database;
+            }
+            Ok(())
+        })
                             .invoke_handler(tauri::generate_handler![
                         get_interfaces,
                         check_npcap,
                         start_capture,
                         stop_capture,
                         get_logs,
+                        get_logs_since,
+                        query_logs,
+                        set_settings,
+                        get_settings,
+                        update_settings,
+                        get_log_status,
+                        get_packet_event_interval,
+                        set_packet_event_interval,
                         clear_logs,
+                        get_relay_topology,
+                        get_transactions,
+                        get_latency_stats,
+                        get_struggling_clients,
+                        get_leases,
+                        get_option50_history,
+                        get_address_conflicts,
+                        get_nak_reports,
+                        get_alerts,
+                        clear_alerts,
+                        get_server_allowlist,
+                        set_server_allowlist,
+                        get_starvation_config,
+                        set_starvation_config,
+                        get_unmatched_fingerprints,
+                        export_unmatched_fingerprints,
+                        load_fingerprint_database,
+                        lookup_mac_vendor,
+                        refresh_oui_database,
+                        open_capture_file,
+                        list_sessions,
+                        open_session,
+                        get_session_transactions,
+                        delete_session,
+                        get_retention_policy,
+                        set_retention_policy,
                         check_for_updates,
                         test_capture
                     ])
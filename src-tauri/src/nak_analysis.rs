@@ -0,0 +1,553 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::net::Ipv4Addr;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use crate::dhcp_capture::{DHCPMessageType, DHCPPacket, MacAddress};
+use crate::option50_history::RequestKind;
+
+// Un NAK arrive normalement dans la seconde; au-delà la requête n'est plus pertinente
+const REQUEST_RETENTION_SECS: i64 = 300;
+const MAX_PENDING_REQUESTS: usize = 4096;
+const MAX_REPORTS: usize = 5000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Subnet {
+    network: Ipv4Addr,
+    mask: Ipv4Addr,
+}
+
+impl Subnet {
+    pub fn new(address: Ipv4Addr, mask: Ipv4Addr) -> Self {
+        Self {
+            network: Ipv4Addr::from(u32::from(address) & u32::from(mask)),
+            mask,
+        }
+    }
+
+    pub fn contains(&self, ip: Ipv4Addr) -> bool {
+        u32::from(ip) & u32::from(self.mask) == u32::from(self.network)
+    }
+}
+
+impl fmt::Display for Subnet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, u32::from(self.mask).count_ones())
+    }
+}
+
+// Point d'entrée du client dans le réseau: relais (giaddr) et port (circuit-id)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Location {
+    giaddr: Ipv4Addr,
+    circuit_id: Option<String>,
+}
+
+impl Location {
+    fn of(packet: &DHCPPacket) -> Self {
+        Self {
+            giaddr: packet.bootp.giaddr,
+            circuit_id: packet.circuit_id(),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.giaddr == Ipv4Addr::UNSPECIFIED {
+            write!(f, "segment local")?;
+        } else {
+            write!(f, "relais {}", self.giaddr)?;
+        }
+        if let Some(ref circuit_id) = self.circuit_id {
+            write!(f, " (circuit {})", circuit_id)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PendingRequest {
+    timestamp: DateTime<Utc>,
+    kind: RequestKind,
+    // Option 50, ou ciaddr en RENEWING/REBINDING
+    requested_ip: Option<Ipv4Addr>,
+    ciaddr: Ipv4Addr,
+    server_id: Option<Ipv4Addr>,
+    location: Location,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NakCause {
+    OutsideSubnet { requested_ip: String, subnet: String },
+    ServerIdMismatch { requested_server: String, nak_server: String },
+    ClientMoved { previous_location: String, current_location: String },
+}
+
+impl fmt::Display for NakCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NakCause::OutsideSubnet { requested_ip, subnet } => {
+                write!(f, "{} est hors du sous-réseau {}", requested_ip, subnet)
+            }
+            NakCause::ServerIdMismatch { requested_server, nak_server } => {
+                write!(f, "requête adressée à {} mais refusée par {}", requested_server, nak_server)
+            }
+            NakCause::ClientMoved { previous_location, current_location } => {
+                write!(f, "client déplacé de {} vers {}", previous_location, current_location)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkedRequest {
+    pub timestamp: String,
+    pub kind: RequestKind,
+    pub requested_ip: Option<String>,
+    pub ciaddr: Option<String>,
+    pub server_id: Option<String>,
+    pub latency_ms: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NakReport {
+    pub timestamp: String,
+    pub xid: String,
+    pub chaddr: String,
+    pub server: String,
+    pub giaddr: Option<String>,
+    pub circuit_id: Option<String>,
+    pub request: Option<LinkedRequest>,
+    pub causes: Vec<NakCause>,
+    // Option 56 jointe par le serveur
+    pub server_message: Option<String>,
+    pub summary: String,
+}
+
+// Relie chaque DHCPNAK à la requête qui l'a provoqué et en déduit les causes probables
+#[derive(Default)]
+pub struct NakAnalyzer {
+    pending: HashMap<(u32, MacAddress), PendingRequest>,
+    // Sous-réseaux appris des Ack (option 1), par giaddr; 0.0.0.0 désigne le segment local
+    learned_subnets: HashMap<Ipv4Addr, Subnet>,
+    // Sous-réseau du dernier bail de chaque client, seule référence pour un RENEWING
+    client_subnets: HashMap<MacAddress, Subnet>,
+    // Sous-réseaux de l'interface de capture, prioritaires pour le segment local
+    local_subnets: Vec<Subnet>,
+    last_locations: HashMap<MacAddress, Location>,
+    reports: VecDeque<NakReport>,
+}
+
+impl NakAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_local_subnets(&mut self, subnets: Vec<Subnet>) {
+        self.local_subnets = subnets;
+    }
+
+    pub fn record(&mut self, packet: &DHCPPacket) -> Option<NakReport> {
+        match packet.message_type {
+            DHCPMessageType::Request => {
+                self.record_request(packet);
+                None
+            }
+            DHCPMessageType::Ack => {
+                self.record_ack(packet);
+                None
+            }
+            DHCPMessageType::Nack => Some(self.record_nak(packet)),
+            _ => None,
+        }
+    }
+
+    fn record_request(&mut self, packet: &DHCPPacket) {
+        let Some(kind) = RequestKind::classify(packet) else {
+            return;
+        };
+
+        if self.pending.len() >= MAX_PENDING_REQUESTS {
+            let cutoff = packet.timestamp - Duration::seconds(REQUEST_RETENTION_SECS);
+            self.pending.retain(|_, request| request.timestamp >= cutoff);
+            if self.pending.len() >= MAX_PENDING_REQUESTS {
+                return;
+            }
+        }
+
+        let requested_ip = match kind {
+            RequestKind::Renewing | RequestKind::Rebinding => Some(packet.bootp.ciaddr),
+            _ => packet.option_50,
+        };
+
+        // Une retransmission remplace la précédente: le NAK répond à la plus récente
+        self.pending.insert((packet.bootp.xid, packet.bootp.chaddr), PendingRequest {
+            timestamp: packet.timestamp,
+            kind,
+            requested_ip,
+            ciaddr: packet.bootp.ciaddr,
+            server_id: packet.server_identifier(),
+            location: Location::of(packet),
+        });
+    }
+
+    fn record_ack(&mut self, packet: &DHCPPacket) {
+        let request = self.pending.remove(&(packet.bootp.xid, packet.bootp.chaddr));
+
+        // Un INFORM n'attribue rien et ne renseigne pas sur le sous-réseau du bail
+        if packet.bootp.yiaddr == Ipv4Addr::UNSPECIFIED {
+            return;
+        }
+
+        let subnet = packet.subnet_mask().map(|mask| Subnet::new(packet.bootp.yiaddr, mask));
+        if let Some(subnet) = subnet {
+            self.client_subnets.insert(packet.bootp.chaddr, subnet);
+        }
+        // Un RENEWING unicast peut venir de n'importe quel sous-réseau routé: il ne dit rien
+        // du segment où l'Ack a été capturé ni de l'emplacement du client
+        if request.as_ref().is_some_and(|request| request.kind == RequestKind::Renewing) {
+            return;
+        }
+
+        let location = request.map(|request| request.location).unwrap_or_else(|| Location::of(packet));
+        if let Some(subnet) = subnet {
+            self.learned_subnets.insert(location.giaddr, subnet);
+        }
+        self.last_locations.insert(packet.bootp.chaddr, location);
+    }
+
+    fn record_nak(&mut self, packet: &DHCPPacket) -> NakReport {
+        let chaddr = packet.bootp.chaddr;
+        let nak_server = packet.server_address();
+        let request = self.pending.remove(&(packet.bootp.xid, chaddr));
+
+        let mut causes = Vec::new();
+        if let Some(ref request) = request {
+            if let Some(cause) = self.subnet_cause(chaddr, request) {
+                causes.push(cause);
+            }
+
+            if let Some(requested_server) = request.server_id.filter(|server| *server != nak_server) {
+                causes.push(NakCause::ServerIdMismatch {
+                    requested_server: requested_server.to_string(),
+                    nak_server: nak_server.to_string(),
+                });
+            }
+
+            let previous = self.last_locations.get(&chaddr).filter(|_| request.kind != RequestKind::Renewing);
+            if let Some(previous) = previous {
+                if moved(previous, &request.location) {
+                    causes.push(NakCause::ClientMoved {
+                        previous_location: previous.to_string(),
+                        current_location: request.location.to_string(),
+                    });
+                }
+            }
+        }
+
+        let server_message = packet.server_message().map(str::to_string);
+        let mut summary = if causes.is_empty() {
+            if request.is_some() {
+                "Cause non déterminée".to_string()
+            } else {
+                "Requête d'origine non capturée".to_string()
+            }
+        } else {
+            causes.iter().map(|cause| cause.to_string()).collect::<Vec<_>>().join("; ")
+        };
+        if let Some(ref message) = server_message {
+            summary.push_str(&format!(" — serveur: \"{}\"", message));
+        }
+
+        let location = request.as_ref().map(|request| request.location.clone()).unwrap_or_else(|| Location::of(packet));
+        let report = NakReport {
+            timestamp: packet.timestamp.to_rfc3339(),
+            xid: format!("0x{:08x}", packet.bootp.xid),
+            chaddr: chaddr.to_string(),
+            server: nak_server.to_string(),
+            giaddr: Some(location.giaddr)
+                .filter(|giaddr| *giaddr != Ipv4Addr::UNSPECIFIED)
+                .map(|giaddr| giaddr.to_string()),
+            circuit_id: location.circuit_id,
+            request: request.map(|request| LinkedRequest {
+                timestamp: request.timestamp.to_rfc3339(),
+                kind: request.kind,
+                requested_ip: request.requested_ip.map(|ip| ip.to_string()),
+                ciaddr: Some(request.ciaddr)
+                    .filter(|ciaddr| *ciaddr != Ipv4Addr::UNSPECIFIED)
+                    .map(|ciaddr| ciaddr.to_string()),
+                server_id: request.server_id.map(|server| server.to_string()),
+                latency_ms: (packet.timestamp - request.timestamp)
+                    .num_microseconds()
+                    .map(|us| us as f64 / 1000.0)
+                    .unwrap_or(0.0),
+            }),
+            causes,
+            server_message,
+            summary,
+        };
+
+        if self.reports.len() >= MAX_REPORTS {
+            self.reports.pop_front();
+        }
+        self.reports.push_back(report.clone());
+        report
+    }
+
+    fn subnet_cause(&self, chaddr: MacAddress, request: &PendingRequest) -> Option<NakCause> {
+        let requested_ip = request.requested_ip?;
+        let local = request.location.giaddr == Ipv4Addr::UNSPECIFIED;
+
+        // Un RENEWING unicast ne passe ni par un relais ni forcément par le segment capturé:
+        // seul le sous-réseau du bail de ce client est comparable. Ailleurs (broadcast ou relayé),
+        // l'adresse de l'interface fait foi sur le segment local si elle est connue
+        let candidates: Vec<Subnet> = if request.kind == RequestKind::Renewing {
+            self.client_subnets.get(&chaddr).copied().into_iter().collect()
+        } else if local && !self.local_subnets.is_empty() {
+            self.local_subnets.clone()
+        } else {
+            self.learned_subnets.get(&request.location.giaddr).copied().into_iter().collect()
+        };
+
+        if candidates.is_empty() || candidates.iter().any(|subnet| subnet.contains(requested_ip)) {
+            return None;
+        }
+
+        Some(NakCause::OutsideSubnet {
+            requested_ip: requested_ip.to_string(),
+            subnet: candidates.iter().map(|subnet| subnet.to_string()).collect::<Vec<_>>().join(", "),
+        })
+    }
+
+    // Les sous-réseaux de l'interface restent valables tant que la capture continue
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.learned_subnets.clear();
+        self.client_subnets.clear();
+        self.last_locations.clear();
+        self.reports.clear();
+    }
+
+    pub fn snapshot(&self) -> Vec<NakReport> {
+        self.reports.iter().cloned().collect()
+    }
+}
+
+// Un changement de circuit-id n'est significatif que si les deux côtés en portent un
+fn moved(previous: &Location, current: &Location) -> bool {
+    if previous.giaddr != current.giaddr {
+        return true;
+    }
+    match (&previous.circuit_id, &current.circuit_id) {
+        (Some(previous), Some(current)) => previous != current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dhcp_capture::test_support::{mac, packet, SERVER_IP};
+    use crate::dhcp_options::{DhcpOption, RelaySubOption};
+
+    fn ip(text: &str) -> Ipv4Addr {
+        text.parse().unwrap()
+    }
+
+    fn location(giaddr: &str, circuit_id: Option<&str>) -> Location {
+        Location {
+            giaddr: ip(giaddr),
+            circuit_id: circuit_id.map(str::to_string),
+        }
+    }
+
+    fn relayed(mut packet: DHCPPacket, giaddr: &str, circuit_id: &[u8]) -> DHCPPacket {
+        packet.bootp.giaddr = ip(giaddr);
+        packet.options.push(DhcpOption::RelayAgentInformation(vec![RelaySubOption::CircuitId(circuit_id.to_vec())]));
+        packet
+    }
+
+    fn init_reboot(chaddr: MacAddress, xid: u32, requested_ip: &str, offset_ms: i64) -> DHCPPacket {
+        let mut request = packet(DHCPMessageType::Request, chaddr, xid, offset_ms);
+        request.option_50 = Some(ip(requested_ip));
+        request
+    }
+
+    fn renewing(chaddr: MacAddress, xid: u32, ciaddr: &str, offset_ms: i64) -> DHCPPacket {
+        let mut request = packet(DHCPMessageType::Request, chaddr, xid, offset_ms);
+        request.bootp.ciaddr = ip(ciaddr);
+        request.source_ip = ip(ciaddr);
+        request.destination_ip = SERVER_IP;
+        request
+    }
+
+    fn ack(chaddr: MacAddress, xid: u32, yiaddr: &str, mask: &str, offset_ms: i64) -> DHCPPacket {
+        let mut ack = packet(DHCPMessageType::Ack, chaddr, xid, offset_ms);
+        ack.bootp.yiaddr = ip(yiaddr);
+        ack.options.push(DhcpOption::SubnetMask(ip(mask)));
+        ack
+    }
+
+    fn nak(chaddr: MacAddress, xid: u32, offset_ms: i64) -> DHCPPacket {
+        packet(DHCPMessageType::Nack, chaddr, xid, offset_ms)
+    }
+
+    #[test]
+    fn subnet_masks_the_network_address() {
+        let subnet = Subnet::new(ip("192.168.1.77"), ip("255.255.255.0"));
+        assert_eq!(subnet, Subnet::new(ip("192.168.1.0"), ip("255.255.255.0")));
+        assert_eq!(subnet.to_string(), "192.168.1.0/24");
+        assert!(subnet.contains(ip("192.168.1.255")));
+        assert!(!subnet.contains(ip("192.168.2.1")));
+
+        let everything = Subnet::new(ip("10.1.2.3"), Ipv4Addr::UNSPECIFIED);
+        assert_eq!(everything.to_string(), "0.0.0.0/0");
+        assert!(everything.contains(ip("172.16.0.1")));
+    }
+
+    #[test]
+    fn moved_compares_relay_then_circuit() {
+        let port_1 = location("10.0.1.1", Some("port-1"));
+        assert!(!moved(&port_1, &port_1));
+        assert!(moved(&port_1, &location("10.0.1.1", Some("port-2"))));
+        assert!(moved(&port_1, &location("10.0.2.1", Some("port-1"))));
+        assert!(moved(&port_1, &location("0.0.0.0", None)));
+        // Circuit-id absent d'un côté: seul le relais compte
+        assert!(!moved(&port_1, &location("10.0.1.1", None)));
+        assert!(!moved(&location("10.0.1.1", None), &port_1));
+    }
+
+    #[test]
+    fn links_nak_to_its_request() {
+        let mut analyzer = NakAnalyzer::new();
+        let mut request = init_reboot(mac(1), 7, "192.168.1.50", 0);
+        request.options.push(DhcpOption::ServerIdentifier(ip("192.168.1.2")));
+        analyzer.record(&request);
+        let mut response = nak(mac(1), 7, 12);
+        response.options.push(DhcpOption::Message("wrong server".to_string()));
+        let report = analyzer.record(&response).unwrap();
+
+        let linked = report.request.unwrap();
+        assert_eq!(linked.kind, RequestKind::Selecting);
+        assert_eq!(linked.requested_ip.as_deref(), Some("192.168.1.50"));
+        assert_eq!(linked.server_id.as_deref(), Some("192.168.1.2"));
+        assert_eq!(linked.ciaddr, None);
+        assert_eq!(linked.latency_ms, 12.0);
+        assert!(matches!(
+            report.causes.as_slice(),
+            [NakCause::ServerIdMismatch { requested_server, nak_server }]
+                if requested_server == "192.168.1.2" && *nak_server == SERVER_IP.to_string()
+        ));
+        assert!(report.summary.ends_with("— serveur: \"wrong server\""));
+
+        // La requête a été consommée: un second NAK n'a plus d'origine
+        let report = analyzer.record(&nak(mac(1), 7, 20)).unwrap();
+        assert!(report.request.is_none());
+        assert_eq!(report.summary, "Requête d'origine non capturée");
+        assert_eq!(analyzer.snapshot().len(), 2);
+    }
+
+    #[test]
+    fn flags_request_outside_the_local_subnet() {
+        let mut analyzer = NakAnalyzer::new();
+        analyzer.set_local_subnets(vec![Subnet::new(ip("192.168.1.10"), ip("255.255.255.0"))]);
+        analyzer.record(&init_reboot(mac(1), 1, "10.9.9.9", 0));
+        let report = analyzer.record(&nak(mac(1), 1, 5)).unwrap();
+        assert!(matches!(
+            report.causes.as_slice(),
+            [NakCause::OutsideSubnet { requested_ip, subnet }] if requested_ip == "10.9.9.9" && subnet == "192.168.1.0/24"
+        ));
+
+        analyzer.record(&init_reboot(mac(2), 2, "192.168.1.60", 10));
+        let report = analyzer.record(&nak(mac(2), 2, 15)).unwrap();
+        assert!(report.causes.is_empty());
+        assert_eq!(report.summary, "Cause non déterminée");
+    }
+
+    #[test]
+    fn clear_keeps_the_local_subnets() {
+        let mut analyzer = NakAnalyzer::new();
+        analyzer.set_local_subnets(vec![Subnet::new(ip("192.168.1.10"), ip("255.255.255.0"))]);
+        analyzer.record(&init_reboot(mac(1), 1, "10.9.9.9", 0));
+        analyzer.record(&nak(mac(1), 1, 5));
+
+        // clear_logs en pleine capture: seul l'état de la capture est oublié
+        analyzer.clear();
+        assert!(analyzer.snapshot().is_empty());
+        assert!(analyzer.record(&nak(mac(1), 1, 6)).unwrap().request.is_none());
+
+        analyzer.record(&init_reboot(mac(1), 2, "10.9.9.9", 10));
+        let report = analyzer.record(&nak(mac(1), 2, 15)).unwrap();
+        assert!(matches!(
+            report.causes.as_slice(),
+            [NakCause::OutsideSubnet { subnet, .. }] if subnet == "192.168.1.0/24"
+        ));
+    }
+
+    #[test]
+    fn renewing_is_not_compared_with_the_capture_segment() {
+        let mut analyzer = NakAnalyzer::new();
+        // Capture sur le segment du serveur, client routé depuis 10.20.0.0/16
+        analyzer.set_local_subnets(vec![Subnet::new(ip("192.168.1.10"), ip("255.255.255.0"))]);
+        analyzer.record(&ack(mac(1), 1, "10.20.3.4", "255.255.0.0", 0));
+
+        analyzer.record(&renewing(mac(1), 2, "10.20.3.4", 1000));
+        let report = analyzer.record(&nak(mac(1), 2, 1005)).unwrap();
+        assert_eq!(report.request.unwrap().kind, RequestKind::Renewing);
+        assert!(report.causes.is_empty());
+
+        // Renouvellement d'une adresse étrangère au dernier bail du client
+        analyzer.record(&renewing(mac(1), 3, "172.16.0.9", 2000));
+        let report = analyzer.record(&nak(mac(1), 3, 2005)).unwrap();
+        assert!(matches!(
+            report.causes.as_slice(),
+            [NakCause::OutsideSubnet { subnet, .. }] if subnet == "10.20.0.0/16"
+        ));
+    }
+
+    #[test]
+    fn renewing_ack_does_not_teach_the_local_segment() {
+        let mut analyzer = NakAnalyzer::new();
+        analyzer.record(&renewing(mac(1), 1, "10.20.3.4", 0));
+        analyzer.record(&ack(mac(1), 1, "10.20.3.4", "255.255.0.0", 5));
+
+        // Un autre client du segment local ne doit pas être comparé à 10.20.0.0/16
+        analyzer.record(&init_reboot(mac(2), 2, "192.168.1.60", 10));
+        let report = analyzer.record(&nak(mac(2), 2, 15)).unwrap();
+        assert!(report.causes.is_empty());
+    }
+
+    #[test]
+    fn explains_nak_after_a_vlan_change() {
+        let mut analyzer = NakAnalyzer::new();
+        let laptop = mac(1);
+
+        // Bail obtenu sur le VLAN 10, port 3 du commutateur
+        let mut request = relayed(init_reboot(laptop, 1, "10.0.10.50", 0), "10.0.10.1", b"sw1/3");
+        request.options.push(DhcpOption::ServerIdentifier(SERVER_IP));
+        analyzer.record(&request);
+        analyzer.record(&relayed(ack(laptop, 1, "10.0.10.50", "255.255.255.0", 10), "10.0.10.1", b"sw1/3"));
+
+        // Un autre poste fait apprendre le sous-réseau du VLAN 20
+        analyzer.record(&relayed(ack(mac(2), 9, "10.0.20.7", "255.255.255.0", 500), "10.0.20.1", b"sw1/9"));
+
+        // Le port passe dans le VLAN 20: le client redemande son ancienne adresse
+        analyzer.record(&relayed(init_reboot(laptop, 2, "10.0.10.50", 60_000), "10.0.20.1", b"sw1/3"));
+        let report = analyzer.record(&relayed(nak(laptop, 2, 60_004), "10.0.20.1", b"sw1/3")).unwrap();
+
+        assert_eq!(report.giaddr.as_deref(), Some("10.0.20.1"));
+        assert_eq!(report.request.as_ref().unwrap().kind, RequestKind::InitReboot);
+        assert!(matches!(
+            report.causes.as_slice(),
+            [
+                NakCause::OutsideSubnet { requested_ip, subnet },
+                NakCause::ClientMoved { previous_location, current_location },
+            ] if requested_ip == "10.0.10.50"
+                && subnet == "10.0.20.0/24"
+                && previous_location.starts_with("relais 10.0.10.1")
+                && current_location.starts_with("relais 10.0.20.1")
+        ));
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
 use pcap::Device;
 
 #[derive(Debug, Clone)]
@@ -67,6 +68,26 @@ pub fn get_network_interfaces() -> Vec<NetworkInterface> {
     }
 }
 
+// Adresses IPv4 et masques de l'interface, résolue comme le fait la capture
+pub fn interface_ipv4_networks(interface_name: &str) -> Vec<(Ipv4Addr, Ipv4Addr)> {
+    let devices = Device::list().unwrap_or_default();
+    let Some(device) = devices.into_iter().find(|d| {
+        d.name == interface_name ||
+        d.name.contains(interface_name) ||
+        d.desc.as_ref().map(|desc| desc.contains(interface_name)).unwrap_or(false)
+    }) else {
+        return Vec::new();
+    };
+
+    device.addresses
+        .iter()
+        .filter_map(|address| match (address.addr, address.netmask) {
+            (IpAddr::V4(addr), Some(IpAddr::V4(netmask))) => Some((addr, netmask)),
+            _ => None,
+        })
+        .collect()
+}
+
 fn generate_clean_name(description: &str, device_name: &str, interface_count: &mut HashMap<String, u32>) -> String {
     if device_name.starts_with(r"\Device\NPF_") {
         let desc_lower = description.to_lowercase();
@@ -0,0 +1,276 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::Ipv4Addr;
+use serde::{Serialize, Deserialize};
+use crate::dhcp_capture::{DHCPMessageType, DHCPPacket, MacAddress};
+use crate::dhcp_options::format_identifier;
+
+const MAX_REQUESTS_PER_CLIENT: usize = 200;
+
+// État du client déduit d'un DHCPREQUEST (RFC 2131 §4.3.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING-KEBAB-CASE")]
+pub enum RequestKind {
+    // Discover portant une option 50: préférence exprimée avant l'offre
+    Discover,
+    Selecting,
+    InitReboot,
+    Renewing,
+    Rebinding,
+    // Combinaison d'options et de ciaddr non prévue par la RFC
+    Invalid,
+}
+
+impl RequestKind {
+    pub fn classify(packet: &DHCPPacket) -> Option<Self> {
+        match packet.message_type {
+            DHCPMessageType::Discover => return packet.option_50.map(|_| RequestKind::Discover),
+            DHCPMessageType::Request => {}
+            _ => return None,
+        }
+
+        let has_server_id = packet.server_identifier().is_some();
+        let has_requested_ip = packet.option_50.is_some();
+        let has_ciaddr = packet.bootp.ciaddr != Ipv4Addr::UNSPECIFIED;
+
+        let kind = match (has_server_id, has_requested_ip, has_ciaddr) {
+            (true, true, false) => RequestKind::Selecting,
+            (false, true, false) => RequestKind::InitReboot,
+            (false, false, true) => {
+                // Un RENEWING est envoyé en unicast au serveur, jamais via un relais
+                let broadcast = packet.destination_ip == Ipv4Addr::BROADCAST
+                    || packet.bootp.giaddr != Ipv4Addr::UNSPECIFIED;
+                if broadcast { RequestKind::Rebinding } else { RequestKind::Renewing }
+            }
+            _ => RequestKind::Invalid,
+        };
+        Some(kind)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestRecord {
+    pub timestamp: String,
+    pub xid: String,
+    pub kind: RequestKind,
+    // Option 50, ou ciaddr en RENEWING/REBINDING
+    pub requested_ip: Option<String>,
+    pub server_id: Option<String>,
+    pub last_assigned: Option<String>,
+    pub mismatch: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientRequestHistory {
+    pub client: String,
+    pub chaddr: String,
+    pub client_id: Option<String>,
+    pub last_assigned: Option<String>,
+    pub mismatch_count: u32,
+    pub requests: Vec<RequestRecord>,
+}
+
+struct ClientEntry {
+    chaddr: MacAddress,
+    client_id: Option<String>,
+    last_assigned: Option<Ipv4Addr>,
+    mismatch_count: u32,
+    requests: VecDeque<RequestRecord>,
+}
+
+// Historique des adresses demandées par client (client-id, sinon chaddr)
+#[derive(Default)]
+pub struct Option50History {
+    clients: HashMap<String, ClientEntry>,
+    // Clé choisie par les requêtes de chaque chaddr: les serveurs renvoient rarement l'option 61 dans l'Ack
+    keys_by_chaddr: HashMap<MacAddress, String>,
+}
+
+impl Option50History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, packet: &DHCPPacket) {
+        let client_id = packet.client_identifier().map(format_identifier);
+
+        if packet.message_type == DHCPMessageType::Ack {
+            let key = client_id.clone()
+                .or_else(|| self.keys_by_chaddr.get(&packet.bootp.chaddr).cloned())
+                .unwrap_or_else(|| packet.bootp.chaddr.to_string());
+            // Seuls les clients déjà suivis nous intéressent; un INFORM n'attribue rien
+            if let Some(entry) = self.clients.get_mut(&key) {
+                if packet.bootp.yiaddr != Ipv4Addr::UNSPECIFIED {
+                    entry.last_assigned = Some(packet.bootp.yiaddr);
+                }
+            } else if packet.bootp.yiaddr != Ipv4Addr::UNSPECIFIED {
+                self.clients.insert(key, ClientEntry {
+                    chaddr: packet.bootp.chaddr,
+                    client_id,
+                    last_assigned: Some(packet.bootp.yiaddr),
+                    mismatch_count: 0,
+                    requests: VecDeque::new(),
+                });
+            }
+            return;
+        }
+
+        let Some(kind) = RequestKind::classify(packet) else {
+            return;
+        };
+        let key = client_id.clone().unwrap_or_else(|| packet.bootp.chaddr.to_string());
+        self.keys_by_chaddr.insert(packet.bootp.chaddr, key.clone());
+
+        let requested_ip = match kind {
+            RequestKind::Renewing | RequestKind::Rebinding => Some(packet.bootp.ciaddr),
+            _ => packet.option_50,
+        };
+
+        let entry = self.clients.entry(key).or_insert_with(|| ClientEntry {
+            chaddr: packet.bootp.chaddr,
+            client_id,
+            last_assigned: None,
+            mismatch_count: 0,
+            requests: VecDeque::new(),
+        });
+
+        let mismatch = match (requested_ip, entry.last_assigned) {
+            (Some(requested), Some(assigned)) => requested != assigned,
+            _ => false,
+        };
+        if mismatch {
+            entry.mismatch_count += 1;
+        }
+
+        entry.requests.push_back(RequestRecord {
+            timestamp: packet.timestamp.to_rfc3339(),
+            xid: format!("0x{:08x}", packet.bootp.xid),
+            kind,
+            requested_ip: requested_ip.map(|ip| ip.to_string()),
+            server_id: packet.server_identifier().map(|ip| ip.to_string()),
+            last_assigned: entry.last_assigned.map(|ip| ip.to_string()),
+            mismatch,
+        });
+        if entry.requests.len() > MAX_REQUESTS_PER_CLIENT {
+            entry.requests.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.clients.clear();
+        self.keys_by_chaddr.clear();
+    }
+
+    pub fn snapshot(&self, mismatches_only: bool) -> Vec<ClientRequestHistory> {
+        let mut history: Vec<ClientRequestHistory> = self.clients
+            .iter()
+            .filter(|(_, entry)| !entry.requests.is_empty())
+            .filter(|(_, entry)| !mismatches_only || entry.mismatch_count > 0)
+            .map(|(client, entry)| ClientRequestHistory {
+                client: client.clone(),
+                chaddr: entry.chaddr.to_string(),
+                client_id: entry.client_id.clone(),
+                last_assigned: entry.last_assigned.map(|ip| ip.to_string()),
+                mismatch_count: entry.mismatch_count,
+                requests: entry.requests.iter().cloned().collect(),
+            })
+            .collect();
+        history.sort_by(|a, b| a.client.cmp(&b.client));
+        history
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dhcp_capture::test_support::{mac, packet, SERVER_IP};
+    use crate::dhcp_options::DhcpOption;
+
+    const CLIENT_ID: [u8; 7] = [0x01, 0x00, 0x11, 0x22, 0x33, 0x44, 0x01];
+
+    fn request(option_50: Option<Ipv4Addr>, server_id: Option<Ipv4Addr>, ciaddr: Ipv4Addr) -> DHCPPacket {
+        let mut request = packet(DHCPMessageType::Request, mac(1), 1, 0);
+        request.option_50 = option_50;
+        if let Some(server_id) = server_id {
+            request.options.push(DhcpOption::ServerIdentifier(server_id));
+        }
+        request.bootp.ciaddr = ciaddr;
+        request
+    }
+
+    fn ack(yiaddr: Ipv4Addr, offset_ms: i64) -> DHCPPacket {
+        let mut ack = packet(DHCPMessageType::Ack, mac(1), 1, offset_ms);
+        ack.bootp.yiaddr = yiaddr;
+        ack
+    }
+
+    #[test]
+    fn classifies_each_rfc_2131_request_state() {
+        let requested = Some(Ipv4Addr::new(192, 168, 1, 50));
+        let ciaddr = Ipv4Addr::new(192, 168, 1, 50);
+
+        let selecting = request(requested, Some(SERVER_IP), Ipv4Addr::UNSPECIFIED);
+        assert_eq!(RequestKind::classify(&selecting), Some(RequestKind::Selecting));
+
+        let init_reboot = request(requested, None, Ipv4Addr::UNSPECIFIED);
+        assert_eq!(RequestKind::classify(&init_reboot), Some(RequestKind::InitReboot));
+
+        let mut renewing = request(None, None, ciaddr);
+        renewing.destination_ip = SERVER_IP;
+        assert_eq!(RequestKind::classify(&renewing), Some(RequestKind::Renewing));
+
+        let rebinding = request(None, None, ciaddr);
+        assert_eq!(RequestKind::classify(&rebinding), Some(RequestKind::Rebinding));
+
+        let mut relayed = request(None, None, ciaddr);
+        relayed.destination_ip = SERVER_IP;
+        relayed.bootp.giaddr = Ipv4Addr::new(10, 0, 0, 1);
+        assert_eq!(RequestKind::classify(&relayed), Some(RequestKind::Rebinding));
+
+        let invalid = request(requested, Some(SERVER_IP), ciaddr);
+        assert_eq!(RequestKind::classify(&invalid), Some(RequestKind::Invalid));
+    }
+
+    #[test]
+    fn classifies_discover_only_with_option_50() {
+        let mut discover = packet(DHCPMessageType::Discover, mac(1), 1, 0);
+        assert_eq!(RequestKind::classify(&discover), None);
+        discover.option_50 = Some(Ipv4Addr::new(192, 168, 1, 50));
+        assert_eq!(RequestKind::classify(&discover), Some(RequestKind::Discover));
+        assert_eq!(RequestKind::classify(&ack(Ipv4Addr::new(192, 168, 1, 50), 0)), None);
+    }
+
+    #[test]
+    fn flags_request_differing_from_last_assigned() {
+        let mut history = Option50History::new();
+        history.record(&request(Some(Ipv4Addr::new(192, 168, 1, 50)), Some(SERVER_IP), Ipv4Addr::UNSPECIFIED));
+        history.record(&ack(Ipv4Addr::new(192, 168, 1, 50), 10));
+        history.record(&request(Some(Ipv4Addr::new(192, 168, 1, 60)), None, Ipv4Addr::UNSPECIFIED));
+
+        let snapshot = history.snapshot(true);
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].mismatch_count, 1);
+        let last = snapshot[0].requests.last().unwrap();
+        assert!(last.mismatch);
+        assert_eq!(last.last_assigned.as_deref(), Some("192.168.1.50"));
+    }
+
+    #[test]
+    fn resolves_ack_without_client_id_to_the_requesting_client() {
+        let with_client_id = |mut packet: DHCPPacket| {
+            packet.options.push(DhcpOption::ClientIdentifier(CLIENT_ID.to_vec()));
+            packet
+        };
+
+        let mut history = Option50History::new();
+        history.record(&with_client_id(request(Some(Ipv4Addr::new(192, 168, 1, 50)), Some(SERVER_IP), Ipv4Addr::UNSPECIFIED)));
+        // L'Ack ne reprend pas l'option 61
+        history.record(&ack(Ipv4Addr::new(192, 168, 1, 50), 10));
+        history.record(&with_client_id(request(Some(Ipv4Addr::new(192, 168, 1, 60)), None, Ipv4Addr::UNSPECIFIED)));
+
+        let snapshot = history.snapshot(false);
+        assert_eq!(snapshot.len(), 1, "l'Ack ne doit pas créer une seconde entrée");
+        assert!(snapshot[0].client_id.is_some());
+        assert_eq!(snapshot[0].last_assigned.as_deref(), Some("192.168.1.50"));
+        assert_eq!(snapshot[0].mismatch_count, 1);
+    }
+}
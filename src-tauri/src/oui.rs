@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+use crate::dhcp_capture::MacAddress;
+
+pub const OUI_DATABASE_FILE: &str = "oui.tsv";
+const EMBEDDED_TABLE: &str = include_str!("../data/oui.tsv");
+// Bit U/L du premier octet (IEEE 802): adresse administrée localement, typique des MAC aléatoires
+const LOCALLY_ADMINISTERED_BIT: u8 = 0x02;
+const MULTICAST_BIT: u8 = 0x01;
+
+#[derive(Error, Debug)]
+pub enum OuiError {
+    #[error("Impossible de lire {path}: {source}")]
+    Io { path: String, source: std::io::Error },
+    #[error("Aucun préfixe OUI reconnu dans le fichier")]
+    Empty,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacVendorInfo {
+    pub mac: String,
+    pub oui: String,
+    pub vendor: Option<String>,
+    pub locally_administered: bool,
+    pub multicast: bool,
+}
+
+pub fn is_locally_administered(mac: &MacAddress) -> bool {
+    mac.0[0] & LOCALLY_ADMINISTERED_BIT != 0
+}
+
+fn oui_key(mac: &MacAddress) -> u32 {
+    u32::from_be_bytes([0, mac.0[0], mac.0[1], mac.0[2]])
+}
+
+fn parse_prefix(text: &str) -> Option<u32> {
+    let hex: String = text.chars().filter(|c| *c != '-' && *c != ':').collect();
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(&hex, 16).ok()
+}
+
+// Découpe une ligne CSV en respectant les guillemets (format IEEE oui.csv)
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OuiDatabase {
+    vendors: HashMap<u32, String>,
+}
+
+impl OuiDatabase {
+    pub fn embedded() -> Self {
+        Self::parse(EMBEDDED_TABLE).expect("table OUI embarquée invalide")
+    }
+
+    // Accepte la table compacte (TSV), le oui.txt et le oui.csv publiés par l'IEEE
+    pub fn parse(text: &str) -> Result<Self, OuiError> {
+        let mut vendors = HashMap::new();
+        for line in text.lines() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let entry = if let Some((prefix, vendor)) = line.split_once("(hex)") {
+                // oui.txt: "00-50-56   (hex)		VMware, Inc."
+                parse_prefix(prefix.trim()).map(|key| (key, vendor.trim().to_string()))
+            } else if line.starts_with("MA-L,") {
+                // oui.csv: Registry,Assignment,Organization Name,Organization Address
+                let fields = split_csv_line(line);
+                match (fields.get(1), fields.get(2)) {
+                    (Some(prefix), Some(vendor)) => parse_prefix(prefix).map(|key| (key, vendor.trim().to_string())),
+                    _ => None,
+                }
+            } else if let Some((prefix, vendor)) = line.split_once('\t') {
+                parse_prefix(prefix.trim()).map(|key| (key, vendor.trim().to_string()))
+            } else {
+                None
+            };
+
+            if let Some((key, vendor)) = entry.filter(|(_, vendor)| !vendor.is_empty()) {
+                vendors.insert(key, vendor);
+            }
+        }
+
+        if vendors.is_empty() {
+            return Err(OuiError::Empty);
+        }
+        Ok(Self { vendors })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, OuiError> {
+        let bytes = fs::read(path).map_err(|source| OuiError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::parse(&String::from_utf8_lossy(&bytes))
+    }
+
+    // Sauvegarde au format compact, rechargé au démarrage suivant
+    pub fn save(&self, path: &Path) -> Result<(), OuiError> {
+        let mut entries: Vec<(&u32, &String)> = self.vendors.iter().collect();
+        entries.sort();
+        let mut table = String::from("# Table OUI compacte (préfixe hexadécimal, tabulation, fabricant)\n");
+        for (key, vendor) in entries {
+            table.push_str(&format!("{:06X}\t{}\n", key, vendor));
+        }
+        fs::write(path, table).map_err(|source| OuiError::Io {
+            path: path.display().to_string(),
+            source,
+        })
+    }
+
+    pub fn vendor_count(&self) -> usize {
+        self.vendors.len()
+    }
+
+    pub fn vendor(&self, mac: &MacAddress) -> Option<&str> {
+        if is_locally_administered(mac) {
+            return None;
+        }
+        self.vendors.get(&oui_key(mac)).map(String::as_str)
+    }
+
+    pub fn lookup(&self, mac: &MacAddress) -> MacVendorInfo {
+        MacVendorInfo {
+            mac: mac.to_string(),
+            oui: format!("{:02x}:{:02x}:{:02x}", mac.0[0], mac.0[1], mac.0[2]),
+            vendor: self.vendor(mac).map(str::to_string),
+            locally_administered: is_locally_administered(mac),
+            multicast: mac.0[0] & MULTICAST_BIT != 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OUI_TXT: &str = "OUI/MA-L                                                    Organization                                 \r
+company_id                                                  Organization                                 \r
+                                                            Address                                      \r
+\r
+00-50-56   (hex)\t\tVMware, Inc.\r
+005056     (base 16)\t\tVMware, Inc.\r
+\t\t\t\t3401 Hillview Avenue\r
+\t\t\t\tPalo Alto  CA  94304\r
+\t\t\t\tUS\r
+\r
+3C-22-FB   (hex)\t\tApple, Inc.\r
+3C22FB     (base 16)\t\tApple, Inc.\r
+\t\t\t\t1 Infinite Loop\r
+";
+
+    const OUI_CSV: &str = "Registry,Assignment,Organization Name,Organization Address
+MA-L,005056,\"VMware, Inc.\",3401 Hillview Avenue Palo Alto CA US 94304
+MA-L,A4C3F0,\"Intel Corporate\",Lot 8 Jalan Hi-Tech 2/3  Kulim Kedah  MY 09000
+MA-L,F01898,\"Apple, Inc. \"\"Cupertino\"\"\",1 Infinite Loop Cupertino CA US 95014
+MA-M,70B3D5000,Example Ltd,Somewhere
+";
+
+    fn mac(bytes: [u8; 6]) -> MacAddress {
+        MacAddress(bytes)
+    }
+
+    #[test]
+    fn parses_oui_txt() {
+        let database = OuiDatabase::parse(OUI_TXT).unwrap();
+        // Les lignes "(base 16)" et les adresses ne créent pas d'entrée
+        assert_eq!(database.vendor_count(), 2);
+        assert_eq!(database.vendor(&mac([0x00, 0x50, 0x56, 0x01, 0x02, 0x03])), Some("VMware, Inc."));
+        assert_eq!(database.vendor(&mac([0x3c, 0x22, 0xfb, 0xaa, 0xbb, 0xcc])), Some("Apple, Inc."));
+    }
+
+    #[test]
+    fn parses_oui_csv() {
+        let database = OuiDatabase::parse(OUI_CSV).unwrap();
+        // Les blocs MA-M et l'en-tête sont ignorés
+        assert_eq!(database.vendor_count(), 3);
+        assert_eq!(database.vendor(&mac([0x00, 0x50, 0x56, 0, 0, 1])), Some("VMware, Inc."));
+        assert_eq!(database.vendor(&mac([0xa4, 0xc3, 0xf0, 0, 0, 1])), Some("Intel Corporate"));
+        assert_eq!(database.vendor(&mac([0xf0, 0x18, 0x98, 0, 0, 1])), Some("Apple, Inc. \"Cupertino\""));
+    }
+
+    #[test]
+    fn splits_quoted_csv_fields() {
+        assert_eq!(split_csv_line("a,\"b, c\",\"d \"\"e\"\"\","), vec!["a", "b, c", "d \"e\"", ""]);
+    }
+
+    #[test]
+    fn rejects_files_without_prefixes() {
+        assert!(matches!(OuiDatabase::parse("# vide\n\nnot an oui file\n"), Err(OuiError::Empty)));
+        assert!(matches!(OuiDatabase::parse("Registry,Assignment,Organization Name\n"), Err(OuiError::Empty)));
+    }
+
+    #[test]
+    fn parses_prefix_separators() {
+        assert_eq!(parse_prefix("00-50-56"), Some(0x005056));
+        assert_eq!(parse_prefix("00:50:56"), Some(0x005056));
+        assert_eq!(parse_prefix("a4c3f0"), Some(0xa4c3f0));
+        assert_eq!(parse_prefix("0050"), None);
+        assert_eq!(parse_prefix("00505G"), None);
+    }
+
+    #[test]
+    fn flags_locally_administered_and_multicast_addresses() {
+        let database = OuiDatabase::parse("3E22FB\tNe doit pas correspondre\n005056\tVMware, Inc.\n").unwrap();
+
+        // MAC privée iOS/Android: bit U/L positionné, aucun fabricant même si le préfixe existe
+        let private = database.lookup(&mac([0x3e, 0x22, 0xfb, 0x12, 0x34, 0x56]));
+        assert!(private.locally_administered);
+        assert!(!private.multicast);
+        assert_eq!(private.vendor, None);
+        assert_eq!(private.oui, "3e:22:fb");
+
+        let global = database.lookup(&mac([0x00, 0x50, 0x56, 0x12, 0x34, 0x56]));
+        assert!(!global.locally_administered);
+        assert_eq!(global.vendor.as_deref(), Some("VMware, Inc."));
+
+        assert!(database.lookup(&mac([0x01, 0x00, 0x5e, 0, 0, 1])).multicast);
+        assert!(is_locally_administered(&mac([0x02, 0, 0, 0, 0, 0])));
+        assert!(!is_locally_administered(&mac([0xfd, 0, 0, 0, 0, 0])));
+    }
+
+    #[test]
+    fn saved_table_reloads_identically() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(OUI_DATABASE_FILE);
+        let database = OuiDatabase::parse(OUI_CSV).unwrap();
+        database.save(&path).unwrap();
+
+        let reloaded = OuiDatabase::load(&path).unwrap();
+        assert_eq!(reloaded.vendors, database.vendors);
+        assert!(matches!(OuiDatabase::load(&directory.path().join("absent.txt")), Err(OuiError::Io { .. })));
+    }
+
+    #[test]
+    fn embedded_table_parses() {
+        let database = OuiDatabase::embedded();
+        assert!(database.vendor_count() > 50);
+        assert_eq!(database.vendor(&mac([0x00, 0x00, 0x0c, 0, 0, 1])), Some("Cisco Systems, Inc"));
+    }
+}
@@ -0,0 +1,595 @@
+use std::net::Ipv4Addr;
+use thiserror::Error;
+use crate::dhcp_capture::{BootpHeader, MacAddress, BOOTP_FILE_RANGE, BOOTP_HEADER_LEN, BOOTP_SNAME_RANGE};
+
+// Types de lien libpcap (https://www.tcpdump.org/linktypes.html)
+pub const LINKTYPE_NULL: i32 = 0;
+pub const LINKTYPE_ETHERNET: i32 = 1;
+pub const LINKTYPE_RAW: i32 = 101;
+pub const LINKTYPE_LOOP: i32 = 108;
+pub const LINKTYPE_LINUX_SLL: i32 = 113;
+pub const LINKTYPE_IPV4: i32 = 228;
+// Valeurs DLT_RAW historiques selon la plateforme (BSD / OpenBSD)
+const DLT_RAW_BSD: i32 = 12;
+const DLT_RAW_OPENBSD: i32 = 14;
+
+const ETHERNET_HEADER_LEN: usize = 14;
+const VLAN_TAG_LEN: usize = 4;
+const SLL_HEADER_LEN: usize = 16;
+const NULL_HEADER_LEN: usize = 4;
+const IPV4_MIN_HEADER_LEN: usize = 20;
+const UDP_HEADER_LEN: usize = 8;
+// ARP Ethernet/IPv4 (RFC 826): 8 octets fixes + 2 x (MAC + IPv4)
+const ARP_IPV4_LEN: usize = 28;
+const ARP_HTYPE_ETHERNET: u16 = 1;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+pub const ETHERTYPE_ARP: u16 = 0x0806;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETHERTYPE_QINQ: u16 = 0x88a8;
+const ETHERTYPE_QINQ_LEGACY: u16 = 0x9100;
+const AF_INET: u32 = 2;
+const IP_PROTOCOL_UDP: u8 = 17;
+
+pub const DHCP_SERVER_PORT: u16 = 67;
+pub const DHCP_CLIENT_PORT: u16 = 68;
+pub const DHCP_MAGIC_COOKIE: u32 = 0x6382_5363;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    #[error("type de lien non supporté: {0}")]
+    UnsupportedLinkType(i32),
+    #[error("couche liaison rejetée: {0}")]
+    Link(&'static str),
+    #[error("tag VLAN rejeté: {0}")]
+    Vlan(&'static str),
+    #[error("protocole réseau non IPv4 (ethertype 0x{0:04x})")]
+    NotIpv4(u16),
+    #[error("en-tête IPv4 rejeté: {0}")]
+    Ipv4(&'static str),
+    #[error("protocole IP {0} n'est pas UDP")]
+    NotUdp(u8),
+    #[error("en-tête UDP rejeté: {0}")]
+    Udp(&'static str),
+    #[error("ports UDP {source_port} -> {destination_port} hors DHCP")]
+    NotDhcpPort { source_port: u16, destination_port: u16 },
+    #[error("en-tête BOOTP rejeté: {0}")]
+    Bootp(&'static str),
+    #[error("magic cookie DHCP invalide: 0x{0:08x}")]
+    MagicCookie(u32),
+    #[error("protocole réseau non ARP (ethertype 0x{0:04x})")]
+    NotArp(u16),
+    #[error("paquet ARP rejeté: {0}")]
+    Arp(&'static str),
+}
+
+#[derive(Debug, Clone)]
+pub struct DecodedFrame<'a> {
+    pub source_mac: Option<MacAddress>,
+    pub destination_mac: Option<MacAddress>,
+    pub vlan_ids: Vec<u16>,
+    pub source_ip: Ipv4Addr,
+    pub destination_ip: Ipv4Addr,
+    pub source_port: u16,
+    pub destination_port: u16,
+    pub bootp: BootpHeader,
+    pub sname_field: &'a [u8],
+    pub file_field: &'a [u8],
+    pub options: &'a [u8],
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArpFrame {
+    pub vlan_ids: Vec<u16>,
+    pub operation: u16,
+    pub sender_mac: MacAddress,
+    pub sender_ip: Ipv4Addr,
+    pub target_mac: MacAddress,
+    pub target_ip: Ipv4Addr,
+}
+
+struct LinkLayer<'a> {
+    source_mac: Option<MacAddress>,
+    destination_mac: Option<MacAddress>,
+    vlan_ids: Vec<u16>,
+    ethertype: u16,
+    payload: &'a [u8],
+}
+
+struct Ipv4Layer<'a> {
+    source_ip: Ipv4Addr,
+    destination_ip: Ipv4Addr,
+    protocol: u8,
+    payload: &'a [u8],
+}
+
+struct UdpLayer<'a> {
+    source_port: u16,
+    destination_port: u16,
+    payload: &'a [u8],
+}
+
+// Décode une trame complète: liaison -> (VLAN) -> IPv4 -> UDP -> BOOTP -> magic cookie
+pub fn decode_frame(link_type: i32, data: &[u8]) -> Result<DecodedFrame<'_>, DecodeError> {
+    let link = decode_link_layer(link_type, data)?;
+    if link.ethertype != ETHERTYPE_IPV4 {
+        return Err(DecodeError::NotIpv4(link.ethertype));
+    }
+
+    let ip = decode_ipv4(link.payload)?;
+    if ip.protocol != IP_PROTOCOL_UDP {
+        return Err(DecodeError::NotUdp(ip.protocol));
+    }
+
+    let udp = decode_udp(ip.payload)?;
+    let is_dhcp_port = |port| port == DHCP_SERVER_PORT || port == DHCP_CLIENT_PORT;
+    if !is_dhcp_port(udp.source_port) && !is_dhcp_port(udp.destination_port) {
+        return Err(DecodeError::NotDhcpPort {
+            source_port: udp.source_port,
+            destination_port: udp.destination_port,
+        });
+    }
+
+    let (bootp, options) = decode_bootp(udp.payload)?;
+
+    Ok(DecodedFrame {
+        source_mac: link.source_mac,
+        destination_mac: link.destination_mac,
+        vlan_ids: link.vlan_ids,
+        source_ip: ip.source_ip,
+        destination_ip: ip.destination_ip,
+        source_port: udp.source_port,
+        destination_port: udp.destination_port,
+        bootp,
+        sname_field: &udp.payload[BOOTP_SNAME_RANGE],
+        file_field: &udp.payload[BOOTP_FILE_RANGE],
+        options,
+    })
+}
+
+// Décode une requête ou réponse ARP Ethernet/IPv4, capturée en complément du DHCP
+pub fn decode_arp(link_type: i32, data: &[u8]) -> Result<ArpFrame, DecodeError> {
+    let link = decode_link_layer(link_type, data)?;
+    if link.ethertype != ETHERTYPE_ARP {
+        return Err(DecodeError::NotArp(link.ethertype));
+    }
+
+    let arp = link.payload;
+    if arp.len() < ARP_IPV4_LEN {
+        return Err(DecodeError::Arp("paquet tronqué"));
+    }
+    if read_u16(arp, 0) != ARP_HTYPE_ETHERNET || read_u16(arp, 2) != ETHERTYPE_IPV4 || arp[4] != 6 || arp[5] != 4 {
+        return Err(DecodeError::Arp("adresses autres qu'Ethernet/IPv4"));
+    }
+
+    Ok(ArpFrame {
+        vlan_ids: link.vlan_ids,
+        operation: read_u16(arp, 6),
+        sender_mac: read_mac(arp, 8),
+        sender_ip: Ipv4Addr::new(arp[14], arp[15], arp[16], arp[17]),
+        target_mac: read_mac(arp, 18),
+        target_ip: Ipv4Addr::new(arp[24], arp[25], arp[26], arp[27]),
+    })
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn read_mac(data: &[u8], offset: usize) -> MacAddress {
+    let mut mac = [0u8; 6];
+    mac.copy_from_slice(&data[offset..offset + 6]);
+    MacAddress(mac)
+}
+
+fn decode_link_layer(link_type: i32, data: &[u8]) -> Result<LinkLayer<'_>, DecodeError> {
+    match link_type {
+        LINKTYPE_ETHERNET => decode_ethernet(data),
+        LINKTYPE_LINUX_SLL => decode_linux_sll(data),
+        LINKTYPE_NULL | LINKTYPE_LOOP => decode_null(data),
+        LINKTYPE_RAW | LINKTYPE_IPV4 | DLT_RAW_BSD | DLT_RAW_OPENBSD => Ok(LinkLayer {
+            source_mac: None,
+            destination_mac: None,
+            vlan_ids: Vec::new(),
+            ethertype: ETHERTYPE_IPV4,
+            payload: data,
+        }),
+        other => Err(DecodeError::UnsupportedLinkType(other)),
+    }
+}
+
+fn decode_ethernet(data: &[u8]) -> Result<LinkLayer<'_>, DecodeError> {
+    if data.len() < ETHERNET_HEADER_LEN {
+        return Err(DecodeError::Link("trame Ethernet tronquée"));
+    }
+
+    let destination_mac = read_mac(data, 0);
+    let source_mac = read_mac(data, 6);
+    let (vlan_ids, ethertype, offset) = strip_vlan_tags(data, 12)?;
+
+    Ok(LinkLayer {
+        source_mac: Some(source_mac),
+        destination_mac: Some(destination_mac),
+        vlan_ids,
+        ethertype,
+        payload: &data[offset..],
+    })
+}
+
+// Retire les tags 802.1Q / 802.1ad empilés, renvoie les VLAN IDs, l'ethertype final et l'offset du payload
+fn strip_vlan_tags(data: &[u8], ethertype_offset: usize) -> Result<(Vec<u16>, u16, usize), DecodeError> {
+    let mut vlan_ids = Vec::new();
+    let mut offset = ethertype_offset;
+    let mut ethertype = read_u16(data, offset);
+
+    while matches!(ethertype, ETHERTYPE_VLAN | ETHERTYPE_QINQ | ETHERTYPE_QINQ_LEGACY) {
+        if data.len() < offset + 2 + VLAN_TAG_LEN {
+            return Err(DecodeError::Vlan("tag VLAN tronqué"));
+        }
+        vlan_ids.push(read_u16(data, offset + 2) & 0x0fff);
+        offset += VLAN_TAG_LEN;
+        ethertype = read_u16(data, offset);
+    }
+
+    Ok((vlan_ids, ethertype, offset + 2))
+}
+
+fn decode_linux_sll(data: &[u8]) -> Result<LinkLayer<'_>, DecodeError> {
+    if data.len() < SLL_HEADER_LEN {
+        return Err(DecodeError::Link("en-tête Linux SLL tronqué"));
+    }
+
+    // L'adresse de la couche liaison est celle de l'émetteur
+    let address_len = read_u16(data, 4) as usize;
+    let source_mac = if address_len == 6 { Some(read_mac(data, 6)) } else { None };
+    let (vlan_ids, ethertype, offset) = strip_vlan_tags(data, 14)?;
+
+    Ok(LinkLayer {
+        source_mac,
+        destination_mac: None,
+        vlan_ids,
+        ethertype,
+        payload: &data[offset..],
+    })
+}
+
+fn decode_null(data: &[u8]) -> Result<LinkLayer<'_>, DecodeError> {
+    if data.len() < NULL_HEADER_LEN {
+        return Err(DecodeError::Link("en-tête loopback tronqué"));
+    }
+
+    // La famille d'adresse est dans l'ordre d'octets de la machine de capture
+    let family = [data[0], data[1], data[2], data[3]];
+    if u32::from_le_bytes(family) != AF_INET && u32::from_be_bytes(family) != AF_INET {
+        return Err(DecodeError::Link("famille d'adresse loopback non IPv4"));
+    }
+
+    Ok(LinkLayer {
+        source_mac: None,
+        destination_mac: None,
+        vlan_ids: Vec::new(),
+        ethertype: ETHERTYPE_IPV4,
+        payload: &data[NULL_HEADER_LEN..],
+    })
+}
+
+fn decode_ipv4(data: &[u8]) -> Result<Ipv4Layer<'_>, DecodeError> {
+    if data.len() < IPV4_MIN_HEADER_LEN {
+        return Err(DecodeError::Ipv4("en-tête tronqué"));
+    }
+
+    let version = data[0] >> 4;
+    if version != 4 {
+        return Err(DecodeError::Ipv4("version différente de 4"));
+    }
+
+    let header_len = ((data[0] & 0x0f) as usize) * 4;
+    if header_len < IPV4_MIN_HEADER_LEN {
+        return Err(DecodeError::Ipv4("IHL inférieur à 5"));
+    }
+    if data.len() < header_len {
+        return Err(DecodeError::Ipv4("options IPv4 tronquées"));
+    }
+
+    let total_len = read_u16(data, 2) as usize;
+    if total_len < header_len {
+        return Err(DecodeError::Ipv4("longueur totale inférieure à l'en-tête"));
+    }
+
+    // Offset de fragment non nul ou bit MF: seul le premier fragment complet est exploitable
+    let fragment = read_u16(data, 6);
+    if fragment & 0x3fff != 0 {
+        return Err(DecodeError::Ipv4("paquet fragmenté"));
+    }
+
+    // Ignorer le bourrage Ethernet au-delà de la longueur totale
+    let end = total_len.min(data.len());
+
+    Ok(Ipv4Layer {
+        source_ip: Ipv4Addr::new(data[12], data[13], data[14], data[15]),
+        destination_ip: Ipv4Addr::new(data[16], data[17], data[18], data[19]),
+        protocol: data[9],
+        payload: &data[header_len..end],
+    })
+}
+
+fn decode_udp(data: &[u8]) -> Result<UdpLayer<'_>, DecodeError> {
+    if data.len() < UDP_HEADER_LEN {
+        return Err(DecodeError::Udp("en-tête tronqué"));
+    }
+
+    let length = read_u16(data, 4) as usize;
+    if length < UDP_HEADER_LEN {
+        return Err(DecodeError::Udp("longueur inférieure à l'en-tête"));
+    }
+
+    let end = length.min(data.len());
+
+    Ok(UdpLayer {
+        source_port: read_u16(data, 0),
+        destination_port: read_u16(data, 2),
+        payload: &data[UDP_HEADER_LEN..end],
+    })
+}
+
+fn decode_bootp(data: &[u8]) -> Result<(BootpHeader, &[u8]), DecodeError> {
+    let header = BootpHeader::parse(data).ok_or(DecodeError::Bootp("en-tête fixe tronqué"))?;
+
+    if data.len() < BOOTP_HEADER_LEN + 4 {
+        return Err(DecodeError::Bootp("magic cookie absent"));
+    }
+
+    let cookie = read_u32(data, BOOTP_HEADER_LEN);
+    if cookie != DHCP_MAGIC_COOKIE {
+        return Err(DecodeError::MagicCookie(cookie));
+    }
+
+    Ok((header, &data[BOOTP_HEADER_LEN + 4..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIENT_MAC: [u8; 6] = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55];
+    const BROADCAST_MAC: [u8; 6] = [0xff; 6];
+
+    fn dhcp_payload(message_type: u8) -> Vec<u8> {
+        let mut payload = vec![0u8; BOOTP_HEADER_LEN];
+        payload[0] = 1; // BOOTREQUEST
+        payload[1] = 1; // Ethernet
+        payload[2] = 6;
+        payload[4..8].copy_from_slice(&0xdead_beefu32.to_be_bytes());
+        payload[10] = 0x80; // broadcast
+        payload[28..34].copy_from_slice(&CLIENT_MAC);
+        payload.extend_from_slice(&DHCP_MAGIC_COOKIE.to_be_bytes());
+        payload.extend_from_slice(&[53, 1, message_type, 255]);
+        payload
+    }
+
+    fn udp(source_port: u16, destination_port: u16, payload: &[u8]) -> Vec<u8> {
+        let mut segment = Vec::new();
+        segment.extend_from_slice(&source_port.to_be_bytes());
+        segment.extend_from_slice(&destination_port.to_be_bytes());
+        segment.extend_from_slice(&((UDP_HEADER_LEN + payload.len()) as u16).to_be_bytes());
+        segment.extend_from_slice(&[0, 0]);
+        segment.extend_from_slice(payload);
+        segment
+    }
+
+    fn ipv4_with_options(protocol: u8, ip_options: &[u8], payload: &[u8]) -> Vec<u8> {
+        assert_eq!(ip_options.len() % 4, 0);
+        let header_len = IPV4_MIN_HEADER_LEN + ip_options.len();
+        let mut packet = vec![0u8; IPV4_MIN_HEADER_LEN];
+        packet[0] = 0x40 | (header_len / 4) as u8;
+        packet[2..4].copy_from_slice(&((header_len + payload.len()) as u16).to_be_bytes());
+        packet[8] = 64;
+        packet[9] = protocol;
+        packet[12..16].copy_from_slice(&[0, 0, 0, 0]);
+        packet[16..20].copy_from_slice(&[255, 255, 255, 255]);
+        packet.extend_from_slice(ip_options);
+        packet.extend_from_slice(payload);
+        packet
+    }
+
+    fn ipv4(protocol: u8, payload: &[u8]) -> Vec<u8> {
+        ipv4_with_options(protocol, &[], payload)
+    }
+
+    fn ethernet(vlan_tags: &[(u16, u16)], ethertype: u16, payload: &[u8]) -> Vec<u8> {
+        let mut frame = Vec::new();
+        frame.extend_from_slice(&BROADCAST_MAC);
+        frame.extend_from_slice(&CLIENT_MAC);
+        for &(tpid, vlan_id) in vlan_tags {
+            frame.extend_from_slice(&tpid.to_be_bytes());
+            frame.extend_from_slice(&vlan_id.to_be_bytes());
+        }
+        frame.extend_from_slice(&ethertype.to_be_bytes());
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    fn discover_frame() -> Vec<u8> {
+        let segment = udp(DHCP_CLIENT_PORT, DHCP_SERVER_PORT, &dhcp_payload(1));
+        ethernet(&[], ETHERTYPE_IPV4, &ipv4(IP_PROTOCOL_UDP, &segment))
+    }
+
+    #[test]
+    fn decodes_plain_ethernet_frame() {
+        let data = discover_frame();
+        let frame = decode_frame(LINKTYPE_ETHERNET, &data).unwrap();
+
+        assert_eq!(frame.source_mac, Some(MacAddress(CLIENT_MAC)));
+        assert_eq!(frame.destination_mac, Some(MacAddress(BROADCAST_MAC)));
+        assert!(frame.vlan_ids.is_empty());
+        assert_eq!(frame.source_ip, Ipv4Addr::UNSPECIFIED);
+        assert_eq!(frame.destination_ip, Ipv4Addr::BROADCAST);
+        assert_eq!(frame.source_port, DHCP_CLIENT_PORT);
+        assert_eq!(frame.destination_port, DHCP_SERVER_PORT);
+        assert_eq!(frame.bootp.xid, 0xdead_beef);
+        assert_eq!(frame.bootp.chaddr, MacAddress(CLIENT_MAC));
+        assert!(frame.bootp.is_broadcast());
+        assert_eq!(frame.options, &[53, 1, 1, 255]);
+    }
+
+    #[test]
+    fn decodes_802_1q_and_qinq_tags() {
+        let segment = udp(DHCP_CLIENT_PORT, DHCP_SERVER_PORT, &dhcp_payload(1));
+        let packet = ipv4(IP_PROTOCOL_UDP, &segment);
+
+        let single = ethernet(&[(ETHERTYPE_VLAN, 42)], ETHERTYPE_IPV4, &packet);
+        let frame = decode_frame(LINKTYPE_ETHERNET, &single).unwrap();
+        assert_eq!(frame.vlan_ids, vec![42]);
+        assert_eq!(frame.bootp.xid, 0xdead_beef);
+
+        // Les bits PCP/DEI ne doivent pas polluer le VLAN ID
+        let stacked = ethernet(&[(ETHERTYPE_QINQ, 0xe064), (ETHERTYPE_VLAN, 200)], ETHERTYPE_IPV4, &packet);
+        let frame = decode_frame(LINKTYPE_ETHERNET, &stacked).unwrap();
+        assert_eq!(frame.vlan_ids, vec![100, 200]);
+        assert_eq!(frame.options, &[53, 1, 1, 255]);
+    }
+
+    #[test]
+    fn honours_ipv4_header_length() {
+        let segment = udp(DHCP_CLIENT_PORT, DHCP_SERVER_PORT, &dhcp_payload(3));
+        // Option Router Alert (4 octets) -> IHL = 6
+        let packet = ipv4_with_options(IP_PROTOCOL_UDP, &[0x94, 0x04, 0x00, 0x00], &segment);
+        let data = ethernet(&[], ETHERTYPE_IPV4, &packet);
+
+        let frame = decode_frame(LINKTYPE_ETHERNET, &data).unwrap();
+        assert_eq!(frame.source_port, DHCP_CLIENT_PORT);
+        assert_eq!(frame.options, &[53, 1, 3, 255]);
+    }
+
+    #[test]
+    fn ignores_ethernet_trailer_padding() {
+        let mut data = discover_frame();
+        data.extend_from_slice(&[0xaa; 16]);
+
+        let frame = decode_frame(LINKTYPE_ETHERNET, &data).unwrap();
+        assert_eq!(frame.options, &[53, 1, 1, 255]);
+    }
+
+    #[test]
+    fn decodes_non_ethernet_link_types() {
+        let segment = udp(DHCP_SERVER_PORT, DHCP_CLIENT_PORT, &dhcp_payload(2));
+        let packet = ipv4(IP_PROTOCOL_UDP, &segment);
+
+        let raw = decode_frame(LINKTYPE_RAW, &packet).unwrap();
+        assert_eq!(raw.source_mac, None);
+        assert_eq!(raw.options, &[53, 1, 2, 255]);
+
+        let mut sll = vec![0, 0, 0, 1, 0, 6];
+        sll.extend_from_slice(&CLIENT_MAC);
+        sll.extend_from_slice(&[0, 0]);
+        sll.extend_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+        sll.extend_from_slice(&packet);
+        let frame = decode_frame(LINKTYPE_LINUX_SLL, &sll).unwrap();
+        assert_eq!(frame.source_mac, Some(MacAddress(CLIENT_MAC)));
+        assert_eq!(frame.destination_port, DHCP_CLIENT_PORT);
+
+        let mut null = AF_INET.to_le_bytes().to_vec();
+        null.extend_from_slice(&packet);
+        assert!(decode_frame(LINKTYPE_NULL, &null).is_ok());
+    }
+
+    #[test]
+    fn rejects_each_layer_with_typed_error() {
+        assert_eq!(
+            decode_frame(147, &discover_frame()).unwrap_err(),
+            DecodeError::UnsupportedLinkType(147)
+        );
+        assert!(matches!(
+            decode_frame(LINKTYPE_ETHERNET, &[0u8; 10]).unwrap_err(),
+            DecodeError::Link(_)
+        ));
+        assert!(matches!(
+            decode_frame(LINKTYPE_ETHERNET, &ethernet(&[(ETHERTYPE_VLAN, 1)], ETHERTYPE_IPV4, &[])[..16]).unwrap_err(),
+            DecodeError::Vlan(_)
+        ));
+        assert_eq!(
+            decode_frame(LINKTYPE_ETHERNET, &ethernet(&[], 0x0806, &[0u8; 28])).unwrap_err(),
+            DecodeError::NotIpv4(0x0806)
+        );
+        assert!(matches!(
+            decode_frame(LINKTYPE_ETHERNET, &ethernet(&[], ETHERTYPE_IPV4, &[0x45; 10])).unwrap_err(),
+            DecodeError::Ipv4(_)
+        ));
+        assert_eq!(
+            decode_frame(LINKTYPE_ETHERNET, &ethernet(&[], ETHERTYPE_IPV4, &ipv4(6, &[0u8; 20]))).unwrap_err(),
+            DecodeError::NotUdp(6)
+        );
+        assert!(matches!(
+            decode_frame(LINKTYPE_ETHERNET, &ethernet(&[], ETHERTYPE_IPV4, &ipv4(IP_PROTOCOL_UDP, &[0u8; 4]))).unwrap_err(),
+            DecodeError::Udp(_)
+        ));
+
+        let dns = udp(5353, 53, &[0u8; 12]);
+        assert_eq!(
+            decode_frame(LINKTYPE_ETHERNET, &ethernet(&[], ETHERTYPE_IPV4, &ipv4(IP_PROTOCOL_UDP, &dns))).unwrap_err(),
+            DecodeError::NotDhcpPort { source_port: 5353, destination_port: 53 }
+        );
+
+        let short = udp(DHCP_CLIENT_PORT, DHCP_SERVER_PORT, &[0u8; 100]);
+        assert!(matches!(
+            decode_frame(LINKTYPE_ETHERNET, &ethernet(&[], ETHERTYPE_IPV4, &ipv4(IP_PROTOCOL_UDP, &short))).unwrap_err(),
+            DecodeError::Bootp(_)
+        ));
+
+        let mut bootp_only = dhcp_payload(1);
+        bootp_only[BOOTP_HEADER_LEN..BOOTP_HEADER_LEN + 4].copy_from_slice(&[1, 2, 3, 4]);
+        let segment = udp(DHCP_CLIENT_PORT, DHCP_SERVER_PORT, &bootp_only);
+        assert_eq!(
+            decode_frame(LINKTYPE_ETHERNET, &ethernet(&[], ETHERTYPE_IPV4, &ipv4(IP_PROTOCOL_UDP, &segment))).unwrap_err(),
+            DecodeError::MagicCookie(0x0102_0304)
+        );
+    }
+
+    #[test]
+    fn rejects_ipv4_fragments_and_bad_ihl() {
+        let segment = udp(DHCP_CLIENT_PORT, DHCP_SERVER_PORT, &dhcp_payload(1));
+
+        let mut fragment = ipv4(IP_PROTOCOL_UDP, &segment);
+        fragment[6] = 0x20; // More Fragments
+        assert_eq!(
+            decode_frame(LINKTYPE_RAW, &fragment).unwrap_err(),
+            DecodeError::Ipv4("paquet fragmenté")
+        );
+
+        let mut bad_ihl = ipv4(IP_PROTOCOL_UDP, &segment);
+        bad_ihl[0] = 0x44;
+        assert_eq!(
+            decode_frame(LINKTYPE_RAW, &bad_ihl).unwrap_err(),
+            DecodeError::Ipv4("IHL inférieur à 5")
+        );
+    }
+
+    #[test]
+    fn decodes_arp_reply() {
+        let squatter = [0x02, 0xaa, 0xbb, 0xcc, 0xdd, 0xee];
+        let mut arp = Vec::new();
+        arp.extend_from_slice(&ARP_HTYPE_ETHERNET.to_be_bytes());
+        arp.extend_from_slice(&ETHERTYPE_IPV4.to_be_bytes());
+        arp.extend_from_slice(&[6, 4, 0, 2]);
+        arp.extend_from_slice(&squatter);
+        arp.extend_from_slice(&[192, 168, 1, 50]);
+        arp.extend_from_slice(&CLIENT_MAC);
+        arp.extend_from_slice(&[0, 0, 0, 0]);
+        let data = ethernet(&[(ETHERTYPE_VLAN, 10)], ETHERTYPE_ARP, &arp);
+
+        let frame = decode_arp(LINKTYPE_ETHERNET, &data).unwrap();
+        assert_eq!(frame.vlan_ids, vec![10]);
+        assert_eq!(frame.operation, 2);
+        assert_eq!(frame.sender_mac, MacAddress(squatter));
+        assert_eq!(frame.sender_ip, Ipv4Addr::new(192, 168, 1, 50));
+        assert_eq!(frame.target_mac, MacAddress(CLIENT_MAC));
+
+        assert_eq!(decode_frame(LINKTYPE_ETHERNET, &data).unwrap_err(), DecodeError::NotIpv4(ETHERTYPE_ARP));
+        assert_eq!(decode_arp(LINKTYPE_ETHERNET, &discover_frame()).unwrap_err(), DecodeError::NotArp(ETHERTYPE_IPV4));
+        assert_eq!(decode_arp(LINKTYPE_ETHERNET, &data[..30]).unwrap_err(), DecodeError::Arp("paquet tronqué"));
+    }
+}
@@ -0,0 +1,216 @@
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::log_store::{LogBatch, LogWindow};
+use crate::DHCPLog;
+
+pub const PACKET_EVENT: &str = "dhcp://packet";
+pub const DEFAULT_COALESCE_INTERVAL_MS: u64 = 100;
+pub const MAX_COALESCE_INTERVAL_MS: u64 = 5000;
+// Au-delà, le lot part sans attendre la fin de l'intervalle
+const MAX_BATCH_SIZE: usize = 1000;
+
+// Log accompagné de l'état du LogStore au moment de son insertion
+type SessionLog = (LogWindow, DHCPLog);
+
+// Regroupe les paquets capturés en lots émis au plus une fois par intervalle
+#[derive(Clone)]
+pub struct PacketEvents {
+    sender: Sender<SessionLog>,
+    receiver: Arc<Mutex<Option<Receiver<SessionLog>>>>,
+    interval_ms: Arc<Mutex<u64>>,
+}
+
+impl PacketEvents {
+    pub fn new(interval_ms: u64) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            sender,
+            receiver: Arc::new(Mutex::new(Some(receiver))),
+            interval_ms: Arc::new(Mutex::new(interval_ms)),
+        }
+    }
+
+    pub fn interval_ms(&self) -> u64 {
+        self.interval_ms.lock().map(|interval| *interval).unwrap_or(DEFAULT_COALESCE_INTERVAL_MS)
+    }
+
+    pub fn set_interval_ms(&self, interval_ms: u64) {
+        if let Ok(mut interval) = self.interval_ms.lock() {
+            *interval = interval_ms;
+        }
+    }
+
+    pub fn publish(&self, window: LogWindow, log: DHCPLog) {
+        // Les paquets publiés avant `start` attendent dans le canal
+        let _ = self.sender.send((window, log));
+    }
+
+    // Démarre le thread d'émission; sans effet s'il tourne déjà
+    pub fn start<F>(&self, emit: F)
+    where
+        F: Fn(LogBatch) + Send + 'static,
+    {
+        let Some(receiver) = self.receiver.lock().ok().and_then(|mut receiver| receiver.take()) else {
+            return;
+        };
+        let interval_ms = self.interval_ms.clone();
+
+        thread::spawn(move || {
+            let mut next = receiver.recv().ok();
+
+            // Le premier paquet d'un lot ouvre la fenêtre de regroupement
+            while let Some((mut window, first)) = next.take() {
+                let interval = interval_ms.lock().map(|interval| *interval).unwrap_or(DEFAULT_COALESCE_INTERVAL_MS);
+                let deadline = Instant::now() + Duration::from_millis(interval);
+                let mut logs = vec![first];
+                let mut disconnected = false;
+
+                while logs.len() < MAX_BATCH_SIZE {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    match receiver.recv_timeout(remaining) {
+                        // Un lot ne mélange jamais deux sessions
+                        Ok((log_window, log)) if log_window.session != window.session => {
+                            next = Some((log_window, log));
+                            break;
+                        }
+                        Ok((log_window, log)) => {
+                            window = log_window;
+                            logs.push(log);
+                        }
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => {
+                            disconnected = true;
+                            break;
+                        }
+                    }
+                }
+
+                let cursor = logs.last().map(|log| log.id).unwrap_or_default();
+                emit(LogBatch {
+                    session: window.session,
+                    cursor,
+                    capacity: window.capacity,
+                    overwritten: window.overwritten,
+                    logs,
+                });
+                if disconnected {
+                    break;
+                }
+                if next.is_none() {
+                    next = receiver.recv().ok();
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn window(session: u64) -> LogWindow {
+        LogWindow { session, capacity: 5000, overwritten: 0 }
+    }
+
+    fn log(id: u64) -> DHCPLog {
+        DHCPLog { id, ..Default::default() }
+    }
+
+    fn started(events: &PacketEvents) -> Receiver<LogBatch> {
+        let (sender, batches) = mpsc::channel();
+        events.start(move |batch| {
+            let _ = sender.send(batch);
+        });
+        batches
+    }
+
+    fn ids(batch: &LogBatch) -> Vec<u64> {
+        batch.logs.iter().map(|log| log.id).collect()
+    }
+
+    #[test]
+    fn coalesces_packets_within_the_interval() {
+        let events = PacketEvents::new(200);
+        let batches = started(&events);
+        let opened = Instant::now();
+        for id in 1..=3 {
+            events.publish(window(1), log(id));
+        }
+
+        let batch = batches.recv_timeout(TIMEOUT).unwrap();
+        assert!(opened.elapsed() >= Duration::from_millis(200));
+        assert_eq!(ids(&batch), vec![1, 2, 3]);
+        assert_eq!((batch.session, batch.cursor, batch.capacity), (1, 3, 5000));
+
+        // Un paquet isolé ouvre un nouveau lot
+        events.publish(window(1), log(4));
+        assert_eq!(ids(&batches.recv_timeout(TIMEOUT).unwrap()), vec![4]);
+    }
+
+    #[test]
+    fn full_batch_leaves_before_the_interval() {
+        let events = PacketEvents::new(1000);
+        // Paquets en attente avant le démarrage: le premier lot est plein d'emblée
+        for id in 1..=(MAX_BATCH_SIZE as u64 + 5) {
+            events.publish(window(1), log(id));
+        }
+        let opened = Instant::now();
+        let batches = started(&events);
+
+        let batch = batches.recv_timeout(TIMEOUT).unwrap();
+        assert!(opened.elapsed() < Duration::from_millis(1000));
+        assert_eq!(batch.logs.len(), MAX_BATCH_SIZE);
+        assert_eq!(batch.cursor, MAX_BATCH_SIZE as u64);
+
+        let rest = batches.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!(ids(&rest), (MAX_BATCH_SIZE as u64 + 1..=MAX_BATCH_SIZE as u64 + 5).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn never_mixes_two_sessions() {
+        let events = PacketEvents::new(200);
+        events.publish(window(1), log(1));
+        events.publish(window(1), log(2));
+        // clear_logs entre deux paquets: nouvelle session du LogStore, ids repartis de 1
+        events.publish(window(2), log(1));
+        events.publish(window(2), log(2));
+        let batches = started(&events);
+
+        let first = batches.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!((first.session, ids(&first)), (1, vec![1, 2]));
+        let second = batches.recv_timeout(TIMEOUT).unwrap();
+        assert_eq!((second.session, ids(&second)), (2, vec![1, 2]));
+        assert_eq!(second.cursor, 2);
+    }
+
+    #[test]
+    fn interval_change_applies_to_the_next_batch() {
+        let events = PacketEvents::new(MAX_COALESCE_INTERVAL_MS);
+        let batches = started(&events);
+        events.set_interval_ms(0);
+        assert_eq!(events.interval_ms(), 0);
+
+        let opened = Instant::now();
+        events.publish(window(1), log(1));
+        assert_eq!(ids(&batches.recv_timeout(TIMEOUT).unwrap()), vec![1]);
+        assert!(opened.elapsed() < Duration::from_millis(MAX_COALESCE_INTERVAL_MS));
+    }
+
+    #[test]
+    fn start_runs_only_once() {
+        let events = PacketEvents::new(0);
+        let batches = started(&events);
+        let (sender, second) = mpsc::channel();
+        events.start(move |batch| {
+            let _ = sender.send(batch);
+        });
+
+        events.publish(window(1), log(1));
+        assert_eq!(ids(&batches.recv_timeout(TIMEOUT).unwrap()), vec![1]);
+        assert!(second.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+}
@@ -0,0 +1,393 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+
+// Blocs et options pcapng (draft-ietf-opsawg-pcapng)
+const BLOCK_SECTION_HEADER: u32 = 0x0A0D_0D0A;
+const BLOCK_INTERFACE_DESCRIPTION: u32 = 0x0000_0001;
+const BLOCK_ENHANCED_PACKET: u32 = 0x0000_0006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+const OPT_END_OF_OPT: u16 = 0;
+const OPT_COMMENT: u16 = 1;
+const SHB_OS: u16 = 3;
+const SHB_USERAPPL: u16 = 4;
+const IF_NAME: u16 = 2;
+const IF_DESCRIPTION: u16 = 3;
+const IF_TSRESOL: u16 = 9;
+// Horodatage en nanosecondes (10^-9)
+const TSRESOL_NANOS: u8 = 9;
+const SNAPLEN: u32 = 65_535;
+// Noms produits par next_file_path: dhcp-monitor_AAAAMMJJ_HHMMSS[_N].pcapng
+const FILE_PREFIX: &str = "dhcp-monitor_";
+const FILE_EXTENSION: &str = ".pcapng";
+const STAMP_FORMAT: &str = "%Y%m%d_%H%M%S";
+const STAMP_LEN: usize = 15;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingOptions {
+    pub directory: String,
+    #[serde(default)]
+    pub max_file_size_mb: Option<u64>,
+    #[serde(default)]
+    pub rotate_every_minutes: Option<u64>,
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+}
+
+fn default_max_files() -> usize {
+    10
+}
+
+struct PcapngWriter {
+    out: BufWriter<File>,
+    bytes_written: u64,
+}
+
+impl PcapngWriter {
+    fn create(path: &Path, interface_name: &str, interface_description: Option<&str>, link_type: i32) -> io::Result<Self> {
+        let mut writer = Self {
+            out: BufWriter::new(File::create(path)?),
+            bytes_written: 0,
+        };
+
+        let comment = format!("Enregistré par DHCP Monitor v{}", env!("CARGO_PKG_VERSION"));
+        let application = format!("DHCP Monitor {}", env!("CARGO_PKG_VERSION"));
+        let mut shb = Vec::new();
+        shb.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        shb.extend_from_slice(&1u16.to_le_bytes());
+        shb.extend_from_slice(&0u16.to_le_bytes());
+        // Longueur de section inconnue
+        shb.extend_from_slice(&(-1i64).to_le_bytes());
+        push_option(&mut shb, OPT_COMMENT, comment.as_bytes());
+        push_option(&mut shb, SHB_OS, std::env::consts::OS.as_bytes());
+        push_option(&mut shb, SHB_USERAPPL, application.as_bytes());
+        push_option(&mut shb, OPT_END_OF_OPT, &[]);
+        writer.write_block(BLOCK_SECTION_HEADER, &shb)?;
+
+        let mut idb = Vec::new();
+        idb.extend_from_slice(&(link_type as u16).to_le_bytes());
+        idb.extend_from_slice(&0u16.to_le_bytes());
+        idb.extend_from_slice(&SNAPLEN.to_le_bytes());
+        push_option(&mut idb, IF_NAME, interface_name.as_bytes());
+        if let Some(description) = interface_description {
+            push_option(&mut idb, IF_DESCRIPTION, description.as_bytes());
+        }
+        push_option(&mut idb, IF_TSRESOL, &[TSRESOL_NANOS]);
+        push_option(&mut idb, OPT_END_OF_OPT, &[]);
+        writer.write_block(BLOCK_INTERFACE_DESCRIPTION, &idb)?;
+
+        Ok(writer)
+    }
+
+    fn write_packet(&mut self, timestamp: DateTime<Utc>, data: &[u8], original_len: u32) -> io::Result<()> {
+        let nanos = timestamp.timestamp_nanos_opt().unwrap_or(0) as u64;
+
+        let mut epb = Vec::with_capacity(20 + data.len() + 3);
+        epb.extend_from_slice(&0u32.to_le_bytes()); // interface 0
+        epb.extend_from_slice(&((nanos >> 32) as u32).to_le_bytes());
+        epb.extend_from_slice(&(nanos as u32).to_le_bytes());
+        epb.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        epb.extend_from_slice(&original_len.to_le_bytes());
+        epb.extend_from_slice(data);
+        pad_to_32_bits(&mut epb);
+        self.write_block(BLOCK_ENHANCED_PACKET, &epb)
+    }
+
+    fn write_block(&mut self, block_type: u32, body: &[u8]) -> io::Result<()> {
+        let total_len = (body.len() + 12) as u32;
+        self.out.write_all(&block_type.to_le_bytes())?;
+        self.out.write_all(&total_len.to_le_bytes())?;
+        self.out.write_all(body)?;
+        self.out.write_all(&total_len.to_le_bytes())?;
+        self.bytes_written += total_len as u64;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+fn push_option(buffer: &mut Vec<u8>, code: u16, value: &[u8]) {
+    buffer.extend_from_slice(&code.to_le_bytes());
+    buffer.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    buffer.extend_from_slice(value);
+    pad_to_32_bits(buffer);
+}
+
+fn pad_to_32_bits(buffer: &mut Vec<u8>) {
+    while buffer.len() % 4 != 0 {
+        buffer.push(0);
+    }
+}
+
+// Enregistre les trames DHCP dans des fichiers pcapng avec rotation par taille ou par durée
+pub struct CaptureRecorder {
+    options: RecordingOptions,
+    directory: PathBuf,
+    interface_name: String,
+    interface_description: Option<String>,
+    link_type: i32,
+    writer: Option<PcapngWriter>,
+    opened_at: Instant,
+    current: Option<PathBuf>,
+}
+
+impl CaptureRecorder {
+    pub fn new(
+        options: RecordingOptions,
+        interface_name: &str,
+        interface_description: Option<&str>,
+        link_type: i32,
+    ) -> io::Result<Self> {
+        if options.max_files == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "max_files doit être au moins 1"));
+        }
+
+        let directory = PathBuf::from(&options.directory);
+        fs::create_dir_all(&directory)?;
+
+        let mut recorder = Self {
+            options,
+            directory,
+            interface_name: interface_name.to_string(),
+            interface_description: interface_description.map(str::to_string),
+            link_type,
+            writer: None,
+            opened_at: Instant::now(),
+            current: None,
+        };
+        recorder.rotate()?;
+        Ok(recorder)
+    }
+
+    pub fn write_packet(&mut self, timestamp: DateTime<Utc>, data: &[u8], original_len: u32) -> io::Result<()> {
+        if self.should_rotate() {
+            self.rotate()?;
+        }
+
+        if let Some(writer) = self.writer.as_mut() {
+            writer.write_packet(timestamp, data, original_len)?;
+            // Vider à chaque trame: le trafic DHCP est faible et le fichier doit rester exploitable en cas d'arrêt brutal
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    fn should_rotate(&self) -> bool {
+        let Some(writer) = self.writer.as_ref() else {
+            return true;
+        };
+
+        let size_exceeded = self.options.max_file_size_mb
+            .map(|mb| writer.bytes_written >= mb * 1024 * 1024)
+            .unwrap_or(false);
+        let age_exceeded = self.options.rotate_every_minutes
+            .map(|minutes| self.opened_at.elapsed() >= Duration::from_secs(minutes * 60))
+            .unwrap_or(false);
+
+        size_exceeded || age_exceeded
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush()?;
+        }
+
+        let path = self.next_file_path();
+        println!("💾 Enregistrement pcapng vers {}", path.display());
+        self.writer = Some(PcapngWriter::create(
+            &path,
+            &self.interface_name,
+            self.interface_description.as_deref(),
+            self.link_type,
+        )?);
+        self.opened_at = Instant::now();
+        self.current = Some(path);
+        self.prune()
+    }
+
+    // Le quota porte sur le dossier entier: les fichiers des sessions précédentes comptent aussi
+    fn prune(&self) -> io::Result<()> {
+        let mut files = self.recordings()?;
+        files.retain(|path| Some(path) != self.current.as_ref());
+        let excess = (files.len() + 1).saturating_sub(self.options.max_files);
+
+        for oldest in &files[..excess] {
+            println!("🗑️ Suppression de l'ancien enregistrement {}", oldest.display());
+            if let Err(e) = fs::remove_file(oldest) {
+                eprintln!("❌ Impossible de supprimer {}: {}", oldest.display(), e);
+            }
+        }
+        Ok(())
+    }
+
+    // Enregistrements présents dans le dossier, du plus ancien au plus récent
+    fn recordings(&self) -> io::Result<Vec<PathBuf>> {
+        let mut files: Vec<((String, u32), PathBuf)> = fs::read_dir(&self.directory)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let path = entry.path();
+                let key = recording_key(path.file_name()?.to_str()?)?;
+                Some((key, path))
+            })
+            .collect();
+        files.sort();
+        Ok(files.into_iter().map(|(_, path)| path).collect())
+    }
+
+    fn next_file_path(&self) -> PathBuf {
+        let stamp = Utc::now().format(STAMP_FORMAT);
+        let base = format!("{}{}", FILE_PREFIX, stamp);
+        let mut path = self.directory.join(format!("{}{}", base, FILE_EXTENSION));
+        // Plusieurs rotations dans la même seconde
+        let mut suffix = 1;
+        while path.exists() {
+            path = self.directory.join(format!("{}_{}{}", base, suffix, FILE_EXTENSION));
+            suffix += 1;
+        }
+        path
+    }
+}
+
+// Clé de tri d'un enregistrement (horodatage, suffixe de rotation), None pour les autres fichiers
+fn recording_key(name: &str) -> Option<(String, u32)> {
+    let stem = name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_EXTENSION)?;
+    let stamp = stem.get(..STAMP_LEN)?;
+    let well_formed = stamp.char_indices().all(|(i, c)| if i == 8 { c == '_' } else { c.is_ascii_digit() });
+    if !well_formed {
+        return None;
+    }
+    let suffix = match &stem[STAMP_LEN..] {
+        "" => 0,
+        rest => rest.strip_prefix('_')?.parse().ok()?,
+    };
+    Some((stamp.to_string(), suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration as ChronoDuration;
+
+    const LINKTYPE_ETHERNET: i32 = 1;
+
+    fn options(directory: &Path) -> RecordingOptions {
+        RecordingOptions {
+            directory: directory.display().to_string(),
+            max_file_size_mb: None,
+            rotate_every_minutes: None,
+            max_files: 10,
+        }
+    }
+
+    fn recorder(options: RecordingOptions) -> CaptureRecorder {
+        CaptureRecorder::new(options, "eth0", Some("Test"), LINKTYPE_ETHERNET).unwrap()
+    }
+
+    fn file_names(directory: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn writes_frames_readable_by_libpcap() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut recorder = recorder(options(directory.path()));
+        let start = DateTime::<Utc>::from_timestamp(1_700_000_000, 123_456_789).unwrap();
+        let frames = [(start, vec![0xAB; 342], 342), (start + ChronoDuration::milliseconds(1500), vec![0xCD; 100], 590)];
+        for (timestamp, data, original_len) in &frames {
+            recorder.write_packet(*timestamp, data, *original_len).unwrap();
+        }
+
+        let path = recorder.current.clone().unwrap();
+        let bytes_written = recorder.writer.as_ref().unwrap().bytes_written;
+        assert_eq!(fs::metadata(&path).unwrap().len(), bytes_written);
+        drop(recorder);
+
+        let mut capture = pcap::Capture::from_file(&path).unwrap();
+        for (timestamp, data, original_len) in &frames {
+            let packet = capture.next_packet().unwrap();
+            assert_eq!(packet.data, data.as_slice());
+            assert_eq!(packet.header.caplen as usize, data.len());
+            assert_eq!(packet.header.len, *original_len);
+            // libpcap ramène la résolution nanoseconde à la microseconde
+            assert_eq!(packet.header.ts.tv_sec as i64, timestamp.timestamp());
+            assert_eq!(packet.header.ts.tv_usec as u32, timestamp.timestamp_subsec_micros());
+        }
+        assert!(capture.next_packet().is_err());
+    }
+
+    #[test]
+    fn rotates_when_the_file_is_full() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut recorder = recorder(RecordingOptions { max_file_size_mb: Some(1), ..options(directory.path()) });
+        let frame = vec![0; 65_000];
+        // 17 trames tiennent dans le premier Mio, la 18e ouvre un nouveau fichier
+        for _ in 0..17 {
+            recorder.write_packet(Utc::now(), &frame, frame.len() as u32).unwrap();
+        }
+        assert_eq!(file_names(directory.path()).len(), 1);
+        recorder.write_packet(Utc::now(), &frame, frame.len() as u32).unwrap();
+        assert_eq!(file_names(directory.path()).len(), 2);
+    }
+
+    #[test]
+    fn rotates_when_the_file_is_too_old() {
+        let directory = tempfile::tempdir().unwrap();
+        let mut recorder = recorder(RecordingOptions { rotate_every_minutes: Some(1), ..options(directory.path()) });
+        recorder.write_packet(Utc::now(), &[0; 60], 60).unwrap();
+        assert_eq!(file_names(directory.path()).len(), 1);
+
+        recorder.opened_at = Instant::now().checked_sub(Duration::from_secs(61)).unwrap();
+        recorder.write_packet(Utc::now(), &[0; 60], 60).unwrap();
+        assert_eq!(file_names(directory.path()).len(), 2);
+    }
+
+    #[test]
+    fn prunes_recordings_from_earlier_sessions() {
+        let directory = tempfile::tempdir().unwrap();
+        for name in [
+            "dhcp-monitor_20200101_000000.pcapng",
+            "dhcp-monitor_20200101_000001_2.pcapng",
+            "dhcp-monitor_20200101_000001_10.pcapng",
+            "dhcp-monitor_notes.pcapng",
+            "autre.pcapng",
+        ] {
+            fs::write(directory.path().join(name), b"").unwrap();
+        }
+
+        let mut recorder = recorder(RecordingOptions { max_files: 2, ..options(directory.path()) });
+        let current = recorder.current.clone().unwrap();
+        let current = current.file_name().unwrap().to_string_lossy().into_owned();
+        let mut expected = vec![
+            "autre.pcapng".to_string(),
+            "dhcp-monitor_20200101_000001_10.pcapng".to_string(),
+            current,
+            "dhcp-monitor_notes.pcapng".to_string(),
+        ];
+        expected.sort();
+        assert_eq!(file_names(directory.path()), expected);
+
+        recorder.rotate().unwrap();
+        let names = file_names(directory.path());
+        assert_eq!(names.len(), 4);
+        assert!(!names.contains(&"dhcp-monitor_20200101_000001_10.pcapng".to_string()));
+    }
+
+    #[test]
+    fn orders_recordings_by_stamp_then_suffix() {
+        assert_eq!(recording_key("dhcp-monitor_20240102_030405.pcapng"), Some(("20240102_030405".to_string(), 0)));
+        assert!(recording_key("dhcp-monitor_20240102_030405_2.pcapng") < recording_key("dhcp-monitor_20240102_030405_10.pcapng"));
+        assert_eq!(recording_key("dhcp-monitor_20240102_030405.pcap"), None);
+        assert_eq!(recording_key("dhcp-monitor_20240102-030405.pcapng"), None);
+        assert_eq!(recording_key("dhcp-monitor_20240102_030405_x.pcapng"), None);
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::{BTreeSet, HashMap};
+use std::net::Ipv4Addr;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use crate::dhcp_capture::{DHCPPacket, MacAddress};
+use crate::dhcp_options::{format_identifier, RelaySubOption};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayPort {
+    pub giaddr: String,
+    pub circuit_id: Option<String>,
+    pub remote_ids: Vec<String>,
+    pub link_selection: Option<String>,
+    pub clients: Vec<String>,
+    pub packet_count: u64,
+    pub first_seen: String,
+    pub last_seen: String,
+}
+
+struct RelayPortEntry {
+    remote_ids: BTreeSet<String>,
+    link_selection: Option<Ipv4Addr>,
+    clients: BTreeSet<MacAddress>,
+    packet_count: u64,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+}
+
+// Regroupe le trafic relayé par (giaddr, circuit-id) pour savoir derrière quel port de switch se trouve chaque client
+#[derive(Default)]
+pub struct RelayTopology {
+    ports: HashMap<(Ipv4Addr, Option<String>), RelayPortEntry>,
+}
+
+impl RelayTopology {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, packet: &DHCPPacket) {
+        let giaddr = packet.bootp.giaddr;
+        let sub_options = packet.relay_agent_information().unwrap_or(&[]);
+
+        // Trafic non relayé: rien à regrouper
+        if giaddr.is_unspecified() && sub_options.is_empty() {
+            return;
+        }
+
+        let mut circuit_id = None;
+        let mut remote_id = None;
+        let mut link_selection = None;
+        for sub_option in sub_options {
+            match sub_option {
+                RelaySubOption::CircuitId(data) => circuit_id = Some(format_identifier(data)),
+                RelaySubOption::RemoteId(data) => remote_id = Some(format_identifier(data)),
+                RelaySubOption::LinkSelection(ip) => link_selection = Some(*ip),
+                _ => {}
+            }
+        }
+
+        let entry = self.ports.entry((giaddr, circuit_id)).or_insert_with(|| RelayPortEntry {
+            remote_ids: BTreeSet::new(),
+            link_selection: None,
+            clients: BTreeSet::new(),
+            packet_count: 0,
+            first_seen: packet.timestamp,
+            last_seen: packet.timestamp,
+        });
+
+        if let Some(remote_id) = remote_id {
+            entry.remote_ids.insert(remote_id);
+        }
+        if link_selection.is_some() {
+            entry.link_selection = link_selection;
+        }
+        entry.clients.insert(packet.bootp.chaddr);
+        entry.packet_count += 1;
+        entry.first_seen = entry.first_seen.min(packet.timestamp);
+        entry.last_seen = entry.last_seen.max(packet.timestamp);
+    }
+
+    pub fn clear(&mut self) {
+        self.ports.clear();
+    }
+
+    pub fn snapshot(&self) -> Vec<RelayPort> {
+        let mut keys: Vec<&(Ipv4Addr, Option<String>)> = self.ports.keys().collect();
+        keys.sort();
+
+        keys.into_iter().map(|key| {
+            let entry = &self.ports[key];
+            RelayPort {
+                giaddr: key.0.to_string(),
+                circuit_id: key.1.clone(),
+                remote_ids: entry.remote_ids.iter().cloned().collect(),
+                link_selection: entry.link_selection.map(|ip| ip.to_string()),
+                clients: entry.clients.iter().map(|mac| mac.to_string()).collect(),
+                packet_count: entry.packet_count,
+                first_seen: entry.first_seen.to_rfc3339(),
+                last_seen: entry.last_seen.to_rfc3339(),
+            }
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dhcp_capture::test_support::{at, mac, packet};
+    use crate::dhcp_capture::DHCPMessageType;
+    use crate::dhcp_options::DhcpOption;
+
+    fn relayed(chaddr: MacAddress, giaddr: Ipv4Addr, sub_options: Vec<RelaySubOption>, offset_ms: i64) -> DHCPPacket {
+        let mut packet = packet(DHCPMessageType::Discover, chaddr, 1, offset_ms);
+        packet.bootp.giaddr = giaddr;
+        if !sub_options.is_empty() {
+            packet.options.push(DhcpOption::RelayAgentInformation(sub_options));
+        }
+        packet
+    }
+
+    fn circuit(name: &str) -> RelaySubOption {
+        RelaySubOption::CircuitId(name.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn groups_by_giaddr_and_circuit_id() {
+        let relay_a = Ipv4Addr::new(10, 1, 0, 1);
+        let relay_b = Ipv4Addr::new(10, 2, 0, 1);
+        let mut topology = RelayTopology::new();
+
+        topology.record(&relayed(mac(1), relay_a, vec![circuit("Gi1/0/1"), RelaySubOption::RemoteId(b"sw-a".to_vec())], 0));
+        topology.record(&relayed(mac(1), relay_a, vec![circuit("Gi1/0/1")], 2000));
+        topology.record(&relayed(mac(2), relay_a, vec![circuit("Gi1/0/2")], 1000));
+        // Même circuit-id derrière un autre relais: port distinct
+        topology.record(&relayed(mac(3), relay_b, vec![circuit("Gi1/0/1")], 500));
+        // Relais sans option 82
+        topology.record(&relayed(mac(4), relay_b, Vec::new(), 0));
+
+        let ports = topology.snapshot();
+        let keys: Vec<(&str, Option<&str>)> = ports
+            .iter()
+            .map(|port| (port.giaddr.as_str(), port.circuit_id.as_deref()))
+            .collect();
+        assert_eq!(keys, vec![
+            ("10.1.0.1", Some("Gi1/0/1")),
+            ("10.1.0.1", Some("Gi1/0/2")),
+            ("10.2.0.1", None),
+            ("10.2.0.1", Some("Gi1/0/1")),
+        ]);
+
+        let port = &ports[0];
+        assert_eq!(port.packet_count, 2);
+        assert_eq!(port.clients, vec![mac(1).to_string()]);
+        assert_eq!(port.remote_ids, vec!["sw-a".to_string()]);
+        assert_eq!(port.first_seen, at(0).to_rfc3339());
+        assert_eq!(port.last_seen, at(2000).to_rfc3339());
+        assert_eq!(ports[2].clients, vec![mac(4).to_string()]);
+    }
+
+    #[test]
+    fn collects_clients_and_link_selection_per_port() {
+        let relay = Ipv4Addr::new(10, 1, 0, 1);
+        let link = Ipv4Addr::new(10, 1, 20, 0);
+        let mut topology = RelayTopology::new();
+
+        topology.record(&relayed(mac(2), relay, vec![circuit("p1"), RelaySubOption::LinkSelection(link)], 1000));
+        topology.record(&relayed(mac(1), relay, vec![circuit("p1")], 0));
+
+        let ports = topology.snapshot();
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].clients, vec![mac(1).to_string(), mac(2).to_string()]);
+        // La sélection de lien n'est pas effacée par un paquet qui ne la porte pas
+        assert_eq!(ports[0].link_selection, Some("10.1.20.0".to_string()));
+        // Paquets reçus dans le désordre
+        assert_eq!(ports[0].first_seen, at(0).to_rfc3339());
+        assert_eq!(ports[0].last_seen, at(1000).to_rfc3339());
+    }
+
+    #[test]
+    fn ignores_unrelayed_traffic() {
+        let mut topology = RelayTopology::new();
+        topology.record(&packet(DHCPMessageType::Discover, mac(1), 1, 0));
+        assert!(topology.snapshot().is_empty());
+
+        // Option 82 insérée par un switch local sans giaddr (relais de niveau 2)
+        topology.record(&relayed(mac(1), Ipv4Addr::UNSPECIFIED, vec![circuit("p7")], 0));
+        let ports = topology.snapshot();
+        assert_eq!(ports.len(), 1);
+        assert_eq!(ports[0].giaddr, "0.0.0.0");
+        assert_eq!(ports[0].circuit_id.as_deref(), Some("p7"));
+
+        topology.clear();
+        assert!(topology.snapshot().is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use crate::dhcp_capture::{DHCPMessageType, DHCPPacket, MacAddress};
+
+// Un client est en difficulté à partir de deux retransmissions sans réponse
+const MIN_RETRIES: u32 = 2;
+// Un client silencieux depuis 5 minutes a abandonné ou changé de réseau
+const FORGET_AFTER_SECS: i64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AwaitedReply {
+    Offer,
+    Ack,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrugglingClient {
+    pub chaddr: String,
+    pub xid: String,
+    pub awaiting: AwaitedReply,
+    pub retries: u32,
+    pub waiting_since: String,
+    pub waiting_secs: f64,
+    pub last_sent: String,
+    pub last_secs: u16,
+    pub last_server: Option<String>,
+    pub requested_ip: Option<String>,
+}
+
+struct PendingClient {
+    xid: u32,
+    awaiting: AwaitedReply,
+    retries: u32,
+    first_sent: DateTime<Utc>,
+    last_sent: DateTime<Utc>,
+    last_secs: u16,
+    requested_ip: Option<Ipv4Addr>,
+}
+
+// Détecte les clients qui retransmettent Discover/Request (même xid ou secs croissant) sans réponse
+#[derive(Default)]
+pub struct RetransmissionDetector {
+    pending: HashMap<MacAddress, PendingClient>,
+    last_servers: HashMap<MacAddress, Ipv4Addr>,
+}
+
+impl RetransmissionDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, packet: &DHCPPacket) {
+        let chaddr = packet.bootp.chaddr;
+        match packet.message_type {
+            DHCPMessageType::Discover => self.record_attempt(packet, AwaitedReply::Offer),
+            DHCPMessageType::Request => {
+                if let Some(server) = packet.server_identifier() {
+                    self.last_servers.insert(chaddr, server);
+                }
+                self.record_attempt(packet, AwaitedReply::Ack);
+            }
+            DHCPMessageType::Offer => {
+                self.last_servers.insert(chaddr, packet.server_address());
+                if self.pending.get(&chaddr).is_some_and(|pending| pending.awaiting == AwaitedReply::Offer) {
+                    self.pending.remove(&chaddr);
+                }
+            }
+            DHCPMessageType::Ack | DHCPMessageType::Nack => {
+                self.last_servers.insert(chaddr, packet.server_address());
+                self.pending.remove(&chaddr);
+            }
+            _ => {}
+        }
+    }
+
+    fn record_attempt(&mut self, packet: &DHCPPacket, awaiting: AwaitedReply) {
+        let timestamp = packet.timestamp;
+        let xid = packet.bootp.xid;
+        let secs = packet.bootp.secs;
+        let requested_ip = packet.option_50.or_else(|| {
+            Some(packet.bootp.ciaddr).filter(|ip| *ip != Ipv4Addr::UNSPECIFIED)
+        });
+
+        if self.pending.len() > 1024 {
+            let cutoff = timestamp - Duration::seconds(FORGET_AFTER_SECS);
+            self.pending.retain(|_, pending| pending.last_sent >= cutoff);
+        }
+
+        let Some(pending) = self.pending.get_mut(&packet.bootp.chaddr) else {
+            self.pending.insert(packet.bootp.chaddr, PendingClient {
+                xid,
+                awaiting,
+                retries: 0,
+                first_sent: timestamp,
+                last_sent: timestamp,
+                last_secs: secs,
+                requested_ip,
+            });
+            return;
+        };
+
+        // Même trame vue deux fois (port miroir, plusieurs interfaces)
+        if pending.awaiting == awaiting && pending.xid == xid && timestamp <= pending.last_sent {
+            return;
+        }
+
+        let stale = timestamp - pending.last_sent > Duration::seconds(FORGET_AFTER_SECS);
+        let is_retry = pending.awaiting == awaiting
+            && !stale
+            && (pending.xid == xid || secs > pending.last_secs);
+
+        if is_retry {
+            pending.retries += 1;
+        } else {
+            // Nouvelle tentative: repartir de zéro
+            pending.awaiting = awaiting;
+            pending.retries = 0;
+            pending.first_sent = timestamp;
+        }
+        pending.xid = xid;
+        pending.last_sent = timestamp;
+        pending.last_secs = secs;
+        pending.requested_ip = requested_ip;
+    }
+
+    pub fn clear(&mut self) {
+        self.pending.clear();
+        self.last_servers.clear();
+    }
+
+    pub fn struggling(&self, now: DateTime<Utc>) -> Vec<StrugglingClient> {
+        let cutoff = now - Duration::seconds(FORGET_AFTER_SECS);
+        let mut clients: Vec<StrugglingClient> = self.pending
+            .iter()
+            .filter(|(_, pending)| pending.retries >= MIN_RETRIES && pending.last_sent >= cutoff)
+            .map(|(chaddr, pending)| StrugglingClient {
+                chaddr: chaddr.to_string(),
+                xid: format!("0x{:08x}", pending.xid),
+                awaiting: pending.awaiting,
+                retries: pending.retries,
+                waiting_since: pending.first_sent.to_rfc3339(),
+                waiting_secs: (now - pending.first_sent).num_milliseconds().max(0) as f64 / 1000.0,
+                last_sent: pending.last_sent.to_rfc3339(),
+                last_secs: pending.last_secs,
+                last_server: self.last_servers.get(chaddr).map(|ip| ip.to_string()),
+                requested_ip: pending.requested_ip.map(|ip| ip.to_string()),
+            })
+            .collect();
+        clients.sort_by(|a, b| b.retries.cmp(&a.retries).then_with(|| a.waiting_since.cmp(&b.waiting_since)));
+        clients
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dhcp_capture::test_support::{at, mac, packet};
+
+    fn discover(xid: u32, secs: u16, offset_ms: i64) -> DHCPPacket {
+        let mut discover = packet(DHCPMessageType::Discover, mac(1), xid, offset_ms);
+        discover.bootp.secs = secs;
+        discover
+    }
+
+    #[test]
+    fn same_xid_retries_make_a_client_struggling() {
+        let mut detector = RetransmissionDetector::new();
+        detector.record(&discover(7, 0, 0));
+        detector.record(&discover(7, 4, 4_000));
+        detector.record(&discover(7, 12, 12_000));
+
+        let clients = detector.struggling(at(12_000));
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].retries, 2);
+        assert_eq!(clients[0].awaiting, AwaitedReply::Offer);
+        assert_eq!(clients[0].waiting_secs, 12.0);
+        assert_eq!(clients[0].last_secs, 12);
+    }
+
+    #[test]
+    fn rising_secs_with_new_xid_counts_as_retry() {
+        let mut detector = RetransmissionDetector::new();
+        detector.record(&discover(1, 0, 0));
+        detector.record(&discover(2, 4, 4_000));
+        detector.record(&discover(3, 8, 8_000));
+
+        let clients = detector.struggling(at(8_000));
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].xid, "0x00000003");
+    }
+
+    #[test]
+    fn new_xid_without_rising_secs_starts_over() {
+        let mut detector = RetransmissionDetector::new();
+        detector.record(&discover(1, 0, 0));
+        detector.record(&discover(1, 4, 4_000));
+        detector.record(&discover(2, 0, 8_000));
+        detector.record(&discover(2, 4, 12_000));
+
+        assert!(detector.struggling(at(12_000)).is_empty());
+    }
+
+    #[test]
+    fn needs_min_retries_before_reporting() {
+        let mut detector = RetransmissionDetector::new();
+        detector.record(&discover(7, 0, 0));
+        detector.record(&discover(7, 4, 4_000));
+        assert!(detector.struggling(at(4_000)).is_empty());
+
+        // Doublon vu sur une seconde interface: pas une retransmission
+        detector.record(&discover(7, 4, 4_000));
+        assert!(detector.struggling(at(4_000)).is_empty());
+
+        detector.record(&discover(7, 8, 8_000));
+        let clients = detector.struggling(at(8_000));
+        assert_eq!(clients.len(), 1);
+        assert_eq!(clients[0].retries, MIN_RETRIES);
+    }
+
+    #[test]
+    fn offer_clears_a_client_waiting_for_one() {
+        let mut detector = RetransmissionDetector::new();
+        for (secs, offset_ms) in [(0, 0), (4, 4_000), (8, 8_000)] {
+            detector.record(&discover(7, secs, offset_ms));
+        }
+        assert_eq!(detector.struggling(at(8_000)).len(), 1);
+
+        detector.record(&packet(DHCPMessageType::Offer, mac(1), 7, 8_100));
+        assert!(detector.struggling(at(8_100)).is_empty());
+    }
+
+    #[test]
+    fn forgets_clients_silent_for_too_long() {
+        let mut detector = RetransmissionDetector::new();
+        for (secs, offset_ms) in [(0, 0), (4, 4_000), (8, 8_000)] {
+            detector.record(&discover(7, secs, offset_ms));
+        }
+        assert_eq!(detector.struggling(at(8_000 + FORGET_AFTER_SECS * 1000)).len(), 1);
+        assert!(detector.struggling(at(9_000 + FORGET_AFTER_SECS * 1000)).is_empty());
+    }
+}
@@ -0,0 +1,610 @@
+use std::path::Path;
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Serialize, Deserialize};
+use thiserror::Error;
+use crate::alerts::Alert;
+use crate::transactions::Transaction;
+use crate::DHCPLog;
+
+pub const SESSION_DATABASE_FILE: &str = "sessions.sqlite";
+
+// Chaque entrée fait passer `user_version` au rang suivant; ne jamais modifier une entrée publiée
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE sessions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        interface TEXT NOT NULL,
+        source TEXT NOT NULL,
+        filter TEXT,
+        started_at TEXT NOT NULL,
+        stopped_at TEXT,
+        packet_count INTEGER NOT NULL DEFAULT 0,
+        -- Volume des paquets stockés, pour la rétention sans parcourir la table packets
+        size_bytes INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE TABLE packets (
+        session_id INTEGER NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+        log_id INTEGER NOT NULL,
+        timestamp TEXT NOT NULL,
+        packet_type TEXT NOT NULL,
+        chaddr TEXT NOT NULL,
+        xid TEXT NOT NULL,
+        log TEXT NOT NULL,
+        PRIMARY KEY (session_id, log_id)
+    );
+    CREATE TABLE packet_options (
+        session_id INTEGER NOT NULL,
+        log_id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        code INTEGER NOT NULL,
+        name TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (session_id, log_id, position),
+        FOREIGN KEY (session_id, log_id) REFERENCES packets(session_id, log_id) ON DELETE CASCADE
+    );
+    CREATE INDEX packet_options_code ON packet_options(session_id, code);
+    CREATE TABLE transactions (
+        session_id INTEGER NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+        xid TEXT NOT NULL,
+        chaddr TEXT NOT NULL,
+        state TEXT NOT NULL,
+        started_at TEXT NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (session_id, xid, chaddr, started_at)
+    );
+    CREATE TABLE alerts (
+        session_id INTEGER NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+        alert_id INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        timestamp TEXT NOT NULL,
+        message TEXT NOT NULL,
+        data TEXT NOT NULL,
+        PRIMARY KEY (session_id, alert_id)
+    );",
+];
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("Erreur de la base de sessions: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Données de session invalides: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Session {0} introuvable")]
+    UnknownSession(i64),
+    #[error("Aucune session en cours d'enregistrement")]
+    NoActiveSession,
+    #[error("Base de sessions créée par une version plus récente (schéma {0})")]
+    UnsupportedSchema(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionSource {
+    Live,
+    File,
+}
+
+impl SessionSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            SessionSource::Live => "live",
+            SessionSource::File => "file",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        if value == "file" { SessionSource::File } else { SessionSource::Live }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub id: i64,
+    pub interface: String,
+    pub source: SessionSource,
+    pub filter: Option<String>,
+    pub started_at: String,
+    pub stopped_at: Option<String>,
+    pub packet_count: u64,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredSession {
+    pub summary: SessionSummary,
+    pub logs: Vec<DHCPLog>,
+    pub transactions: Vec<Transaction>,
+    pub alerts: Vec<Alert>,
+}
+
+// Une limite à 0 est désactivée; par défaut rien n'est supprimé tant que l'utilisateur n'a
+// pas choisi de limite
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub max_age_days: u32,
+    pub max_size_mb: u64,
+}
+
+// Sessions de capture enregistrées dans une base SQLite embarquée
+pub struct SessionStore {
+    conn: Connection,
+    active: Option<i64>,
+}
+
+impl SessionStore {
+    pub fn open(path: &Path) -> Result<Self, StorageError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self, StorageError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self, StorageError> {
+        // WAL: une insertion par paquet sans fsync à chaque fois
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+
+        let mut store = Self { conn, active: None };
+        store.migrate()?;
+        Ok(store)
+    }
+
+    fn migrate(&mut self) -> Result<(), StorageError> {
+        let version: i64 = self.conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version > MIGRATIONS.len() as i64 {
+            return Err(StorageError::UnsupportedSchema(version));
+        }
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let tx = self.conn.transaction()?;
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", index as i64 + 1)?;
+            tx.commit()?;
+            println!("🗄️ Base de sessions migrée vers le schéma {}", index + 1);
+        }
+        Ok(())
+    }
+
+    pub fn schema_version(&self) -> Result<i64, StorageError> {
+        Ok(self.conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+    }
+
+    pub fn active_session(&self) -> Option<i64> {
+        self.active
+    }
+
+    pub fn begin_session(
+        &mut self,
+        interface: &str,
+        source: SessionSource,
+        filter: Option<&str>,
+        started_at: DateTime<Utc>,
+    ) -> Result<i64, StorageError> {
+        self.conn.execute(
+            "INSERT INTO sessions (interface, source, filter, started_at) VALUES (?1, ?2, ?3, ?4)",
+            params![interface, source.as_str(), filter, started_at.to_rfc3339()],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.active = Some(id);
+        Ok(id)
+    }
+
+    // Un lot de paquets par transaction, voir SessionWriter
+    pub fn record_packets(&mut self, logs: &[DHCPLog]) -> Result<(), StorageError> {
+        let session_id = self.active.ok_or(StorageError::NoActiveSession)?;
+        let mut size_bytes = 0;
+
+        let tx = self.conn.transaction()?;
+        {
+            let mut insert_packet = tx.prepare_cached(
+                "INSERT INTO packets (session_id, log_id, timestamp, packet_type, chaddr, xid, log)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            )?;
+            let mut insert_option = tx.prepare_cached(
+                "INSERT INTO packet_options (session_id, log_id, position, code, name, value)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for log in logs {
+                let json = serde_json::to_string(log)?;
+                insert_packet.execute(params![session_id, log.id as i64, log.timestamp, log.packet_type, log.chaddr, log.xid, json])?;
+                for (position, option) in log.options.iter().enumerate() {
+                    insert_option.execute(params![session_id, log.id as i64, position as i64, option.code, option.name, option.value])?;
+                }
+                size_bytes += json.len() as i64;
+            }
+        }
+        tx.execute(
+            "UPDATE sessions SET packet_count = packet_count + ?2, size_bytes = size_bytes + ?3 WHERE id = ?1",
+            params![session_id, logs.len() as i64, size_bytes],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn record_alert(&mut self, alert: &Alert) -> Result<(), StorageError> {
+        let session_id = self.active.ok_or(StorageError::NoActiveSession)?;
+        let kind = serde_json::to_value(alert.kind)?;
+        self.conn.execute(
+            "INSERT OR REPLACE INTO alerts (session_id, alert_id, kind, timestamp, message, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                session_id,
+                alert.id as i64,
+                kind.as_str().unwrap_or_default(),
+                alert.timestamp,
+                alert.message,
+                serde_json::to_string(alert)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    // Clôt la session active avec l'état final des transactions
+    pub fn end_session(&mut self, transactions: &[Transaction], stopped_at: DateTime<Utc>) -> Result<(), StorageError> {
+        let session_id = self.active.take().ok_or(StorageError::NoActiveSession)?;
+
+        let tx = self.conn.transaction()?;
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT OR REPLACE INTO transactions (session_id, xid, chaddr, state, started_at, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            )?;
+            for transaction in transactions {
+                let state = serde_json::to_value(transaction.state)?;
+                insert.execute(params![
+                    session_id,
+                    transaction.xid,
+                    transaction.chaddr,
+                    state.as_str().unwrap_or_default(),
+                    transaction.started_at,
+                    serde_json::to_string(transaction)?,
+                ])?;
+            }
+        }
+        tx.execute(
+            "UPDATE sessions SET stopped_at = ?2 WHERE id = ?1",
+            params![session_id, stopped_at.to_rfc3339()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    // Sessions laissées ouvertes par un arrêt brutal: clôturées à leur dernier paquet
+    pub fn close_interrupted_sessions(&mut self) -> Result<usize, StorageError> {
+        let closed = self.conn.execute(
+            "UPDATE sessions SET stopped_at = COALESCE(
+                (SELECT MAX(timestamp) FROM packets WHERE packets.session_id = sessions.id), started_at)
+             WHERE stopped_at IS NULL AND id IS NOT ?1",
+            params![self.active],
+        )?;
+        Ok(closed)
+    }
+
+    pub fn list_sessions(&self) -> Result<Vec<SessionSummary>, StorageError> {
+        let mut statement = self.conn.prepare(
+            "SELECT id, interface, source, filter, started_at, stopped_at, packet_count, size_bytes
+             FROM sessions ORDER BY started_at DESC, id DESC",
+        )?;
+        let sessions = statement
+            .query_map([], Self::summary_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(sessions)
+    }
+
+    fn summary(&self, session_id: i64) -> Result<SessionSummary, StorageError> {
+        self.conn
+            .query_row(
+                "SELECT id, interface, source, filter, started_at, stopped_at, packet_count, size_bytes
+                 FROM sessions WHERE id = ?1",
+                params![session_id],
+                Self::summary_from_row,
+            )
+            .optional()?
+            .ok_or(StorageError::UnknownSession(session_id))
+    }
+
+    fn summary_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SessionSummary> {
+        Ok(SessionSummary {
+            id: row.get(0)?,
+            interface: row.get(1)?,
+            source: SessionSource::parse(&row.get::<_, String>(2)?),
+            filter: row.get(3)?,
+            started_at: row.get(4)?,
+            stopped_at: row.get(5)?,
+            packet_count: row.get::<_, i64>(6)? as u64,
+            size_bytes: row.get::<_, i64>(7)? as u64,
+        })
+    }
+
+    pub fn load_session(&self, session_id: i64) -> Result<StoredSession, StorageError> {
+        let summary = self.summary(session_id)?;
+        Ok(StoredSession {
+            summary,
+            logs: self.load_json("SELECT log FROM packets WHERE session_id = ?1 ORDER BY log_id", session_id)?,
+            transactions: self.load_transactions(session_id)?,
+            alerts: self.load_json("SELECT data FROM alerts WHERE session_id = ?1 ORDER BY alert_id", session_id)?,
+        })
+    }
+
+    pub fn load_transactions(&self, session_id: i64) -> Result<Vec<Transaction>, StorageError> {
+        self.load_json("SELECT data FROM transactions WHERE session_id = ?1 ORDER BY started_at", session_id)
+    }
+
+    fn load_json<T: serde::de::DeserializeOwned>(&self, sql: &str, session_id: i64) -> Result<Vec<T>, StorageError> {
+        let mut statement = self.conn.prepare(sql)?;
+        let rows = statement
+            .query_map(params![session_id], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        rows.iter()
+            .map(|json| serde_json::from_str(json).map_err(StorageError::from))
+            .collect()
+    }
+
+    pub fn delete_session(&mut self, session_id: i64) -> Result<(), StorageError> {
+        if self.active == Some(session_id) {
+            self.active = None;
+        }
+        let deleted = self.conn.execute("DELETE FROM sessions WHERE id = ?1", params![session_id])?;
+        if deleted == 0 {
+            return Err(StorageError::UnknownSession(session_id));
+        }
+        Ok(())
+    }
+
+    // Supprime les sessions trop anciennes puis les plus anciennes jusqu'à repasser sous le volume
+    // maximal; la session en cours n'est jamais supprimée
+    pub fn apply_retention(&mut self, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<usize, StorageError> {
+        let mut expired: Vec<i64> = Vec::new();
+        let mut total_size: u64 = 0;
+        let max_size = policy.max_size_mb.saturating_mul(1024 * 1024);
+        // Une durée au-delà des dates représentables ne périme aucune session
+        let cutoff = now.checked_sub_signed(Duration::days(policy.max_age_days as i64));
+
+        // Des plus récentes aux plus anciennes: le volume cumulé désigne les sessions en trop
+        for session in self.list_sessions()? {
+            if Some(session.id) == self.active {
+                total_size += session.size_bytes;
+                continue;
+            }
+            let started_at = DateTime::parse_from_rfc3339(&session.started_at)
+                .map(|timestamp| timestamp.with_timezone(&Utc))
+                .ok();
+            let too_old = policy.max_age_days > 0
+                && cutoff.zip(started_at).is_some_and(|(cutoff, started_at)| started_at < cutoff);
+            let too_large = policy.max_size_mb > 0 && total_size + session.size_bytes > max_size;
+            if too_old || too_large {
+                expired.push(session.id);
+            } else {
+                total_size += session.size_bytes;
+            }
+        }
+
+        let tx = self.conn.transaction()?;
+        for session_id in &expired {
+            tx.execute("DELETE FROM sessions WHERE id = ?1", params![session_id])?;
+        }
+        tx.commit()?;
+
+        if !expired.is_empty() {
+            println!("🧹 Rétention: {} session(s) supprimée(s)", expired.len());
+        }
+        Ok(expired.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::AlertKind;
+    use crate::transactions::TransactionState;
+    use crate::DHCPOptionLog;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp(1_700_000_000 + seconds, 0).unwrap()
+    }
+
+    fn log(id: u64, packet_type: &str) -> DHCPLog {
+        DHCPLog {
+            id,
+            timestamp: at(id as i64).to_rfc3339(),
+            packet_type: packet_type.to_string(),
+            chaddr: "00:11:22:33:44:55".to_string(),
+            xid: "0x00000001".to_string(),
+            options: vec![
+                DHCPOptionLog { code: 53, name: "DHCP Message Type".to_string(), value: packet_type.to_string() },
+                DHCPOptionLog { code: 12, name: "Host Name".to_string(), value: "laptop".to_string() },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn transaction() -> Transaction {
+        Transaction {
+            xid: "0x00000001".to_string(),
+            chaddr: "00:11:22:33:44:55".to_string(),
+            state: TransactionState::Completed,
+            started_at: at(1).to_rfc3339(),
+            last_seen: at(4).to_rfc3339(),
+            discover_count: 1,
+            request_count: 1,
+            offer_servers: vec!["10.0.0.1".to_string()],
+            ack_server: Some("10.0.0.1".to_string()),
+            nak_server: None,
+            offer_latency_ms: Some(12.5),
+            ack_latency_ms: Some(3.0),
+            yiaddr: Some("10.0.0.50".to_string()),
+        }
+    }
+
+    fn record_session(store: &mut SessionStore, started_at: DateTime<Utc>, packets: u64) -> i64 {
+        let id = store.begin_session("eth0", SessionSource::Live, Some("udp and (port 67 or port 68)"), started_at).unwrap();
+        for n in 1..=packets {
+            store.record_packets(&[log(n, "Discover")]).unwrap();
+        }
+        store.end_session(&[], started_at + Duration::minutes(5)).unwrap();
+        id
+    }
+
+    #[test]
+    fn migrates_a_fresh_database_and_reopens_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SESSION_DATABASE_FILE);
+
+        let store = SessionStore::open(&path).unwrap();
+        assert_eq!(store.schema_version().unwrap(), MIGRATIONS.len() as i64);
+        drop(store);
+
+        // Une seconde ouverture ne rejoue aucune migration
+        let store = SessionStore::open(&path).unwrap();
+        assert_eq!(store.schema_version().unwrap(), MIGRATIONS.len() as i64);
+    }
+
+    #[test]
+    fn refuses_a_newer_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SESSION_DATABASE_FILE);
+
+        let conn = Connection::open(&path).unwrap();
+        conn.pragma_update(None, "user_version", 99).unwrap();
+        drop(conn);
+
+        assert!(matches!(SessionStore::open(&path), Err(StorageError::UnsupportedSchema(99))));
+    }
+
+    #[test]
+    fn round_trips_a_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = SessionStore::open(&dir.path().join(SESSION_DATABASE_FILE)).unwrap();
+
+        let id = store.begin_session("eth0", SessionSource::Live, Some("udp and (port 67 or port 68)"), at(0)).unwrap();
+        assert_eq!(store.active_session(), Some(id));
+        store.record_packets(&[log(1, "Discover"), log(2, "Offer")]).unwrap();
+        store.record_alert(&Alert {
+            id: 7,
+            kind: AlertKind::RogueServer,
+            timestamp: at(2).to_rfc3339(),
+            message: "Serveur DHCP non autorisé".to_string(),
+            packet: log(2, "Offer"),
+        })
+        .unwrap();
+        store.end_session(&[transaction()], at(60)).unwrap();
+        assert_eq!(store.active_session(), None);
+
+        let sessions = store.list_sessions().unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].packet_count, 2);
+        assert_eq!(sessions[0].filter.as_deref(), Some("udp and (port 67 or port 68)"));
+        assert_eq!(sessions[0].stopped_at, Some(at(60).to_rfc3339()));
+        assert!(sessions[0].size_bytes > 0);
+
+        let stored = store.load_session(id).unwrap();
+        assert_eq!(stored.logs.iter().map(|log| log.packet_type.as_str()).collect::<Vec<_>>(), ["Discover", "Offer"]);
+        assert_eq!(stored.logs[1].options.len(), 2);
+        assert_eq!(stored.alerts.len(), 1);
+        assert_eq!(stored.alerts[0].kind, AlertKind::RogueServer);
+        assert_eq!(stored.transactions.len(), 1);
+        assert_eq!(stored.transactions[0].yiaddr.as_deref(), Some("10.0.0.50"));
+
+        let options: i64 = store.conn
+            .query_row("SELECT COUNT(*) FROM packet_options WHERE session_id = ?1 AND code = 12", params![id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(options, 2);
+    }
+
+    #[test]
+    fn deleting_a_session_cascades() {
+        let mut store = SessionStore::open_in_memory().unwrap();
+        let id = record_session(&mut store, at(0), 3);
+
+        store.delete_session(id).unwrap();
+        assert!(store.list_sessions().unwrap().is_empty());
+        let orphans: i64 = store.conn
+            .query_row("SELECT (SELECT COUNT(*) FROM packets) + (SELECT COUNT(*) FROM packet_options)", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(orphans, 0);
+        assert!(matches!(store.delete_session(id), Err(StorageError::UnknownSession(_))));
+    }
+
+    #[test]
+    fn recording_without_a_session_fails() {
+        let mut store = SessionStore::open_in_memory().unwrap();
+        assert!(matches!(store.record_packets(&[log(1, "Discover")]), Err(StorageError::NoActiveSession)));
+    }
+
+    #[test]
+    fn retention_drops_old_sessions() {
+        let mut store = SessionStore::open_in_memory().unwrap();
+        let now = at(0) + Duration::days(40);
+        record_session(&mut store, at(0), 1);
+        let recent = record_session(&mut store, now - Duration::days(1), 1);
+
+        let policy = RetentionPolicy { max_age_days: 30, max_size_mb: 0 };
+        assert_eq!(store.apply_retention(&policy, now).unwrap(), 1);
+        let remaining: Vec<i64> = store.list_sessions().unwrap().iter().map(|session| session.id).collect();
+        assert_eq!(remaining, vec![recent]);
+    }
+
+    #[test]
+    fn default_retention_keeps_everything() {
+        let mut store = SessionStore::open_in_memory().unwrap();
+        record_session(&mut store, at(0), 1);
+        record_session(&mut store, at(60), 1);
+        store.conn.execute("UPDATE sessions SET size_bytes = 1024 * 1024 * 1024", []).unwrap();
+
+        assert_eq!(store.apply_retention(&RetentionPolicy::default(), at(0) + Duration::days(365)).unwrap(), 0);
+        assert_eq!(store.list_sessions().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn huge_max_age_keeps_everything() {
+        let mut store = SessionStore::open_in_memory().unwrap();
+        record_session(&mut store, at(0), 1);
+
+        let policy = RetentionPolicy { max_age_days: u32::MAX, max_size_mb: 0 };
+        assert_eq!(store.apply_retention(&policy, at(0) + Duration::days(365)).unwrap(), 0);
+        assert_eq!(store.list_sessions().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn retention_drops_oldest_sessions_over_size() {
+        let mut store = SessionStore::open_in_memory().unwrap();
+        let oldest = record_session(&mut store, at(0), 1);
+        let middle = record_session(&mut store, at(60), 1);
+        let newest = record_session(&mut store, at(120), 1);
+
+        // Tailles fixées pour un calcul exact: deux sessions tiennent dans 1 Mo, pas trois
+        store.conn.execute("UPDATE sessions SET size_bytes = 400 * 1024", []).unwrap();
+        let policy = RetentionPolicy { max_age_days: 0, max_size_mb: 1 };
+        assert_eq!(store.apply_retention(&policy, at(180)).unwrap(), 1);
+
+        let remaining: Vec<i64> = store.list_sessions().unwrap().iter().map(|session| session.id).collect();
+        assert_eq!(remaining, vec![newest, middle]);
+        assert!(!remaining.contains(&oldest));
+    }
+
+    #[test]
+    fn retention_keeps_the_active_session() {
+        let mut store = SessionStore::open_in_memory().unwrap();
+        let active = store.begin_session("eth0", SessionSource::Live, None, at(0)).unwrap();
+
+        let policy = RetentionPolicy { max_age_days: 1, max_size_mb: 0 };
+        assert_eq!(store.apply_retention(&policy, at(0) + Duration::days(10)).unwrap(), 0);
+        assert_eq!(store.list_sessions().unwrap()[0].id, active);
+    }
+
+    #[test]
+    fn closes_interrupted_sessions_at_their_last_packet() {
+        let mut store = SessionStore::open_in_memory().unwrap();
+        let id = store.begin_session("eth0", SessionSource::File, None, at(0)).unwrap();
+        store.record_packets(&[log(5, "Discover")]).unwrap();
+        store.active = None;
+
+        assert_eq!(store.close_interrupted_sessions().unwrap(), 1);
+        let summary = store.summary(id).unwrap();
+        assert_eq!(summary.stopped_at, Some(at(5).to_rfc3339()));
+        assert_eq!(summary.source, SessionSource::File);
+    }
+}
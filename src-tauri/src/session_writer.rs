@@ -0,0 +1,127 @@
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::session_store::SessionStore;
+use crate::DHCPLog;
+
+// Un lot est validé au plus tard après ce délai ou ce nombre de paquets
+const BATCH_INTERVAL_MS: u64 = 250;
+const MAX_BATCH_SIZE: usize = 500;
+
+enum WriterMessage {
+    Packet(Box<DHCPLog>),
+    // Demande d'écriture immédiate, acquittée une fois le lot validé
+    Flush(Sender<()>),
+}
+
+// Enregistre les paquets de la session active par lots, sur un thread dédié, pour que le
+// thread de capture n'attende jamais une transaction SQLite
+#[derive(Clone)]
+pub struct SessionWriter {
+    sender: Sender<WriterMessage>,
+}
+
+impl SessionWriter {
+    pub fn start(sessions: Arc<Mutex<Option<SessionStore>>>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            let mut next = receiver.recv().ok();
+
+            // Le premier paquet d'un lot ouvre la fenêtre de regroupement
+            while let Some(message) = next.take() {
+                let deadline = Instant::now() + Duration::from_millis(BATCH_INTERVAL_MS);
+                let mut logs = Vec::new();
+                let mut acknowledgements = Vec::new();
+                let mut disconnected = false;
+
+                match message {
+                    WriterMessage::Packet(log) => logs.push(*log),
+                    WriterMessage::Flush(done) => acknowledgements.push(done),
+                }
+                while acknowledgements.is_empty() && logs.len() < MAX_BATCH_SIZE {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    match receiver.recv_timeout(remaining) {
+                        Ok(WriterMessage::Packet(log)) => logs.push(*log),
+                        Ok(WriterMessage::Flush(done)) => acknowledgements.push(done),
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => {
+                            disconnected = true;
+                            break;
+                        }
+                    }
+                }
+
+                write_batch(&sessions, &logs);
+                for done in acknowledgements {
+                    let _ = done.send(());
+                }
+                if disconnected {
+                    break;
+                }
+                next = receiver.recv().ok();
+            }
+        });
+
+        Self { sender }
+    }
+
+    pub fn record_packet(&self, log: DHCPLog) {
+        let _ = self.sender.send(WriterMessage::Packet(Box::new(log)));
+    }
+
+    // Attend l'écriture des paquets en file: à appeler avant d'ouvrir ou de clore une session
+    pub fn flush(&self) {
+        let (done, acknowledged) = mpsc::channel();
+        if self.sender.send(WriterMessage::Flush(done)).is_ok() {
+            let _ = acknowledged.recv();
+        }
+    }
+}
+
+fn write_batch(sessions: &Mutex<Option<SessionStore>>, logs: &[DHCPLog]) {
+    if logs.is_empty() {
+        return;
+    }
+    let Ok(mut sessions) = sessions.lock() else {
+        eprintln!("❌ Failed to lock session store");
+        return;
+    };
+    if let Some(store) = sessions.as_mut().filter(|store| store.active_session().is_some()) {
+        if let Err(e) = store.record_packets(logs) {
+            eprintln!("❌ Enregistrement de {} paquet(s) impossible: {}", logs.len(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+    use crate::session_store::SessionSource;
+
+    #[test]
+    fn flush_commits_queued_packets_in_one_batch() {
+        let started_at = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let mut store = SessionStore::open_in_memory().unwrap();
+        let id = store.begin_session("eth0", SessionSource::Live, None, started_at).unwrap();
+        let sessions = Arc::new(Mutex::new(Some(store)));
+        let writer = SessionWriter::start(sessions.clone());
+
+        for n in 1..=3 {
+            writer.record_packet(DHCPLog {
+                id: n,
+                timestamp: started_at.to_rfc3339(),
+                packet_type: "Discover".to_string(),
+                ..Default::default()
+            });
+        }
+        writer.flush();
+
+        let sessions = sessions.lock().unwrap();
+        let stored = sessions.as_ref().unwrap().load_session(id).unwrap();
+        assert_eq!(stored.summary.packet_count, 3);
+        assert_eq!(stored.logs.iter().map(|log| log.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,345 @@
+use std::fs;
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+use crate::alerts::ServerAllowlist;
+use crate::log_store::{DEFAULT_LOG_CAPACITY, MAX_LOG_CAPACITY, MIN_LOG_CAPACITY};
+use crate::packet_events::{DEFAULT_COALESCE_INTERVAL_MS, MAX_COALESCE_INTERVAL_MS};
+use crate::session_store::RetentionPolicy;
+use crate::starvation::{StarvationConfig, MAX_THRESHOLD_EVENTS, MAX_WINDOW_SECS};
+
+pub const SETTINGS_FILE: &str = "settings.json";
+// Bornes du champ "Intervalle d'actualisation" de l'écran Paramètres
+const MIN_REFRESH_INTERVAL_MS: u64 = 500;
+const MAX_REFRESH_INTERVAL_MS: u64 = 10_000;
+const MAX_BPF_FILTER_LEN: usize = 1024;
+
+// Migration du JSON brut d'une version à la suivante; l'indice i passe de la version i à i + 1
+type Migration = fn(&mut Map<String, Value>);
+const MIGRATIONS: &[Migration] = &[migrate_v0];
+pub const SETTINGS_VERSION: u32 = MIGRATIONS.len() as u32;
+
+#[derive(Error, Debug)]
+pub enum SettingsError {
+    #[error("Impossible d'accéder à {path}: {source}")]
+    Io { path: String, source: std::io::Error },
+    #[error("Fichier de paramètres invalide: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("Paramètres enregistrés par une version plus récente (version {0})")]
+    UnsupportedVersion(u64),
+    #[error("{field} hors limites ({min} - {max})")]
+    OutOfRange { field: &'static str, min: u64, max: u64 },
+    #[error("Filtre BPF trop long (maximum {0} caractères)")]
+    FilterTooLong(usize),
+}
+
+// Paramètres conservés entre deux lancements; les champs absents prennent leur valeur par défaut
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub version: u32,
+    pub auto_refresh: bool,
+    pub refresh_interval_ms: u64,
+    pub max_logs: usize,
+    pub spill_to_disk: bool,
+    pub show_raw_data: bool,
+    pub packet_event_interval_ms: u64,
+    // Interface de la dernière capture, présélectionnée au démarrage
+    pub last_interface: Option<String>,
+    // Restreint la capture en plus du filtre DHCP
+    pub bpf_filter: Option<String>,
+    // Capture ARP de la dernière session, pour l'analyse des conflits d'adresse
+    pub capture_arp: bool,
+    pub server_allowlist: ServerAllowlist,
+    pub retention: RetentionPolicy,
+    pub starvation: StarvationConfig,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            auto_refresh: true,
+            refresh_interval_ms: 1000,
+            max_logs: DEFAULT_LOG_CAPACITY,
+            spill_to_disk: false,
+            show_raw_data: false,
+            packet_event_interval_ms: DEFAULT_COALESCE_INTERVAL_MS,
+            last_interface: None,
+            bpf_filter: None,
+            capture_arp: false,
+            server_allowlist: ServerAllowlist::default(),
+            retention: RetentionPolicy::default(),
+            starvation: StarvationConfig::default(),
+        }
+    }
+}
+
+// Version 0: réglages tels que décrits par le frontend (camelCase, sans numéro de version)
+fn migrate_v0(settings: &mut Map<String, Value>) {
+    for (old, new) in [
+        ("autoRefresh", "auto_refresh"),
+        ("refreshInterval", "refresh_interval_ms"),
+        ("maxLogs", "max_logs"),
+        ("showRawData", "show_raw_data"),
+    ] {
+        if let Some(value) = settings.remove(old) {
+            settings.insert(new.to_string(), value);
+        }
+    }
+    // Le thème reste géré par le frontend
+    settings.remove("theme");
+}
+
+fn check_range(field: &'static str, value: u64, min: u64, max: u64) -> Result<(), SettingsError> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(SettingsError::OutOfRange { field, min, max })
+    }
+}
+
+// Ramène la valeur dans les bornes en signalant la correction
+fn clamp_range<T>(field: &'static str, value: &mut T, min: T, max: T, corrections: &mut Vec<String>)
+where
+    T: Copy + Ord + std::fmt::Display,
+{
+    let clamped = (*value).clamp(min, max);
+    if clamped != *value {
+        corrections.push(format!("{} {} ramené à {}", field, value, clamped));
+        *value = clamped;
+    }
+}
+
+// Une chaîne vide vaut absence de valeur
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+}
+
+// JSON brut amené à la version courante
+fn migrated(json: &str) -> Result<Map<String, Value>, SettingsError> {
+    let mut settings: Map<String, Value> = serde_json::from_str(json)?;
+    let version = settings.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version > u64::from(SETTINGS_VERSION) {
+        return Err(SettingsError::UnsupportedVersion(version));
+    }
+    for migrate in &MIGRATIONS[version as usize..] {
+        migrate(&mut settings);
+    }
+    settings.insert("version".to_string(), SETTINGS_VERSION.into());
+    Ok(settings)
+}
+
+// Seuils de détection d'épuisement, aussi vérifiés par set_starvation_config
+pub fn check_starvation_config(config: &StarvationConfig) -> Result<(), SettingsError> {
+    for threshold in config.thresholds() {
+        check_range("Fenêtre de détection (s)", threshold.window_secs, 1, MAX_WINDOW_SECS)?;
+        check_range("Seuil de détection", threshold.max_events as u64, 1, MAX_THRESHOLD_EVENTS as u64)?;
+    }
+    Ok(())
+}
+
+impl AppSettings {
+    // Lecture du démarrage: un champ illisible reprend sa valeur par défaut et une
+    // valeur hors limites est ramenée dans les bornes, sans perdre les autres réglages.
+    // Renvoie aussi la liste des corrections appliquées
+    pub fn parse_lenient(json: &str) -> Result<(Self, Vec<String>), SettingsError> {
+        let mut corrections = Vec::new();
+        let mut accepted = Map::new();
+        for (field, value) in migrated(json)? {
+            // Avec serde(default), un objet à un seul champ se désérialise seul
+            let single = Map::from_iter([(field.clone(), value.clone())]);
+            match serde_json::from_value::<Self>(Value::Object(single)) {
+                Ok(_) => {
+                    accepted.insert(field, value);
+                }
+                Err(e) => corrections.push(format!("{} invalide, valeur par défaut utilisée ({})", field, e)),
+            }
+        }
+
+        let settings: Self = serde_json::from_value(Value::Object(accepted))?;
+        let settings = settings.sanitized(&mut corrections);
+        Ok((settings, corrections))
+    }
+
+    pub fn load(path: &Path) -> Result<(Self, Vec<String>), SettingsError> {
+        let json = fs::read_to_string(path).map_err(|source| SettingsError::Io {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Self::parse_lenient(&json)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        let io_error = |source| SettingsError::Io {
+            path: path.display().to_string(),
+            source,
+        };
+        // Fichier temporaire puis renommage: un arrêt brutal ne laisse jamais un fichier tronqué
+        let temporary = path.with_extension("json.tmp");
+        fs::write(&temporary, serde_json::to_string_pretty(self)?).map_err(io_error)?;
+        fs::rename(&temporary, path).map_err(io_error)
+    }
+
+    // Comme validated, mais corrige au lieu de refuser
+    fn sanitized(mut self, corrections: &mut Vec<String>) -> Self {
+        self.version = SETTINGS_VERSION;
+        self.last_interface = non_empty(self.last_interface);
+        self.bpf_filter = non_empty(self.bpf_filter);
+
+        clamp_range("Intervalle d'actualisation (ms)", &mut self.refresh_interval_ms, MIN_REFRESH_INTERVAL_MS, MAX_REFRESH_INTERVAL_MS, corrections);
+        clamp_range("Nombre maximum de logs", &mut self.max_logs, MIN_LOG_CAPACITY, MAX_LOG_CAPACITY, corrections);
+        clamp_range("Intervalle d'émission des paquets (ms)", &mut self.packet_event_interval_ms, 0, MAX_COALESCE_INTERVAL_MS, corrections);
+        for threshold in self.starvation.thresholds_mut() {
+            clamp_range("Fenêtre de détection (s)", &mut threshold.window_secs, 1, MAX_WINDOW_SECS, corrections);
+            clamp_range("Seuil de détection", &mut threshold.max_events, 1, MAX_THRESHOLD_EVENTS, corrections);
+        }
+        if self.bpf_filter.as_ref().is_some_and(|filter| filter.len() > MAX_BPF_FILTER_LEN) {
+            corrections.push(SettingsError::FilterTooLong(MAX_BPF_FILTER_LEN).to_string());
+            self.bpf_filter = None;
+        }
+        self
+    }
+
+    // Normalise les champs texte et vérifie les bornes de l'écran Paramètres
+    pub fn validated(mut self) -> Result<Self, SettingsError> {
+        self.version = SETTINGS_VERSION;
+        self.last_interface = non_empty(self.last_interface);
+        self.bpf_filter = non_empty(self.bpf_filter);
+
+        check_range("Intervalle d'actualisation (ms)", self.refresh_interval_ms, MIN_REFRESH_INTERVAL_MS, MAX_REFRESH_INTERVAL_MS)?;
+        check_range("Nombre maximum de logs", self.max_logs as u64, MIN_LOG_CAPACITY as u64, MAX_LOG_CAPACITY as u64)?;
+        check_range("Intervalle d'émission des paquets (ms)", self.packet_event_interval_ms, 0, MAX_COALESCE_INTERVAL_MS)?;
+        check_starvation_config(&self.starvation)?;
+        if self.bpf_filter.as_ref().is_some_and(|filter| filter.len() > MAX_BPF_FILTER_LEN) {
+            return Err(SettingsError::FilterTooLong(MAX_BPF_FILTER_LEN));
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_current_settings_and_normalizes_text() {
+        let json = format!(
+            r#"{{"version": {}, "max_logs": 2000, "last_interface": "  ", "bpf_filter": " vlan 10 "}}"#,
+            SETTINGS_VERSION
+        );
+        let (settings, corrections) = AppSettings::parse_lenient(&json).unwrap();
+        assert!(corrections.is_empty());
+        assert_eq!(settings.max_logs, 2000);
+        assert_eq!(settings.last_interface, None);
+        assert_eq!(settings.bpf_filter.as_deref(), Some("vlan 10"));
+        // Champs absents: valeurs par défaut
+        assert_eq!(settings.refresh_interval_ms, AppSettings::default().refresh_interval_ms);
+        assert_eq!(settings.retention, RetentionPolicy::default());
+    }
+
+    #[test]
+    fn migrates_a_v0_frontend_blob() {
+        let json = r#"{"autoRefresh": false, "refreshInterval": 2000, "maxLogs": 500, "showRawData": true, "theme": "dark"}"#;
+        let (settings, corrections) = AppSettings::parse_lenient(json).unwrap();
+        assert!(corrections.is_empty());
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert!(!settings.auto_refresh);
+        assert_eq!(settings.refresh_interval_ms, 2000);
+        assert_eq!(settings.max_logs, 500);
+        assert!(settings.show_raw_data);
+
+        let mut v0 = serde_json::from_str::<Map<String, Value>>(json).unwrap();
+        migrate_v0(&mut v0);
+        assert!(!v0.contains_key("theme"));
+        assert!(!v0.contains_key("maxLogs"));
+        assert_eq!(v0["max_logs"], 500);
+    }
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        let settings = AppSettings { refresh_interval_ms: 100, ..AppSettings::default() };
+        assert!(matches!(
+            settings.validated(),
+            Err(SettingsError::OutOfRange { min: MIN_REFRESH_INTERVAL_MS, max: MAX_REFRESH_INTERVAL_MS, .. })
+        ));
+        let settings = AppSettings { max_logs: MAX_LOG_CAPACITY + 1, ..AppSettings::default() };
+        assert!(matches!(settings.validated(), Err(SettingsError::OutOfRange { .. })));
+        let settings = AppSettings { bpf_filter: Some("x".repeat(MAX_BPF_FILTER_LEN + 1)), ..AppSettings::default() };
+        assert!(matches!(settings.validated(), Err(SettingsError::FilterTooLong(MAX_BPF_FILTER_LEN))));
+    }
+
+    #[test]
+    fn bounds_starvation_thresholds() {
+        let mut settings = AppSettings::default();
+        settings.starvation.discovers_per_port.window_secs = u64::MAX;
+        assert!(matches!(
+            settings.clone().validated(),
+            Err(SettingsError::OutOfRange { min: 1, max: MAX_WINDOW_SECS, .. })
+        ));
+
+        let json = format!(
+            r#"{{"starvation": {{"enabled": true, "distinct_clients": {{"window_secs": 0, "max_events": 50}},
+                "chaddr_mismatches": {{"window_secs": 10, "max_events": 10}},
+                "discovers_per_port": {{"window_secs": {}, "max_events": 0}}}}}}"#,
+            u64::MAX
+        );
+        let (settings, corrections) = AppSettings::parse_lenient(&json).unwrap();
+        assert_eq!(settings.starvation.distinct_clients.window_secs, 1);
+        assert_eq!(settings.starvation.discovers_per_port.window_secs, MAX_WINDOW_SECS);
+        assert_eq!(settings.starvation.discovers_per_port.max_events, 1);
+        assert_eq!(corrections.len(), 3);
+        assert!(check_starvation_config(&settings.starvation).is_ok());
+    }
+
+    #[test]
+    fn rejects_settings_from_a_newer_version() {
+        let json = format!(r#"{{"version": {}}}"#, SETTINGS_VERSION + 1);
+        assert!(matches!(
+            AppSettings::parse_lenient(&json),
+            Err(SettingsError::UnsupportedVersion(v)) if v == u64::from(SETTINGS_VERSION) + 1
+        ));
+    }
+
+    #[test]
+    fn lenient_parse_resets_only_the_invalid_field() {
+        let json = format!(
+            r#"{{"version": {}, "max_logs": "beaucoup", "refresh_interval_ms": 60000, "auto_refresh": false,
+                "last_interface": "eth1", "retention": {{"max_age_days": 7, "max_size_mb": 100}}}}"#,
+            SETTINGS_VERSION
+        );
+        let (settings, corrections) = AppSettings::parse_lenient(&json).unwrap();
+        assert_eq!(settings.max_logs, DEFAULT_LOG_CAPACITY);
+        assert_eq!(settings.refresh_interval_ms, MAX_REFRESH_INTERVAL_MS);
+        assert!(!settings.auto_refresh);
+        assert_eq!(settings.last_interface.as_deref(), Some("eth1"));
+        assert_eq!(settings.retention, RetentionPolicy { max_age_days: 7, max_size_mb: 100 });
+        assert_eq!(corrections.len(), 2);
+        assert!(corrections[0].starts_with("max_logs invalide"));
+    }
+
+    #[test]
+    fn lenient_parse_drops_an_oversized_filter() {
+        let json = format!(r#"{{"bpf_filter": "{}", "max_logs": 50}}"#, "x".repeat(MAX_BPF_FILTER_LEN + 1));
+        let (settings, corrections) = AppSettings::parse_lenient(&json).unwrap();
+        assert_eq!(settings.bpf_filter, None);
+        assert_eq!(settings.max_logs, MIN_LOG_CAPACITY);
+        assert_eq!(corrections.len(), 2);
+    }
+
+    #[test]
+    fn saves_and_reloads() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(SETTINGS_FILE);
+        let settings = AppSettings { max_logs: 4000, bpf_filter: Some("vlan".to_string()), capture_arp: true, ..AppSettings::default() };
+        settings.save(&path).unwrap();
+
+        let (reloaded, corrections) = AppSettings::load(&path).unwrap();
+        assert!(corrections.is_empty());
+        assert_eq!(reloaded.max_logs, 4000);
+        assert_eq!(reloaded.bpf_filter.as_deref(), Some("vlan"));
+        assert!(reloaded.capture_arp);
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+}
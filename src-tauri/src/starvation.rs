@@ -0,0 +1,393 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::Ipv4Addr;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use crate::alerts::{AlertKind, Finding};
+use crate::dhcp_capture::{DHCPMessageType, DHCPPacket, MacAddress};
+
+// Bornes de l'écran Paramètres: une fenêtre plus longue garderait trop de trames en mémoire
+pub const MAX_WINDOW_SECS: u64 = 3600;
+pub const MAX_THRESHOLD_EVENTS: usize = 100_000;
+
+// Seuil sur une fenêtre glissante: alerte quand plus de `max_events` surviennent en `window_secs`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowThreshold {
+    pub window_secs: u64,
+    pub max_events: usize,
+}
+
+impl WindowThreshold {
+    fn window(&self) -> Duration {
+        i64::try_from(self.window_secs)
+            .ok()
+            .and_then(Duration::try_seconds)
+            .unwrap_or(Duration::MAX)
+    }
+
+    // Début de la fenêtre; une fenêtre démesurée remonte au début des temps
+    fn cutoff(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now.checked_sub_signed(self.window()).unwrap_or(DateTime::<Utc>::MIN_UTC)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StarvationConfig {
+    pub enabled: bool,
+    // Nombre de chaddr distincts émettant des Discover
+    pub distinct_clients: WindowThreshold,
+    // Trames client dont le chaddr diffère de la MAC Ethernet source
+    pub chaddr_mismatches: WindowThreshold,
+    // Discover issus d'un même port (MAC source ou circuit-id de l'option 82)
+    pub discovers_per_port: WindowThreshold,
+}
+
+impl StarvationConfig {
+    pub fn thresholds(&self) -> [WindowThreshold; 3] {
+        [self.distinct_clients, self.chaddr_mismatches, self.discovers_per_port]
+    }
+
+    pub fn thresholds_mut(&mut self) -> [&mut WindowThreshold; 3] {
+        [&mut self.distinct_clients, &mut self.chaddr_mismatches, &mut self.discovers_per_port]
+    }
+}
+
+impl Default for StarvationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            distinct_clients: WindowThreshold { window_secs: 10, max_events: 50 },
+            chaddr_mismatches: WindowThreshold { window_secs: 10, max_events: 10 },
+            discovers_per_port: WindowThreshold { window_secs: 10, max_events: 30 },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum PortKey {
+    Mac(MacAddress),
+    Circuit(Ipv4Addr, String),
+}
+
+impl std::fmt::Display for PortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PortKey::Mac(mac) => write!(f, "MAC {}", mac),
+            PortKey::Circuit(giaddr, circuit_id) => write!(f, "relais {} circuit {}", giaddr, circuit_id),
+        }
+    }
+}
+
+fn evict_older<T>(events: &mut VecDeque<(DateTime<Utc>, T)>, cutoff: DateTime<Utc>, mut on_evict: impl FnMut(T)) {
+    while events.front().is_some_and(|(timestamp, _)| *timestamp < cutoff) {
+        if let Some((_, value)) = events.pop_front() {
+            on_evict(value);
+        }
+    }
+}
+
+// Une alerte par signal et par fenêtre, pour ne pas noyer l'opérateur pendant une attaque
+fn cooled_down(last_alert: Option<DateTime<Utc>>, now: DateTime<Utc>, window: Duration) -> bool {
+    last_alert.map_or(true, |last| now - last >= window)
+}
+
+// Détection d'épuisement du pool (DHCP starvation) sur le flux de paquets décodés
+#[derive(Default)]
+pub struct StarvationDetector {
+    config: StarvationConfig,
+    discovers: VecDeque<(DateTime<Utc>, MacAddress)>,
+    distinct_counts: HashMap<MacAddress, usize>,
+    mismatches: VecDeque<(DateTime<Utc>, ())>,
+    ports: HashMap<PortKey, VecDeque<(DateTime<Utc>, ())>>,
+    last_port_sweep: Option<DateTime<Utc>>,
+    distinct_alerted_at: Option<DateTime<Utc>>,
+    mismatch_alerted_at: Option<DateTime<Utc>>,
+    port_alerted_at: HashMap<PortKey, DateTime<Utc>>,
+}
+
+impl StarvationDetector {
+    pub fn new(config: StarvationConfig) -> Self {
+        Self {
+            config,
+            ..Self::default()
+        }
+    }
+
+    pub fn config(&self) -> &StarvationConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: StarvationConfig) {
+        *self = Self::new(config);
+    }
+
+    pub fn clear(&mut self) {
+        self.set_config(self.config.clone());
+    }
+
+    pub fn record(&mut self, packet: &DHCPPacket) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        // Seules les trames émises par les clients intéressent la détection
+        if !self.config.enabled || packet.bootp.op != 1 {
+            return findings;
+        }
+
+        let now = packet.timestamp;
+        findings.extend(self.check_chaddr_mismatch(packet, now));
+
+        if packet.message_type == DHCPMessageType::Discover {
+            findings.extend(self.check_distinct_clients(packet.bootp.chaddr, now));
+            findings.extend(self.check_port_burst(packet, now));
+        }
+
+        findings
+    }
+
+    fn check_distinct_clients(&mut self, chaddr: MacAddress, now: DateTime<Utc>) -> Option<Finding> {
+        let threshold = self.config.distinct_clients;
+        let counts = &mut self.distinct_counts;
+        evict_older(&mut self.discovers, threshold.cutoff(now), |mac| {
+            if let Some(count) = counts.get_mut(&mac) {
+                *count -= 1;
+                if *count == 0 {
+                    counts.remove(&mac);
+                }
+            }
+        });
+
+        self.discovers.push_back((now, chaddr));
+        *self.distinct_counts.entry(chaddr).or_insert(0) += 1;
+
+        let distinct = self.distinct_counts.len();
+        if distinct <= threshold.max_events || !cooled_down(self.distinct_alerted_at, now, threshold.window()) {
+            return None;
+        }
+        self.distinct_alerted_at = Some(now);
+
+        Some(Finding {
+            kind: AlertKind::Starvation,
+            message: format!(
+                "Épuisement DHCP probable: Discover de {} clients distincts en {} s",
+                distinct, threshold.window_secs
+            ),
+        })
+    }
+
+    fn check_chaddr_mismatch(&mut self, packet: &DHCPPacket, now: DateTime<Utc>) -> Option<Finding> {
+        // Derrière un relais, la MAC source est celle du relais
+        if packet.bootp.giaddr != Ipv4Addr::UNSPECIFIED {
+            return None;
+        }
+        let source_mac = packet.source_mac?;
+        if source_mac == packet.bootp.chaddr {
+            return None;
+        }
+
+        let threshold = self.config.chaddr_mismatches;
+        evict_older(&mut self.mismatches, threshold.cutoff(now), |_| {});
+        self.mismatches.push_back((now, ()));
+
+        let count = self.mismatches.len();
+        if count <= threshold.max_events || !cooled_down(self.mismatch_alerted_at, now, threshold.window()) {
+            return None;
+        }
+        self.mismatch_alerted_at = Some(now);
+
+        Some(Finding {
+            kind: AlertKind::Starvation,
+            message: format!(
+                "Épuisement DHCP probable: {} trames avec un chaddr différent de la MAC source en {} s (dernière: {} depuis {})",
+                count, threshold.window_secs, packet.bootp.chaddr, source_mac
+            ),
+        })
+    }
+
+    fn check_port_burst(&mut self, packet: &DHCPPacket, now: DateTime<Utc>) -> Option<Finding> {
+        let key = port_key(packet)?;
+        let threshold = self.config.discovers_per_port;
+        let cutoff = threshold.cutoff(now);
+
+        // Purge périodique des ports silencieux (MAC sources aléatoires)
+        if cooled_down(self.last_port_sweep, now, threshold.window()) {
+            self.ports.retain(|_, events| events.back().is_some_and(|(timestamp, _)| *timestamp >= cutoff));
+            self.port_alerted_at.retain(|_, alerted_at| now - *alerted_at < threshold.window());
+            self.last_port_sweep = Some(now);
+        }
+
+        let events = self.ports.entry(key.clone()).or_default();
+        evict_older(events, cutoff, |_| {});
+        events.push_back((now, ()));
+
+        let count = events.len();
+        if count <= threshold.max_events || !cooled_down(self.port_alerted_at.get(&key).copied(), now, threshold.window()) {
+            return None;
+        }
+        self.port_alerted_at.insert(key.clone(), now);
+
+        Some(Finding {
+            kind: AlertKind::Starvation,
+            message: format!(
+                "Rafale de Discover: {} en {} s depuis {}",
+                count, threshold.window_secs, key
+            ),
+        })
+    }
+}
+
+fn port_key(packet: &DHCPPacket) -> Option<PortKey> {
+    match packet.circuit_id() {
+        Some(circuit_id) => Some(PortKey::Circuit(packet.bootp.giaddr, circuit_id)),
+        None => packet.source_mac.map(PortKey::Mac),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dhcp_capture::test_support::packet;
+    use crate::dhcp_options::{DhcpOption, RelaySubOption};
+
+    const ATTACKER: MacAddress = MacAddress([0x02, 0xde, 0xad, 0xbe, 0xef, 0x01]);
+
+    fn client_mac(n: u32) -> MacAddress {
+        let b = n.to_be_bytes();
+        MacAddress([0x00, 0x11, b[0], b[1], b[2], b[3]])
+    }
+
+    fn discover(offset_ms: i64, chaddr: MacAddress, source_mac: MacAddress) -> DHCPPacket {
+        let mut packet = packet(DHCPMessageType::Discover, chaddr, offset_ms as u32, offset_ms);
+        packet.source_mac = Some(source_mac);
+        packet
+    }
+
+    fn relayed(mut packet: DHCPPacket, giaddr: Ipv4Addr, circuit_id: &str) -> DHCPPacket {
+        packet.bootp.giaddr = giaddr;
+        packet.bootp.hops = 1;
+        packet.options.push(DhcpOption::RelayAgentInformation(vec![
+            RelaySubOption::CircuitId(circuit_id.as_bytes().to_vec()),
+        ]));
+        packet
+    }
+
+    fn config(distinct: usize, mismatches: usize, per_port: usize) -> StarvationConfig {
+        StarvationConfig {
+            enabled: true,
+            distinct_clients: WindowThreshold { window_secs: 10, max_events: distinct },
+            chaddr_mismatches: WindowThreshold { window_secs: 10, max_events: mismatches },
+            discovers_per_port: WindowThreshold { window_secs: 10, max_events: per_port },
+        }
+    }
+
+    fn run(detector: &mut StarvationDetector, packets: impl IntoIterator<Item = DHCPPacket>) -> Vec<Finding> {
+        packets.into_iter().flat_map(|packet| detector.record(&packet)).collect()
+    }
+
+    #[test]
+    fn legitimate_clients_raise_nothing() {
+        let mut detector = StarvationDetector::new(StarvationConfig::default());
+        // 20 clients qui retentent 3 fois chacun, espacés d'une seconde
+        let packets = (0..60).map(|i| {
+            let mac = client_mac(i % 20);
+            discover(i as i64 * 1000, mac, mac)
+        });
+        assert!(run(&mut detector, packets).is_empty());
+    }
+
+    #[test]
+    fn flags_randomized_chaddr_flood_once_per_window() {
+        let mut detector = StarvationDetector::new(config(50, usize::MAX, usize::MAX));
+        // 200 clients aléatoires en 2 secondes, chaddr cohérent avec la MAC source
+        let packets = (0..200).map(|i| {
+            let mac = client_mac(i);
+            discover(i as i64 * 10, mac, mac)
+        });
+        let findings = run(&mut detector, packets);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].kind, AlertKind::Starvation);
+        assert!(findings[0].message.contains("51 clients distincts"));
+    }
+
+    #[test]
+    fn distinct_clients_window_slides() {
+        let mut detector = StarvationDetector::new(config(5, usize::MAX, usize::MAX));
+        // 5 nouveaux clients toutes les 11 secondes: jamais plus de 5 dans la fenêtre
+        let packets = (0..30).map(|i| {
+            let mac = client_mac(i);
+            discover((i / 5) as i64 * 11_000 + (i % 5) as i64 * 100, mac, mac)
+        });
+        assert!(run(&mut detector, packets).is_empty());
+    }
+
+    #[test]
+    fn flags_chaddr_not_matching_source_mac() {
+        let mut detector = StarvationDetector::new(config(usize::MAX, 10, usize::MAX));
+        let packets = (0..11).map(|i| discover(i as i64 * 100, client_mac(i), ATTACKER));
+        let findings = run(&mut detector, packets);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("chaddr différent"));
+        assert!(findings[0].message.contains(&ATTACKER.to_string()));
+    }
+
+    #[test]
+    fn relayed_traffic_is_not_a_chaddr_mismatch() {
+        let mut detector = StarvationDetector::new(config(usize::MAX, 0, usize::MAX));
+        let relay_mac = MacAddress([0x00, 0xaa, 0xbb, 0xcc, 0xdd, 0xee]);
+        let packet = relayed(discover(0, client_mac(1), relay_mac), Ipv4Addr::new(10, 0, 0, 1), "Gi1/0/1");
+        assert!(detector.record(&packet).is_empty());
+    }
+
+    #[test]
+    fn flags_discover_burst_from_one_port() {
+        let mut detector = StarvationDetector::new(config(usize::MAX, usize::MAX, 30));
+        let packets = (0..40).map(|i| discover(i * 50, ATTACKER, ATTACKER));
+        let findings = run(&mut detector, packets);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains(&format!("MAC {}", ATTACKER)));
+    }
+
+    #[test]
+    fn relayed_burst_is_keyed_by_circuit_id() {
+        let mut detector = StarvationDetector::new(config(usize::MAX, usize::MAX, 5));
+        let relay_mac = MacAddress([0x00, 0xaa, 0xbb, 0xcc, 0xdd, 0xee]);
+        let giaddr = Ipv4Addr::new(10, 0, 0, 1);
+        // Deux ports derrière le même relais: seul le port bavard déclenche l'alerte
+        let mut packets = Vec::new();
+        for i in 0..6 {
+            packets.push(relayed(discover(i * 100, client_mac(i as u32), relay_mac), giaddr, "Gi1/0/7"));
+            if i < 3 {
+                packets.push(relayed(discover(i * 100 + 50, client_mac(100 + i as u32), relay_mac), giaddr, "Gi1/0/8"));
+            }
+        }
+        let findings = run(&mut detector, packets);
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].message.contains("circuit Gi1/0/7"));
+    }
+
+    #[test]
+    fn alerts_again_after_cooldown() {
+        let mut detector = StarvationDetector::new(config(usize::MAX, usize::MAX, 3));
+        let first = (0..5).map(|i| discover(i * 100, ATTACKER, ATTACKER));
+        let second = (0..5).map(|i| discover(20_000 + i * 100, ATTACKER, ATTACKER));
+        assert_eq!(run(&mut detector, first.chain(second)).len(), 2);
+    }
+
+    #[test]
+    fn disabled_detector_is_silent() {
+        let mut detector = StarvationDetector::new(StarvationConfig { enabled: false, ..config(0, 0, 0) });
+        assert!(detector.record(&discover(0, client_mac(1), ATTACKER)).is_empty());
+    }
+
+    #[test]
+    fn huge_window_does_not_overflow() {
+        let threshold = WindowThreshold { window_secs: u64::MAX, max_events: 1 };
+        assert_eq!(threshold.window(), Duration::MAX);
+
+        let mut detector = StarvationDetector::new(StarvationConfig {
+            enabled: true,
+            distinct_clients: threshold,
+            chaddr_mismatches: threshold,
+            discovers_per_port: threshold,
+        });
+        let findings = run(&mut detector, (0..3).map(|i| discover(i * 100, client_mac(i as u32), ATTACKER)));
+        assert_eq!(findings.len(), 3);
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::{HashMap, VecDeque};
+use std::net::Ipv4Addr;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Serialize, Deserialize};
+use crate::dhcp_capture::{DHCPMessageType, DHCPPacket, MacAddress};
+use crate::latency_stats::{LatencyKind, LatencySample};
+
+// Délai sans réponse au-delà duquel un échange est considéré bloqué
+const RESPONSE_TIMEOUT_SECS: i64 = 10;
+const MAX_TRANSACTIONS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionState {
+    InProgress,
+    Completed,
+    Nak,
+    NoOffer,
+    NoAck,
+    Abandoned,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    pub xid: String,
+    pub chaddr: String,
+    pub state: TransactionState,
+    pub started_at: String,
+    pub last_seen: String,
+    pub discover_count: u32,
+    pub request_count: u32,
+    pub offer_servers: Vec<String>,
+    pub ack_server: Option<String>,
+    pub nak_server: Option<String>,
+    pub offer_latency_ms: Option<f64>,
+    pub ack_latency_ms: Option<f64>,
+    pub yiaddr: Option<String>,
+}
+
+pub type TransactionKey = (u32, MacAddress);
+
+#[derive(Debug, Clone)]
+pub struct TransactionEntry {
+    pub xid: u32,
+    pub chaddr: MacAddress,
+    pub started_at: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub discover_at: Option<DateTime<Utc>>,
+    pub offer_at: Option<DateTime<Utc>>,
+    pub request_at: Option<DateTime<Utc>>,
+    pub ack_at: Option<DateTime<Utc>>,
+    pub nak_at: Option<DateTime<Utc>>,
+    pub discover_count: u32,
+    pub request_count: u32,
+    pub offer_servers: Vec<Ipv4Addr>,
+    pub ack_server: Option<Ipv4Addr>,
+    pub nak_server: Option<Ipv4Addr>,
+    pub yiaddr: Option<Ipv4Addr>,
+}
+
+impl TransactionEntry {
+    fn new(xid: u32, chaddr: MacAddress, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            xid,
+            chaddr,
+            started_at: timestamp,
+            last_seen: timestamp,
+            discover_at: None,
+            offer_at: None,
+            request_at: None,
+            ack_at: None,
+            nak_at: None,
+            discover_count: 0,
+            request_count: 0,
+            offer_servers: Vec::new(),
+            ack_server: None,
+            nak_server: None,
+            yiaddr: None,
+        }
+    }
+
+    pub fn state(&self, now: DateTime<Utc>) -> TransactionState {
+        if self.nak_at.is_some() {
+            return TransactionState::Nak;
+        }
+        if self.ack_at.is_some() {
+            return TransactionState::Completed;
+        }
+        if now - self.last_seen < Duration::seconds(RESPONSE_TIMEOUT_SECS) {
+            return TransactionState::InProgress;
+        }
+
+        if self.request_at.is_some() {
+            TransactionState::NoAck
+        } else if self.offer_at.is_some() {
+            // Offre reçue mais jamais acceptée par le client
+            TransactionState::Abandoned
+        } else {
+            TransactionState::NoOffer
+        }
+    }
+
+    pub fn offer_latency(&self) -> Option<Duration> {
+        Some(self.offer_at? - self.discover_at?)
+    }
+
+    pub fn ack_latency(&self) -> Option<Duration> {
+        Some(self.ack_at? - self.request_at?)
+    }
+
+    fn to_transaction(&self, now: DateTime<Utc>) -> Transaction {
+        let to_ms = |duration: Duration| duration.num_microseconds().map(|us| us as f64 / 1000.0);
+        Transaction {
+            xid: format!("0x{:08x}", self.xid),
+            chaddr: self.chaddr.to_string(),
+            state: self.state(now),
+            started_at: self.started_at.to_rfc3339(),
+            last_seen: self.last_seen.to_rfc3339(),
+            discover_count: self.discover_count,
+            request_count: self.request_count,
+            offer_servers: self.offer_servers.iter().map(|ip| ip.to_string()).collect(),
+            ack_server: self.ack_server.map(|ip| ip.to_string()),
+            nak_server: self.nak_server.map(|ip| ip.to_string()),
+            offer_latency_ms: self.offer_latency().and_then(to_ms),
+            ack_latency_ms: self.ack_latency().and_then(to_ms),
+            yiaddr: self.yiaddr.map(|ip| ip.to_string()),
+        }
+    }
+}
+
+// Regroupe les échanges DORA par (xid, chaddr)
+#[derive(Default)]
+pub struct TransactionTracker {
+    entries: HashMap<TransactionKey, TransactionEntry>,
+    order: VecDeque<TransactionKey>,
+}
+
+impl TransactionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Renvoie le délai de réponse quand le paquet est la première réponse d'un serveur à l'échange
+    pub fn record(&mut self, packet: &DHCPPacket) -> Option<LatencySample> {
+        let is_dora = matches!(
+            packet.message_type,
+            DHCPMessageType::Discover
+                | DHCPMessageType::Offer
+                | DHCPMessageType::Request
+                | DHCPMessageType::Ack
+                | DHCPMessageType::Nack
+        );
+        if !is_dora {
+            return None;
+        }
+
+        let key = (packet.bootp.xid, packet.bootp.chaddr);
+        if !self.entries.contains_key(&key) {
+            self.order.push_back(key);
+            if self.order.len() > MAX_TRANSACTIONS {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.entries.remove(&oldest);
+                }
+            }
+        }
+
+        let timestamp = packet.timestamp;
+        let entry = self.entries
+            .entry(key)
+            .or_insert_with(|| TransactionEntry::new(key.0, key.1, timestamp));
+        entry.started_at = entry.started_at.min(timestamp);
+        entry.last_seen = entry.last_seen.max(timestamp);
+
+        let server = packet.server_address();
+        let sample = |kind, sent_at: Option<DateTime<Utc>>| {
+            sent_at.map(|sent_at| LatencySample {
+                kind,
+                latency: timestamp - sent_at,
+                server,
+                giaddr: packet.bootp.giaddr,
+                timestamp,
+            })
+        };
+
+        match packet.message_type {
+            DHCPMessageType::Discover => {
+                entry.discover_count += 1;
+                entry.discover_at.get_or_insert(timestamp);
+                None
+            }
+            DHCPMessageType::Offer => {
+                entry.offer_at.get_or_insert(timestamp);
+                // Chaque serveur qui répond compte pour sa propre latence
+                if entry.offer_servers.contains(&server) {
+                    return None;
+                }
+                entry.offer_servers.push(server);
+                sample(LatencyKind::DiscoverOffer, entry.discover_at)
+            }
+            DHCPMessageType::Request => {
+                entry.request_count += 1;
+                entry.request_at.get_or_insert(timestamp);
+                None
+            }
+            DHCPMessageType::Ack => {
+                entry.ack_server = Some(server);
+                entry.yiaddr = Some(packet.bootp.yiaddr);
+                if entry.ack_at.is_some() {
+                    return None;
+                }
+                entry.ack_at = Some(timestamp);
+                sample(LatencyKind::RequestAck, entry.request_at)
+            }
+            DHCPMessageType::Nack => {
+                entry.nak_server = Some(server);
+                if entry.nak_at.is_some() {
+                    return None;
+                }
+                entry.nak_at = Some(timestamp);
+                sample(LatencyKind::RequestNak, entry.request_at)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+
+    pub fn snapshot(&self, now: DateTime<Utc>) -> Vec<Transaction> {
+        self.order
+            .iter()
+            .filter_map(|key| self.entries.get(key))
+            .map(|entry| entry.to_transaction(now))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dhcp_capture::test_support::{at, mac, packet, SERVER_IP};
+
+    const XID: u32 = 0x1234_5678;
+
+    fn tracker(messages: &[(DHCPMessageType, i64)]) -> TransactionTracker {
+        let mut tracker = TransactionTracker::new();
+        for &(message_type, offset_ms) in messages {
+            tracker.record(&packet(message_type, mac(1), XID, offset_ms));
+        }
+        tracker
+    }
+
+    fn state(tracker: &TransactionTracker, now_ms: i64) -> TransactionState {
+        tracker.entries[&(XID, mac(1))].state(at(now_ms))
+    }
+
+    #[test]
+    fn completes_a_full_dora_exchange() {
+        use DHCPMessageType::*;
+        let tracker = tracker(&[(Discover, 0), (Offer, 40), (Request, 1000), (Ack, 1025)]);
+        // Un échange abouti ne devient jamais bloqué
+        assert_eq!(state(&tracker, 60_000), TransactionState::Completed);
+
+        let transaction = &tracker.snapshot(at(60_000))[0];
+        assert_eq!(transaction.offer_latency_ms, Some(40.0));
+        assert_eq!(transaction.ack_latency_ms, Some(25.0));
+        assert_eq!(transaction.offer_servers, vec![SERVER_IP.to_string()]);
+        assert_eq!(transaction.ack_server, Some(SERVER_IP.to_string()));
+        assert_eq!((transaction.discover_count, transaction.request_count), (1, 1));
+    }
+
+    #[test]
+    fn nak_wins_over_ack() {
+        use DHCPMessageType::*;
+        let tracker = tracker(&[(Request, 0), (Nack, 5), (Ack, 10)]);
+        assert_eq!(state(&tracker, 10), TransactionState::Nak);
+        assert_eq!(tracker.snapshot(at(10))[0].nak_server, Some(SERVER_IP.to_string()));
+    }
+
+    #[test]
+    fn times_out_against_the_reference_time() {
+        use DHCPMessageType::*;
+        let timeout_ms = RESPONSE_TIMEOUT_SECS * 1000;
+
+        let discover_only = tracker(&[(Discover, 0)]);
+        assert_eq!(state(&discover_only, timeout_ms - 1), TransactionState::InProgress);
+        assert_eq!(state(&discover_only, timeout_ms), TransactionState::NoOffer);
+
+        let offered = tracker(&[(Discover, 0), (Offer, 10)]);
+        assert_eq!(state(&offered, 10 + timeout_ms), TransactionState::Abandoned);
+
+        let requested = tracker(&[(Discover, 0), (Offer, 10), (Request, 20)]);
+        assert_eq!(state(&requested, 10 + timeout_ms), TransactionState::InProgress);
+        assert_eq!(state(&requested, 20 + timeout_ms), TransactionState::NoAck);
+    }
+
+    #[test]
+    fn times_out_after_clearing_a_live_capture() {
+        use crate::capture_clock::CaptureClock;
+
+        let mut clock = CaptureClock::default();
+        clock.set_live(true);
+        let mut tracker = tracker(&[(DHCPMessageType::Discover, 0)]);
+
+        // clear_logs en pleine capture: l'horloge perd le dernier paquet, pas le mode direct
+        tracker.clear();
+        clock.reset();
+
+        let mut discover = packet(DHCPMessageType::Discover, mac(1), XID, 0);
+        discover.timestamp = Utc::now() - Duration::seconds(RESPONSE_TIMEOUT_SECS);
+        clock.observe(discover.timestamp);
+        tracker.record(&discover);
+
+        assert_eq!(tracker.snapshot(clock.now())[0].state, TransactionState::NoOffer);
+    }
+
+    #[test]
+    fn samples_latency_once_per_server() {
+        use DHCPMessageType::*;
+        let mut tracker = TransactionTracker::new();
+        assert!(tracker.record(&packet(Discover, mac(1), XID, 0)).is_none());
+        let sample = tracker.record(&packet(Offer, mac(1), XID, 30)).unwrap();
+        assert_eq!(sample.kind, LatencyKind::DiscoverOffer);
+        assert_eq!(sample.latency, Duration::milliseconds(30));
+        assert_eq!(sample.server, SERVER_IP);
+        // Offre répétée par le même serveur
+        assert!(tracker.record(&packet(Offer, mac(1), XID, 60)).is_none());
+
+        // Ack sans Request observé: aucune latence mesurable
+        assert!(tracker.record(&packet(Ack, mac(1), XID, 90)).is_none());
+    }
+
+    #[test]
+    fn keys_exchanges_by_xid_and_chaddr() {
+        use DHCPMessageType::*;
+        let mut tracker = TransactionTracker::new();
+        tracker.record(&packet(Discover, mac(1), XID, 0));
+        tracker.record(&packet(Discover, mac(2), XID, 0));
+        tracker.record(&packet(Discover, mac(1), XID + 1, 0));
+        tracker.record(&packet(Discover, mac(1), XID, 500));
+        // Les messages hors DORA sont ignorés
+        tracker.record(&packet(Inform, mac(3), XID, 0));
+
+        let transactions = tracker.snapshot(at(0));
+        assert_eq!(transactions.len(), 3);
+        assert_eq!(transactions[0].discover_count, 2);
+        assert_eq!(transactions[0].last_seen, at(500).to_rfc3339());
+    }
+}
@@ -2,6 +2,7 @@ import React, { useState, useEffect } from 'react';
 import { core } from '@tauri-apps/api';
 import { 
   Activity, 
+  LifeBuoy,
   Network, 
   Settings, 
   Shield, 
@@ -13,8 +14,10 @@ import LogsView from './components/LogsView';
 import Option50View from './components/Option50View';
 import NetworkView from './components/NetworkView';
 import SettingsView from './components/SettingsView';
+import StrugglingClientsView from './components/StrugglingClientsView';
+import { AppSettings } from './types';
 
-type View = 'logs' | 'option50' | 'network' | 'settings';
+type View = 'logs' | 'option50' | 'struggling' | 'network' | 'settings';
 
 interface NetworkInterface {
   name: string;
@@ -58,7 +61,12 @@ function App() {
         const interfaces = await core.invoke<NetworkInterface[]>('get_interfaces');
         setInterfaces(interfaces);
         if (interfaces.length > 0 && !selectedInterface) {
-          setSelectedInterface(interfaces[0].name);
+          // Présélectionner l'interface de la dernière capture si elle est toujours présente
+          const settings = await core.invoke<AppSettings>('get_settings').catch(() => null);
+          const last = interfaces.find(iface =>
+            iface.real_name === settings?.last_interface || iface.name === settings?.last_interface
+          );
+          setSelectedInterface((last ?? interfaces[0]).name);
         }
       } else {
         // Version web - utiliser des données simulées
@@ -150,6 +158,7 @@ function App() {
   const navigation = [
     { id: 'logs', name: 'Logs', icon: Activity, color: 'text-blue-500' },
     { id: 'option50', name: 'Option 50', icon: Shield, color: 'text-purple-500' },
+    { id: 'struggling', name: 'Clients en difficulté', icon: LifeBuoy, color: 'text-red-500' },
     { id: 'network', name: 'Réseau', icon: Network, color: 'text-orange-500' },
     { id: 'settings', name: 'Paramètres', icon: Settings, color: 'text-gray-500' },
   ];
@@ -160,6 +169,8 @@ function App() {
         return <LogsView isCapturing={isCapturing} />;
       case 'option50':
         return <Option50View isCapturing={isCapturing} />;
+      case 'struggling':
+        return <StrugglingClientsView isCapturing={isCapturing} />;
       case 'network':
         return <NetworkView interfaces={interfaces} />;
       case 'settings':
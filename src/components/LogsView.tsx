@@ -1,9 +1,12 @@
 import React, { useState, useEffect } from 'react';
 import { core } from '@tauri-apps/api';
-import { Trash2, Download, Filter } from 'lucide-react';
+import { Trash2, Download, Filter, ChevronLeft, ChevronRight } from 'lucide-react';
 import PacketDetails from './PacketDetails';
+import { usePacketStream } from '../hooks/usePacketStream';
+import { LogPage, LogQuery } from '../types';
 
 interface DHCPLog {
+  id: number;
   timestamp: string;
   packet_type: string;
   source_ip: string;
@@ -13,63 +16,96 @@ interface DHCPLog {
   raw_data: string;
 }
 
+// Version web - données simulées
+const mockLogs: DHCPLog[] = [
+  {
+    id: 1,
+    timestamp: new Date().toISOString(),
+    packet_type: "DISCOVER",
+    source_ip: "0.0.0.0",
+    destination_ip: "255.255.255.255",
+    option_50: "192.168.1.100",
+    interface: "Ethernet",
+    raw_data: "Simulation de paquet DHCP",
+  },
+  {
+    id: 2,
+    timestamp: new Date(Date.now() - 5000).toISOString(),
+    packet_type: "OFFER",
+    source_ip: "192.168.1.1",
+    destination_ip: "192.168.1.100",
+    option_50: "192.168.1.100",
+    interface: "Ethernet",
+    raw_data: "Simulation de paquet DHCP",
+  },
+];
+
+const MESSAGE_TYPES = ['discover', 'offer', 'request', 'decline', 'ack', 'nack', 'release', 'inform'];
+const PAGE_SIZE = 200;
+
+// Traduit le texte du filtre en requête backend: index pour une adresse, une MAC ou un type
+// complets, recherche par sous-chaîne pour tout le reste (saisie partielle, xid, nom d'hôte)
+const buildQuery = (text: string): LogQuery => {
+  const value = text.trim();
+  if (/^\d{1,3}(\.\d{1,3}){3}(\/\d{1,2})?$/.test(value)) {
+    return { ip: value };
+  }
+  if (/^[0-9a-f]{2}([:-][0-9a-f]{2}){5}$/i.test(value)) {
+    return { mac: value };
+  }
+  if (MESSAGE_TYPES.includes(value.toLowerCase())) {
+    return { message_types: [value] };
+  }
+  return { text: value };
+};
+
 interface LogsViewProps {
   isCapturing: boolean;
 }
 
 const LogsView: React.FC<LogsViewProps> = ({ isCapturing }) => {
-  const [logs, setLogs] = useState<DHCPLog[]>([]);
+  const { logs, overwritten, clear } = usePacketStream<DHCPLog>(isCapturing, mockLogs);
   const [filter, setFilter] = useState('');
   const [autoScroll, setAutoScroll] = useState(true);
   const [selectedPacket, setSelectedPacket] = useState<DHCPLog | null>(null);
+  const [queriedLogs, setQueriedLogs] = useState<DHCPLog[] | null>(null);
+  const [queriedTotal, setQueriedTotal] = useState(0);
+  const [page, setPage] = useState(0);
 
   useEffect(() => {
-    const fetchLogs = async () => {
-      try {
-        const isTauri = window.__TAURI__ !== undefined;
-        
-        if (isTauri) {
-          // Application de bureau - utiliser le backend Rust
-          const fetchedLogs = await core.invoke<DHCPLog[]>('get_logs');
-          setLogs(fetchedLogs);
-        } else {
-          // Version web - données simulées
-          const mockLogs: DHCPLog[] = [
-            {
-              timestamp: new Date().toISOString(),
-              packet_type: "DISCOVER",
-              source_ip: "0.0.0.0",
-              destination_ip: "255.255.255.255",
-              option_50: "192.168.1.100",
-              interface: "Ethernet",
-              raw_data: "Simulation de paquet DHCP",
-            },
-            {
-              timestamp: new Date(Date.now() - 5000).toISOString(),
-              packet_type: "OFFER",
-              source_ip: "192.168.1.1",
-              destination_ip: "192.168.1.100",
-              option_50: "192.168.1.100",
-              interface: "Ethernet",
-              raw_data: "Simulation de paquet DHCP",
-            },
-          ];
-          setLogs(mockLogs);
+    setPage(0);
+  }, [filter]);
+
+  // Le filtrage est délégué au backend, qui dispose d'index; relancé à chaque nouveau lot
+  useEffect(() => {
+    if (!filter.trim() || window.__TAURI__ === undefined) {
+      setQueriedLogs(null);
+      return;
+    }
+
+    let cancelled = false;
+    const query: LogQuery = { ...buildQuery(filter), offset: page * PAGE_SIZE, limit: PAGE_SIZE };
+    core.invoke<LogPage>('query_logs', { query })
+      .then(result => {
+        if (!cancelled) {
+          setQueriedLogs(result.logs as DHCPLog[]);
+          setQueriedTotal(result.total);
         }
-      } catch (error) {
-        console.error('Failed to fetch logs:', error);
-      }
-    };
+      })
+      .catch(error => {
+        console.error('Failed to query logs:', error);
+        if (!cancelled) {
+          setQueriedLogs([]);
+          setQueriedTotal(0);
+        }
+      });
 
-    // Fetch logs initially
-    fetchLogs();
+    return () => {
+      cancelled = true;
+    };
+  }, [filter, page, logs[logs.length - 1]?.id]);
 
-    // Set up polling if capturing - reduced interval for better responsiveness
-    if (isCapturing) {
-      const interval = setInterval(fetchLogs, 100); // Changed from 1000ms to 100ms
-      return () => clearInterval(interval);
-    }
-  }, [isCapturing]);
+  const pageCount = Math.max(1, Math.ceil(queriedTotal / PAGE_SIZE));
 
   const clearLogs = async () => {
     try {
@@ -82,7 +118,7 @@ const LogsView: React.FC<LogsViewProps> = ({ isCapturing }) => {
         // Version web - simulation
         console.log('Simulation: logs effacés');
       }
-      setLogs([]);
+      clear();
     } catch (error) {
       console.error('Failed to clear logs:', error);
     }
@@ -122,12 +158,14 @@ const LogsView: React.FC<LogsViewProps> = ({ isCapturing }) => {
     }
   };
 
-  const filteredLogs = logs.filter(log =>
+  // Sans requête backend (version web ou filtre vide), filtrage local
+  const filteredLogs = queriedLogs ?? logs.filter(log =>
     log.packet_type.toLowerCase().includes(filter.toLowerCase()) ||
     log.source_ip.includes(filter) ||
     log.destination_ip.includes(filter) ||
     (log.option_50 && log.option_50.includes(filter))
   );
+  const matchCount = queriedLogs ? queriedTotal : filteredLogs.length;
 
   return (
     <div className="h-full flex flex-col">
@@ -145,6 +183,11 @@ const LogsView: React.FC<LogsViewProps> = ({ isCapturing }) => {
               </span>
             </div>
           )}
+          {overwritten > 0 && (
+            <span className="text-sm text-gray-500 dark:text-gray-400">
+              {overwritten} entrée{overwritten !== 1 ? 's' : ''} plus ancienne{overwritten !== 1 ? 's' : ''} supprimée{overwritten !== 1 ? 's' : ''}
+            </span>
+          )}
         </div>
         
         <div className="flex items-center space-x-2">
@@ -246,9 +289,32 @@ const LogsView: React.FC<LogsViewProps> = ({ isCapturing }) => {
       {/* Footer */}
       <div className="p-4 border-t border-gray-200 dark:border-gray-700 bg-gray-50 dark:bg-gray-800">
         <div className="flex items-center justify-between text-sm text-gray-600 dark:text-gray-400">
-          <span>
-            {filteredLogs.length} log{filteredLogs.length !== 1 ? 's' : ''} affiché{filteredLogs.length !== 1 ? 's' : ''}
-          </span>
+          <div className="flex items-center space-x-3">
+            <span>
+              {matchCount} log{matchCount !== 1 ? 's' : ''} trouvé{matchCount !== 1 ? 's' : ''}
+            </span>
+            {queriedLogs && pageCount > 1 && (
+              <div className="flex items-center space-x-1">
+                <button
+                  onClick={() => setPage(current => Math.max(0, current - 1))}
+                  disabled={page === 0}
+                  className="p-1 disabled:opacity-40"
+                  title="Page précédente"
+                >
+                  <ChevronLeft className="h-4 w-4" />
+                </button>
+                <span>Page {page + 1} / {pageCount}</span>
+                <button
+                  onClick={() => setPage(current => Math.min(pageCount - 1, current + 1))}
+                  disabled={page >= pageCount - 1}
+                  className="p-1 disabled:opacity-40"
+                  title="Page suivante"
+                >
+                  <ChevronRight className="h-4 w-4" />
+                </button>
+              </div>
+            )}
+          </div>
           <label className="flex items-center space-x-2">
             <input
               type="checkbox"
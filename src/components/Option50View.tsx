@@ -1,8 +1,9 @@
-import React, { useState, useEffect } from 'react';
-import { core } from '@tauri-apps/api';
+import React from 'react';
 import { Shield, AlertTriangle, CheckCircle } from 'lucide-react';
+import { usePacketStream } from '../hooks/usePacketStream';
 
 interface DHCPLog {
+  id: number;
   timestamp: string;
   packet_type: string;
   source_ip: string;
@@ -12,81 +13,50 @@ interface DHCPLog {
   raw_data: string;
 }
 
+// Version web - données simulées
+const mockLogs: DHCPLog[] = [
+  {
+    id: 1,
+    timestamp: new Date().toISOString(),
+    packet_type: "DISCOVER",
+    source_ip: "0.0.0.0",
+    destination_ip: "255.255.255.255",
+    option_50: "192.168.1.100",
+    interface: "Ethernet",
+    raw_data: "Simulation de paquet DHCP",
+  },
+  {
+    id: 2,
+    timestamp: new Date(Date.now() - 5000).toISOString(),
+    packet_type: "OFFER",
+    source_ip: "192.168.1.1",
+    destination_ip: "192.168.1.100",
+    option_50: "192.168.1.100",
+    interface: "Ethernet",
+    raw_data: "Simulation de paquet DHCP",
+  },
+  {
+    id: 3,
+    timestamp: new Date(Date.now() - 10000).toISOString(),
+    packet_type: "REQUEST",
+    source_ip: "0.0.0.0",
+    destination_ip: "255.255.255.255",
+    option_50: null,
+    interface: "Ethernet",
+    raw_data: "Simulation de paquet DHCP",
+  },
+];
+
 interface Option50ViewProps {
   isCapturing: boolean;
 }
 
 const Option50View: React.FC<Option50ViewProps> = ({ isCapturing }) => {
-  const [logs, setLogs] = useState<DHCPLog[]>([]);
-  const [option50Count, setOption50Count] = useState(0);
-  const [totalPackets, setTotalPackets] = useState(0);
-
-  useEffect(() => {
-    const fetchLogs = async () => {
-      try {
-        const isTauri = window.__TAURI__ !== undefined;
-        
-        if (isTauri) {
-          // Application de bureau - utiliser le backend Rust
-          const fetchedLogs = await core.invoke<DHCPLog[]>('get_logs');
-          setLogs(fetchedLogs);
-          
-          // Calculate statistics
-          const option50Logs = fetchedLogs.filter(log => log.option_50);
-          setOption50Count(option50Logs.length);
-          setTotalPackets(fetchedLogs.length);
-        } else {
-          // Version web - données simulées
-          const mockLogs: DHCPLog[] = [
-            {
-              timestamp: new Date().toISOString(),
-              packet_type: "DISCOVER",
-              source_ip: "0.0.0.0",
-              destination_ip: "255.255.255.255",
-              option_50: "192.168.1.100",
-              interface: "Ethernet",
-              raw_data: "Simulation de paquet DHCP",
-            },
-            {
-              timestamp: new Date(Date.now() - 5000).toISOString(),
-              packet_type: "OFFER",
-              source_ip: "192.168.1.1",
-              destination_ip: "192.168.1.100",
-              option_50: "192.168.1.100",
-              interface: "Ethernet",
-              raw_data: "Simulation de paquet DHCP",
-            },
-            {
-              timestamp: new Date(Date.now() - 10000).toISOString(),
-              packet_type: "REQUEST",
-              source_ip: "0.0.0.0",
-              destination_ip: "255.255.255.255",
-              option_50: null,
-              interface: "Ethernet",
-              raw_data: "Simulation de paquet DHCP",
-            },
-          ];
-          setLogs(mockLogs);
-          
-          // Calculate statistics
-          const option50Logs = mockLogs.filter(log => log.option_50);
-          setOption50Count(option50Logs.length);
-          setTotalPackets(mockLogs.length);
-        }
-      } catch (error) {
-        console.error('Failed to fetch logs:', error);
-      }
-    };
-
-    fetchLogs();
-
-    if (isCapturing) {
-      const interval = setInterval(fetchLogs, 100); // Changed from 1000ms to 100ms for better responsiveness
-      return () => clearInterval(interval);
-    }
-  }, [isCapturing]);
+  const { logs } = usePacketStream<DHCPLog>(isCapturing, mockLogs);
 
   const option50Logs = logs.filter(log => log.option_50);
+  const option50Count = option50Logs.length;
+  const totalPackets = logs.length;
 
   const getPacketTypeIcon = (packetType: string) => {
     switch (packetType.toLowerCase()) {
@@ -1,12 +1,27 @@
 import React from 'react';
-import { X, Network, Clock, MapPin, Server, User, Package } from 'lucide-react';
+import { X, Network, Clock, MapPin, Server, User, Package, AlertTriangle } from 'lucide-react';
 
 interface DHCPLog {
   timestamp: string;
   packet_type: string;
   source_ip: string;
   destination_ip: string;
+  op?: number;
+  hops?: number;
+  xid?: string;
+  secs?: number;
+  flags?: number;
+  broadcast?: boolean;
+  ciaddr?: string;
+  yiaddr?: string;
+  siaddr?: string;
+  giaddr?: string;
+  chaddr?: string;
+  sname?: string;
+  file?: string;
+  options?: { code: number; name: string; value: string }[];
   option_50: string | null;
+  nak_reason?: string | null;
   interface: string;
   raw_data: string;
 }
@@ -77,80 +92,8 @@ const PacketDetails: React.FC<PacketDetailsProps> = ({ packet, onClose }) => {
     }
   };
 
-  const extractDHCPOptions = (rawData: string) => {
-    const options: { [key: number]: string } = {};
-    try {
-      const hexData = parseRawData(rawData);
-      const bytes = hexData.split(' ').map(byte => parseInt(byte, 16));
-      
-      // Chercher les options DHCP (commencent à l'offset 240)
-      let i = 240;
-      while (i < bytes.length - 1) {
-        const optionCode = bytes[i];
-        const optionLength = bytes[i + 1];
-        
-        if (optionCode === 0 || optionCode === 255) break; // Fin des options
-        
-        if (i + 2 + optionLength <= bytes.length) {
-          const optionData = bytes.slice(i + 2, i + 2 + optionLength);
-          options[optionCode] = optionData.map(b => b.toString(16).padStart(2, '0')).join(' ');
-        }
-        
-        i += 2 + optionLength;
-      }
-    } catch (e) {
-      console.error('Erreur parsing options DHCP:', e);
-    }
-    return options;
-  };
-
-  const getOptionName = (code: number) => {
-    const optionNames: { [key: number]: string } = {
-      1: 'Subnet Mask',
-      2: 'Time Offset',
-      3: 'Router',
-      6: 'DNS Server',
-      12: 'Hostname',
-      15: 'Domain Name',
-      28: 'Broadcast Address',
-      50: 'Requested IP Address',
-      51: 'IP Address Lease Time',
-      53: 'DHCP Message Type',
-      54: 'Server Identifier',
-      55: 'Parameter Request List',
-      60: 'Vendor Class Identifier',
-      61: 'Client Identifier',
-      66: 'TFTP Server Name',
-      67: 'Bootfile Name',
-      82: 'Agent Information',
-      255: 'End'
-    };
-    return optionNames[code] || `Option ${code}`;
-  };
-
-  const formatOptionValue = (code: number, value: string) => {
-    if (code === 53) { // DHCP Message Type
-      const messageTypes: { [key: string]: string } = {
-        '01': 'DISCOVER',
-        '02': 'OFFER', 
-        '03': 'REQUEST',
-        '05': 'ACK',
-        '06': 'NAK',
-        '07': 'DECLINE',
-        '08': 'RELEASE',
-        '09': 'INFORM'
-      };
-      return messageTypes[value] || value;
-    } else if (code === 50 || code === 54) { // IP addresses
-      const bytes = value.split(' ').map(byte => parseInt(byte, 16));
-      if (bytes.length === 4) {
-        return bytes.join('.');
-      }
-    }
-    return value;
-  };
-
-  const dhcpOptions = extractDHCPOptions(packet.raw_data);
+  // Les options sont décodées côté Rust
+  const dhcpOptions = packet.options ?? [];
 
   return (
     <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50">
@@ -229,6 +172,36 @@ const PacketDetails: React.FC<PacketDetailsProps> = ({ packet, onClose }) => {
             </div>
           </div>
 
+          {/* BOOTP Header */}
+          {packet.xid && (
+            <div>
+              <h3 className="text-sm font-medium text-gray-700 dark:text-gray-300 mb-3">
+                En-tête BOOTP
+              </h3>
+              <div className="grid grid-cols-2 md:grid-cols-4 gap-2">
+                {[
+                  ['Op', packet.op === 1 ? 'BOOTREQUEST' : packet.op === 2 ? 'BOOTREPLY' : String(packet.op)],
+                  ['XID', packet.xid],
+                  ['Client MAC (chaddr)', packet.chaddr],
+                  ['Hops', String(packet.hops)],
+                  ['Secs', String(packet.secs)],
+                  ['Flags', `0x${(packet.flags ?? 0).toString(16).padStart(4, '0')}${packet.broadcast ? ' (broadcast)' : ''}`],
+                  ['ciaddr', packet.ciaddr],
+                  ['yiaddr', packet.yiaddr],
+                  ['siaddr', packet.siaddr],
+                  ['giaddr', packet.giaddr],
+                  ['sname', packet.sname || '-'],
+                  ['file', packet.file || '-'],
+                ].map(([label, value]) => (
+                  <div key={label} className="bg-gray-50 dark:bg-gray-700 p-3 rounded-lg">
+                    <span className="text-xs text-gray-500 dark:text-gray-400">{label}</span>
+                    <p className="text-gray-900 dark:text-white font-mono text-sm break-all">{value}</p>
+                  </div>
+                ))}
+              </div>
+            </div>
+          )}
+
           {/* Option 50 - Requested IP */}
           {packet.option_50 && (
             <div className="bg-orange-50 dark:bg-orange-900/20 p-4 rounded-lg">
@@ -244,25 +217,40 @@ const PacketDetails: React.FC<PacketDetailsProps> = ({ packet, onClose }) => {
             </div>
           )}
 
+          {/* Cause probable du NAK */}
+          {packet.nak_reason && (
+            <div className="bg-red-50 dark:bg-red-900/20 p-4 rounded-lg">
+              <div className="flex items-center space-x-2 mb-2">
+                <AlertTriangle className="h-4 w-4 text-red-500" />
+                <span className="text-sm font-medium text-red-700 dark:text-red-300">
+                  Cause probable du refus
+                </span>
+              </div>
+              <p className="text-red-900 dark:text-red-100 text-sm">
+                {packet.nak_reason}
+              </p>
+            </div>
+          )}
+
           {/* DHCP Options */}
-          {Object.keys(dhcpOptions).length > 0 && (
+          {dhcpOptions.length > 0 && (
             <div>
               <h3 className="text-sm font-medium text-gray-700 dark:text-gray-300 mb-3">
                 Options DHCP
               </h3>
               <div className="space-y-2">
-                {Object.entries(dhcpOptions).map(([code, value]) => (
-                  <div key={code} className="bg-gray-50 dark:bg-gray-700 p-3 rounded-lg">
+                {dhcpOptions.map((option, index) => (
+                  <div key={`${option.code}-${index}`} className="bg-gray-50 dark:bg-gray-700 p-3 rounded-lg">
                     <div className="flex items-center justify-between">
                       <span className="text-sm font-medium text-gray-700 dark:text-gray-300">
-                        {getOptionName(parseInt(code))}
+                        {option.name}
                       </span>
                       <span className="text-xs text-gray-500 dark:text-gray-400">
-                        Code: {code}
+                        Code: {option.code}
                       </span>
                     </div>
-                    <p className="text-gray-900 dark:text-white font-mono text-sm mt-1">
-                      {formatOptionValue(parseInt(code), value)}
+                    <p className="text-gray-900 dark:text-white font-mono text-sm mt-1 break-all">
+                      {option.value}
                     </p>
                   </div>
                 ))}
@@ -1,6 +1,7 @@
-import React, { useState } from 'react';
+import React, { useEffect, useState } from 'react';
 import { Settings, Monitor, Shield, Info, Download, Github, CheckCircle, AlertCircle, Loader } from 'lucide-react';
 import { core } from '@tauri-apps/api';
+import { AppSettings, LogStatus } from '../types';
 
 interface UpdateInfo {
   current_version: string;
@@ -20,10 +21,51 @@ const SettingsView: React.FC = () => {
   const [refreshInterval, setRefreshInterval] = useState(1000);
   const [maxLogs, setMaxLogs] = useState(1000);
   const [showRawData, setShowRawData] = useState(false);
+  const [spillToDisk, setSpillToDisk] = useState(false);
+  const [bpfFilter, setBpfFilter] = useState('');
+  const [settings, setSettings] = useState<AppSettings | null>(null);
+  const [logStatus, setLogStatus] = useState<LogStatus | null>(null);
+  const [settingsError, setSettingsError] = useState<string | null>(null);
   const [updateInfo, setUpdateInfo] = useState<UpdateInfo | null>(null);
   const [isCheckingUpdate, setIsCheckingUpdate] = useState(false);
   const [updateError, setUpdateError] = useState<string | null>(null);
 
+  // Paramètres enregistrés par le backend dans le dossier de configuration
+  useEffect(() => {
+    if (window.__TAURI__ === undefined) {
+      return;
+    }
+    core.invoke<AppSettings>('get_settings')
+      .then(loaded => {
+        setSettings(loaded);
+        setAutoRefresh(loaded.auto_refresh);
+        setRefreshInterval(loaded.refresh_interval_ms);
+        setMaxLogs(loaded.max_logs);
+        setShowRawData(loaded.show_raw_data);
+        setSpillToDisk(loaded.spill_to_disk);
+        setBpfFilter(loaded.bpf_filter ?? '');
+      })
+      .catch(error => setSettingsError(String(error)));
+    core.invoke<LogStatus>('get_log_status')
+      .then(setLogStatus)
+      .catch(error => console.error('Failed to get log status:', error));
+  }, []);
+
+  // Le backend valide, applique puis enregistre; en cas de refus l'erreur est affichée
+  const saveSettings = async (changes: Partial<AppSettings>) => {
+    if (!settings) {
+      return;
+    }
+    try {
+      const saved = await core.invoke<AppSettings>('update_settings', { settings: { ...settings, ...changes } });
+      setSettings(saved);
+      setSettingsError(null);
+      setLogStatus(await core.invoke<LogStatus>('get_log_status'));
+    } catch (error) {
+      setSettingsError(String(error));
+    }
+  };
+
   const checkForUpdates = async () => {
     setIsCheckingUpdate(true);
     setUpdateError(null);
@@ -94,7 +136,10 @@ const SettingsView: React.FC = () => {
                   <input
                     type="checkbox"
                     checked={autoRefresh}
-                    onChange={(e) => setAutoRefresh(e.target.checked)}
+                    onChange={(e) => {
+                      setAutoRefresh(e.target.checked);
+                      saveSettings({ auto_refresh: e.target.checked });
+                    }}
                     className="sr-only peer"
                   />
                   <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 rounded-full peer dark:bg-gray-700 peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600"></div>
@@ -109,6 +154,7 @@ const SettingsView: React.FC = () => {
                   type="number"
                   value={refreshInterval}
                   onChange={(e) => setRefreshInterval(Number(e.target.value))}
+                  onBlur={() => saveSettings({ refresh_interval_ms: refreshInterval })}
                   min="500"
                   max="10000"
                   step="500"
@@ -127,6 +173,7 @@ const SettingsView: React.FC = () => {
                   type="number"
                   value={maxLogs}
                   onChange={(e) => setMaxLogs(Number(e.target.value))}
+                  onBlur={() => saveSettings({ max_logs: maxLogs })}
                   min="100"
                   max="10000"
                   step="100"
@@ -134,9 +181,33 @@ const SettingsView: React.FC = () => {
                 />
                 <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
                   Limite le nombre de logs en mémoire
+                  {logStatus && logStatus.overwritten > 0 && ` (${logStatus.overwritten} entrées plus anciennes supprimées)`}
                 </p>
               </div>
 
+              <div className="flex items-center justify-between">
+                <div>
+                  <label className="text-sm font-medium text-gray-700 dark:text-gray-300">
+                    Conserver les logs supprimés sur disque
+                  </label>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    {logStatus?.spill_path ?? 'Ajoute les entrées évincées à un fichier JSON Lines'}
+                  </p>
+                </div>
+                <label className="relative inline-flex items-center cursor-pointer">
+                  <input
+                    type="checkbox"
+                    checked={spillToDisk}
+                    onChange={(e) => {
+                      setSpillToDisk(e.target.checked);
+                      saveSettings({ spill_to_disk: e.target.checked });
+                    }}
+                    className="sr-only peer"
+                  />
+                  <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 rounded-full peer dark:bg-gray-700 peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600"></div>
+                </label>
+              </div>
+
               <div className="flex items-center justify-between">
                 <div>
                   <label className="text-sm font-medium text-gray-700 dark:text-gray-300">
@@ -150,12 +221,36 @@ const SettingsView: React.FC = () => {
                   <input
                     type="checkbox"
                     checked={showRawData}
-                    onChange={(e) => setShowRawData(e.target.checked)}
+                    onChange={(e) => {
+                      setShowRawData(e.target.checked);
+                      saveSettings({ show_raw_data: e.target.checked });
+                    }}
                     className="sr-only peer"
                   />
                   <div className="w-11 h-6 bg-gray-200 peer-focus:outline-none peer-focus:ring-4 peer-focus:ring-blue-300 dark:peer-focus:ring-blue-800 rounded-full peer dark:bg-gray-700 peer-checked:after:translate-x-full peer-checked:after:border-white after:content-[''] after:absolute after:top-[2px] after:left-[2px] after:bg-white after:border-gray-300 after:border after:rounded-full after:h-5 after:w-5 after:transition-all dark:border-gray-600 peer-checked:bg-blue-600"></div>
                 </label>
               </div>
+
+              <div>
+                <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
+                  Filtre BPF additionnel
+                </label>
+                <input
+                  type="text"
+                  value={bpfFilter}
+                  onChange={(e) => setBpfFilter(e.target.value)}
+                  onBlur={() => saveSettings({ bpf_filter: bpfFilter.trim() || null })}
+                  placeholder="vlan 10, host 192.168.1.1..."
+                  className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white text-sm font-mono"
+                />
+                <p className="text-xs text-gray-500 dark:text-gray-400 mt-1">
+                  Combiné au filtre DHCP à la prochaine capture
+                </p>
+              </div>
+
+              {settingsError && (
+                <p className="text-sm text-red-600 dark:text-red-400">{settingsError}</p>
+              )}
             </div>
           </div>
 
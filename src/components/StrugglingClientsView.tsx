@@ -0,0 +1,121 @@
+import React, { useState, useEffect } from 'react';
+import { core } from '@tauri-apps/api';
+import { LifeBuoy, RefreshCw } from 'lucide-react';
+import { StrugglingClient } from '../types';
+
+interface StrugglingClientsViewProps {
+  isCapturing: boolean;
+}
+
+const StrugglingClientsView: React.FC<StrugglingClientsViewProps> = ({ isCapturing }) => {
+  const [clients, setClients] = useState<StrugglingClient[]>([]);
+
+  useEffect(() => {
+    const fetchClients = async () => {
+      try {
+        const isTauri = window.__TAURI__ !== undefined;
+
+        if (isTauri) {
+          const fetched = await core.invoke<StrugglingClient[]>('get_struggling_clients');
+          setClients(fetched);
+        } else {
+          // Version web - données simulées
+          setClients([
+            {
+              chaddr: '00:11:22:33:44:55',
+              xid: '0x1a2b3c4d',
+              awaiting: 'offer',
+              retries: 4,
+              waiting_since: new Date(Date.now() - 32000).toISOString(),
+              waiting_secs: 32,
+              last_sent: new Date(Date.now() - 2000).toISOString(),
+              last_secs: 30,
+              last_server: null,
+              requested_ip: '192.168.1.100',
+            },
+          ]);
+        }
+      } catch (error) {
+        console.error('Failed to fetch struggling clients:', error);
+      }
+    };
+
+    fetchClients();
+
+    if (isCapturing) {
+      const interval = setInterval(fetchClients, 1000);
+      return () => clearInterval(interval);
+    }
+  }, [isCapturing]);
+
+  const formatWaiting = (seconds: number) => {
+    if (seconds < 60) {
+      return `${Math.round(seconds)} s`;
+    }
+    return `${Math.floor(seconds / 60)} min ${Math.round(seconds % 60)} s`;
+  };
+
+  return (
+    <div className="h-full flex flex-col">
+      {/* Header */}
+      <div className="flex items-center justify-between p-4 border-b border-gray-200 dark:border-gray-700">
+        <div className="flex items-center space-x-4">
+          <LifeBuoy className="h-6 w-6 text-red-500" />
+          <h2 className="text-xl font-semibold text-gray-900 dark:text-white">
+            Clients en difficulté
+          </h2>
+        </div>
+
+        <div className="flex items-center space-x-2">
+          <RefreshCw className={`h-4 w-4 text-gray-500 ${isCapturing ? 'animate-spin' : ''}`} />
+          <span className="text-sm text-gray-600 dark:text-gray-400">
+            {clients.length} client{clients.length !== 1 ? 's' : ''} sans réponse
+          </span>
+        </div>
+      </div>
+
+      <div className="flex-1 overflow-auto p-4">
+        {clients.length === 0 ? (
+          <div className="text-center py-8">
+            <LifeBuoy className="h-12 w-12 text-gray-400 mx-auto mb-4" />
+            <p className="text-gray-500 dark:text-gray-400">
+              {isCapturing ? 'Aucun client ne retransmet pour le moment...' : 'Aucun client en difficulté'}
+            </p>
+          </div>
+        ) : (
+          <table className="min-w-full text-sm">
+            <thead>
+              <tr className="text-left text-gray-600 dark:text-gray-400 border-b border-gray-200 dark:border-gray-700">
+                <th className="py-2 pr-4">Client</th>
+                <th className="py-2 pr-4">Attend</th>
+                <th className="py-2 pr-4">Retransmissions</th>
+                <th className="py-2 pr-4">Attente</th>
+                <th className="py-2 pr-4">secs</th>
+                <th className="py-2 pr-4">IP demandée</th>
+                <th className="py-2 pr-4">Dernier serveur</th>
+              </tr>
+            </thead>
+            <tbody>
+              {clients.map((client) => (
+                <tr key={client.chaddr} className="border-b border-gray-100 dark:border-gray-800 text-gray-900 dark:text-white">
+                  <td className="py-2 pr-4">
+                    <div className="font-mono">{client.chaddr}</div>
+                    <div className="text-xs text-gray-500 dark:text-gray-400 font-mono">xid {client.xid}</div>
+                  </td>
+                  <td className="py-2 pr-4 uppercase">{client.awaiting}</td>
+                  <td className="py-2 pr-4 font-semibold text-red-600 dark:text-red-400">{client.retries}</td>
+                  <td className="py-2 pr-4">{formatWaiting(client.waiting_secs)}</td>
+                  <td className="py-2 pr-4 font-mono">{client.last_secs}</td>
+                  <td className="py-2 pr-4 font-mono">{client.requested_ip ?? '-'}</td>
+                  <td className="py-2 pr-4 font-mono">{client.last_server ?? 'aucun'}</td>
+                </tr>
+              ))}
+            </tbody>
+          </table>
+        )}
+      </div>
+    </div>
+  );
+};
+
+export default StrugglingClientsView;
@@ -0,0 +1,111 @@
+import { useEffect, useState } from 'react';
+import { core, event } from '@tauri-apps/api';
+
+interface StreamedLog {
+  id: number;
+}
+
+interface LogBatch<T> {
+  session: number;
+  cursor: number;
+  capacity: number;
+  overwritten: number;
+  logs: T[];
+}
+
+// Conservé entre les montages: revenir sur une vue ne rattrape que les paquets manqués
+const cache = {
+  session: -1,
+  cursor: 0,
+  overwritten: 0,
+  logs: [] as StreamedLog[],
+};
+
+const merge = <T extends StreamedLog>(batch: LogBatch<T>) => {
+  if (batch.session < cache.session) {
+    // Lot d'une session déjà effacée, reçu en retard
+    return;
+  }
+  if (batch.session > cache.session) {
+    // Le backend a effacé ses logs depuis le dernier lot reçu
+    cache.session = batch.session;
+    cache.cursor = 0;
+    cache.overwritten = 0;
+    cache.logs = [];
+  }
+  const fresh = batch.logs.filter(log => log.id > cache.cursor);
+  if (fresh.length > 0) {
+    // Le backend ne garde que les `capacity` derniers logs: faire de même
+    cache.logs = [...cache.logs, ...fresh].slice(-batch.capacity);
+  }
+  cache.cursor = Math.max(cache.cursor, batch.cursor);
+  cache.overwritten = Math.max(cache.overwritten, batch.overwritten);
+};
+
+// Logs poussés par le backend via `dhcp://packet`, avec rattrapage par `get_logs_since`
+export const usePacketStream = <T extends StreamedLog>(isCapturing: boolean, mockLogs: T[]) => {
+  const [logs, setLogs] = useState<T[]>(cache.logs as T[]);
+  const [overwritten, setOverwritten] = useState(cache.overwritten);
+
+  useEffect(() => {
+    if (window.__TAURI__ === undefined) {
+      // Version web - données simulées
+      setLogs(mockLogs);
+      return;
+    }
+
+    let cancelled = false;
+    let caughtUp = false;
+    let unlisten: (() => void) | undefined;
+    const pending: LogBatch<T>[] = [];
+
+    const apply = (batch: LogBatch<T>) => {
+      merge(batch);
+      if (!cancelled) {
+        setLogs(cache.logs as T[]);
+        setOverwritten(cache.overwritten);
+      }
+    };
+
+    const connect = async () => {
+      try {
+        // S'abonner avant le rattrapage pour ne perdre aucun lot émis entre-temps
+        const stop = await event.listen<LogBatch<T>>('dhcp://packet', ({ payload }) => {
+          if (caughtUp) {
+            apply(payload);
+          } else {
+            pending.push(payload);
+          }
+        });
+        if (cancelled) {
+          stop();
+          return;
+        }
+        unlisten = stop;
+
+        const batch = await core.invoke<LogBatch<T>>('get_logs_since', { cursor: cache.cursor });
+        apply(batch);
+        caughtUp = true;
+        pending.splice(0).forEach(apply);
+      } catch (error) {
+        console.error('Failed to subscribe to packets:', error);
+      }
+    };
+
+    connect();
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [isCapturing]);
+
+  const clear = () => {
+    cache.logs = [];
+    cache.overwritten = 0;
+    setLogs([]);
+    setOverwritten(0);
+  };
+
+  return { logs, overwritten, clear };
+};
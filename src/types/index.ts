@@ -1,15 +1,70 @@
 // Types pour l'application DHCP Monitor
 
 export interface DHCPLog {
+  id: number;
   timestamp: string;
   packet_type: string;
   source_ip: string;
   destination_ip: string;
+  source_mac: string | null;
+  destination_mac: string | null;
+  vlan_ids: number[];
+  source_port: number;
+  destination_port: number;
+  op: number;
+  htype: number;
+  hlen: number;
+  hops: number;
+  xid: string;
+  secs: number;
+  flags: number;
+  broadcast: boolean;
+  ciaddr: string;
+  yiaddr: string;
+  siaddr: string;
+  giaddr: string;
+  chaddr: string;
+  chaddr_vendor: string | null;
+  chaddr_locally_administered: boolean;
+  source_mac_vendor: string | null;
+  sname: string;
+  file: string;
+  options: DHCPOption[];
+  option_overload: boolean;
+  options_concatenated: boolean;
   option_50: string | null;
+  fingerprint: string | null;
+  device: string | null;
+  device_category: string | null;
+  nak_reason: string | null;
   interface: string;
   raw_data: string;
 }
 
+export interface DHCPOption {
+  code: number;
+  name: string;
+  value: string;
+}
+
+export interface RelayPort {
+  giaddr: string;
+  circuit_id: string | null;
+  remote_ids: string[];
+  link_selection: string | null;
+  clients: string[];
+  packet_count: number;
+  first_seen: string;
+  last_seen: string;
+}
+
+export interface RecordingOptions {
+  directory: string;
+  max_file_size_mb?: number | null;
+  rotate_every_minutes?: number | null;
+  max_files?: number;
+}
+
 export interface Statistics {
   total_packets: number;
   option_50_packets: number;
@@ -44,13 +99,21 @@ export interface BarChartData {
 // Types pour les thèmes
 export type Theme = 'light' | 'dark' | 'auto';
 
-// Types pour les paramètres
+// Types pour les paramètres, enregistrés côté backend (`get_settings` / `update_settings`)
 export interface AppSettings {
-  autoRefresh: boolean;
-  refreshInterval: number;
-  maxLogs: number;
-  showRawData: boolean;
-  theme: Theme;
+  version: number;
+  auto_refresh: boolean;
+  refresh_interval_ms: number;
+  max_logs: number;
+  spill_to_disk: boolean;
+  show_raw_data: boolean;
+  packet_event_interval_ms: number;
+  last_interface: string | null;
+  bpf_filter: string | null;
+  capture_arp: boolean;
+  server_allowlist: ServerAllowlist;
+  retention: RetentionPolicy;
+  starvation: StarvationConfig;
 }
 
 // Types pour les erreurs
@@ -58,4 +121,280 @@ export interface AppError {
   message: string;
   code?: string;
   details?: any;
-}
\ No newline at end of file
+}
+export type TransactionState =
+  | 'in_progress'
+  | 'completed'
+  | 'nak'
+  | 'no_offer'
+  | 'no_ack'
+  | 'abandoned';
+
+export interface Transaction {
+  xid: string;
+  chaddr: string;
+  state: TransactionState;
+  started_at: string;
+  last_seen: string;
+  discover_count: number;
+  request_count: number;
+  offer_servers: string[];
+  ack_server: string | null;
+  nak_server: string | null;
+  offer_latency_ms: number | null;
+  ack_latency_ms: number | null;
+  yiaddr: string | null;
+}
+
+export type AlertKind = 'rogue_server' | 'starvation' | 'address_conflict';
+
+export interface Alert {
+  id: number;
+  kind: AlertKind;
+  timestamp: string;
+  message: string;
+  packet: DHCPLog;
+}
+
+export interface ServerAllowlist {
+  ips: string[];
+  macs: string[];
+}
+
+export interface WindowThreshold {
+  window_secs: number;
+  max_events: number;
+}
+
+export interface StarvationConfig {
+  enabled: boolean;
+  distinct_clients: WindowThreshold;
+  chaddr_mismatches: WindowThreshold;
+  discovers_per_port: WindowThreshold;
+}
+
+export type LeaseState = 'active' | 'expired' | 'released' | 'declined';
+
+export interface Lease {
+  ip: string;
+  chaddr: string;
+  client_id: string | null;
+  hostname: string | null;
+  server: string;
+  state: LeaseState;
+  lease_time: number | null;
+  renewal_time: number | null;
+  rebinding_time: number | null;
+  granted_at: string;
+  last_renewed: string;
+  expires_at: string | null;
+  renew_count: number;
+}
+
+export type LeaseSortField = 'ip' | 'chaddr' | 'hostname' | 'server' | 'last_renewed' | 'expires_at';
+
+export interface LeaseQuery {
+  state?: LeaseState | null;
+  server?: string | null;
+  search?: string | null;
+  sort_by?: LeaseSortField;
+  descending?: boolean;
+}
+
+export type RequestKind =
+  | 'DISCOVER'
+  | 'SELECTING'
+  | 'INIT-REBOOT'
+  | 'RENEWING'
+  | 'REBINDING'
+  | 'INVALID';
+
+export interface RequestRecord {
+  timestamp: string;
+  xid: string;
+  kind: RequestKind;
+  requested_ip: string | null;
+  server_id: string | null;
+  last_assigned: string | null;
+  mismatch: boolean;
+}
+
+export interface ClientRequestHistory {
+  client: string;
+  chaddr: string;
+  client_id: string | null;
+  last_assigned: string | null;
+  mismatch_count: number;
+  requests: RequestRecord[];
+}
+
+export interface ClientFingerprint {
+  parameter_request_list: number[];
+  vendor_class: string | null;
+  max_message_size: number | null;
+}
+
+export interface UnmatchedFingerprint {
+  fingerprint: ClientFingerprint;
+  signature: string;
+  count: number;
+  first_seen: string;
+  example_chaddr: string;
+}
+
+export interface MacVendorInfo {
+  mac: string;
+  oui: string;
+  vendor: string | null;
+  locally_administered: boolean;
+  multicast: boolean;
+}
+
+export interface Squatter {
+  mac: string;
+  last_seen: string;
+}
+
+export interface AddressConflict {
+  ip: string;
+  decline_count: number;
+  first_declined: string;
+  last_declined: string;
+  declining_clients: string[];
+  acked_by: string | null;
+  ack_to_decline_ms: number | null;
+  squatters: Squatter[];
+}
+
+export type LatencyKind = 'discover_offer' | 'request_ack' | 'request_nak';
+
+export interface LatencySummary {
+  count: number;
+  min_ms: number;
+  max_ms: number;
+  mean_ms: number;
+  p50_ms: number;
+  p90_ms: number;
+  p99_ms: number;
+}
+
+export interface MinuteLatency {
+  minute: string;
+  summary: LatencySummary;
+}
+
+export interface LatencyGroup {
+  key: string;
+  kind: LatencyKind;
+  overall: LatencySummary;
+  minutes: MinuteLatency[];
+}
+
+export interface LatencyReport {
+  by_server: LatencyGroup[];
+  by_relay: LatencyGroup[];
+}
+
+export interface StrugglingClient {
+  chaddr: string;
+  xid: string;
+  awaiting: 'offer' | 'ack';
+  retries: number;
+  waiting_since: string;
+  waiting_secs: number;
+  last_sent: string;
+  last_secs: number;
+  last_server: string | null;
+  requested_ip: string | null;
+}
+
+export type NakCause =
+  | { kind: 'outside_subnet'; requested_ip: string; subnet: string }
+  | { kind: 'server_id_mismatch'; requested_server: string; nak_server: string }
+  | { kind: 'client_moved'; previous_location: string; current_location: string };
+
+export interface LinkedRequest {
+  timestamp: string;
+  kind: RequestKind;
+  requested_ip: string | null;
+  ciaddr: string | null;
+  server_id: string | null;
+  latency_ms: number;
+}
+
+export interface NakReport {
+  timestamp: string;
+  xid: string;
+  chaddr: string;
+  server: string;
+  giaddr: string | null;
+  circuit_id: string | null;
+  request: LinkedRequest | null;
+  causes: NakCause[];
+  server_message: string | null;
+  summary: string;
+}
+
+// Lot poussé par l'événement `dhcp://packet` ou renvoyé par `get_logs_since`
+export interface LogBatch {
+  session: number;
+  cursor: number;
+  capacity: number;
+  overwritten: number;
+  logs: DHCPLog[];
+}
+
+// Tampon circulaire de logs côté backend (`set_settings`)
+export interface LogSettings {
+  max_logs: number;
+  spill_to_disk: boolean;
+}
+
+export interface LogStatus {
+  count: number;
+  capacity: number;
+  overwritten: number;
+  spill_path: string | null;
+}
+
+export type LogSortField = 'timestamp' | 'packet_type' | 'source_ip' | 'destination_ip' | 'chaddr' | 'xid';
+
+export interface LogQuery {
+  message_types?: string[];
+  ip?: string;
+  mac?: string;
+  options?: number[];
+  from?: string;
+  to?: string;
+  hostname?: string;
+  text?: string;
+  sort_by?: LogSortField;
+  descending?: boolean;
+  offset?: number;
+  limit?: number;
+}
+
+export interface LogPage {
+  total: number;
+  logs: DHCPLog[];
+}
+
+export type SessionSource = 'live' | 'file';
+
+// Session de capture enregistrée dans la base SQLite (`list_sessions`)
+export interface SessionSummary {
+  id: number;
+  interface: string;
+  source: SessionSource;
+  filter: string | null;
+  started_at: string;
+  stopped_at: string | null;
+  packet_count: number;
+  size_bytes: number;
+}
+
+// Une limite à 0 est désactivée
+export interface RetentionPolicy {
+  max_age_days: number;
+  max_size_mb: number;
+}